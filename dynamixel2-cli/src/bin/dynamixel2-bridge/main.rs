@@ -0,0 +1,377 @@
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use dynamixel2::bus::{instruction_id, packet_id, InstructionPacket, StatusPacket};
+use dynamixel2::device::{Instruction, Instructions};
+use dynamixel2::{ReadError, SerialPort};
+
+#[path = "../dynamixel2/logging.rs"]
+mod logging;
+mod options;
+mod tcp;
+
+use options::Options;
+use tcp::TcpPort;
+
+type Client = dynamixel2::Client<serial2::SerialPort>;
+type Connection = dynamixel2::Device<TcpPort>;
+
+/// The timeout used when waiting for instructions from a TCP client.
+///
+/// The timeout only determines how often we wake up: partially received packets are kept between reads.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The minimum size of the read and write buffers.
+const MIN_BUFFER_SIZE: usize = 16;
+
+fn main() {
+	if let Err(()) = do_main(clap::Parser::parse()) {
+		std::process::exit(1);
+	}
+}
+
+fn do_main(options: Options) -> Result<(), ()> {
+	logging::init(module_path!(), options.verbose as i8);
+	if options.buffer_size < MIN_BUFFER_SIZE {
+		log::error!("Invalid buffer size: must be at least {} bytes", MIN_BUFFER_SIZE);
+		return Err(());
+	}
+
+	let client = Client::open_with_buffers(
+		&options.serial_port,
+		options.baud_rate,
+		vec![0; options.buffer_size],
+		vec![0; options.buffer_size],
+	)
+	.map_err(|e| log::error!("Failed to open serial port: {}: {}", options.serial_port.display(), e))?;
+	let client = Arc::new(Mutex::new(client));
+
+	let listener = TcpListener::bind(options.listen).map_err(|e| log::error!("Failed to listen on {}: {}", options.listen, e))?;
+	log::info!(
+		"Bridging serial port {} with baud rate {} on {}",
+		options.serial_port.display(),
+		options.baud_rate,
+		options.listen
+	);
+
+	for stream in listener.incoming() {
+		let stream = match stream {
+			Ok(stream) => stream,
+			Err(e) => {
+				log::error!("Failed to accept connection: {}", e);
+				continue;
+			},
+		};
+		let client = client.clone();
+		let buffer_size = options.buffer_size;
+		std::thread::spawn(move || handle_connection(stream, client, buffer_size));
+	}
+
+	Ok(())
+}
+
+/// Forward instructions from a single TCP client to the bus until the client disconnects.
+fn handle_connection<Port>(stream: TcpStream, client: Arc<Mutex<dynamixel2::Client<Port>>>, buffer_size: usize)
+where
+	Port: SerialPort,
+	Port::Error: std::fmt::Display,
+{
+	let peer = match stream.peer_addr() {
+		Ok(peer) => peer.to_string(),
+		Err(_) => String::from("unknown peer"),
+	};
+	log::info!("{}: Connected", peer);
+
+	if let Err(e) = stream.set_nodelay(true) {
+		log::warn!("{}: Failed to disable Nagle's algorithm: {}", peer, e);
+	}
+
+	let baud_rate = lock(&client).baud_rate();
+	let port = TcpPort::new(stream, baud_rate);
+	let mut connection = match Connection::with_buffers(port, vec![0; buffer_size], vec![0; buffer_size]) {
+		Ok(connection) => connection,
+		Err(e) => {
+			log::error!("{}: Failed to set up connection: {}", peer, e);
+			return;
+		},
+	};
+
+	loop {
+		let transaction = match connection.read_raw_instruction_timeout(POLL_INTERVAL) {
+			Ok(packet) => Transaction::from_packet(packet),
+			Err(ReadError::Io(e)) if TcpPort::is_timeout_error(&e) => continue,
+			Err(ReadError::Io(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+				log::info!("{}: Disconnected", peer);
+				return;
+			},
+			Err(ReadError::Io(e)) => {
+				log::error!("{}: Failed to read from connection: {}", peer, e);
+				return;
+			},
+			Err(e) => {
+				log::warn!("{}: Dropping invalid packet: {}", peer, e);
+				continue;
+			},
+		};
+
+		let transaction = match transaction {
+			Ok(transaction) => transaction,
+			Err(e) => {
+				log::warn!("{}: Dropping invalid instruction: {}", peer, e);
+				continue;
+			},
+		};
+
+		log::debug!(
+			"{}: Forwarding instruction 0x{:02X} for motor {}",
+			peer,
+			transaction.instruction_id,
+			transaction.packet_id
+		);
+		let mut client = lock(&client);
+		if let Err(e) = transaction.execute(&mut client, &mut connection) {
+			log::error!("{}: Failed to write to connection: {}", peer, e);
+			return;
+		}
+	}
+}
+
+/// Lock the client, even if another connection panicked while holding the lock.
+///
+/// The next instruction discards any pending data on the bus, so the client can not be left in an inconsistent state.
+fn lock<T>(client: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+	client.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// An instruction and the replies it is expected to trigger.
+struct Transaction {
+	packet_id: u8,
+	instruction_id: u8,
+	parameters: Vec<u8>,
+	replies: Replies,
+}
+
+/// The replies expected for an instruction.
+enum Replies {
+	/// The instruction does not trigger any reply.
+	None,
+
+	/// A broadcast ping, which is answered by an unknown number of motors.
+	Scan,
+
+	/// One reply from each motor in order, with the expected number of parameters.
+	Motors(Vec<(u8, u16)>),
+}
+
+impl Transaction {
+	/// Parse and validate an instruction packet received from a TCP client.
+	fn from_packet(packet: InstructionPacket) -> Result<Self, String> {
+		let instruction: Instruction<&[u8]> = packet.try_into().map_err(|e| format!("{}", e))?;
+		let unicast = |count| {
+			if instruction.id == packet_id::BROADCAST {
+				Replies::None
+			} else {
+				Replies::Motors(vec![(instruction.id, count)])
+			}
+		};
+
+		let replies = match instruction.instruction {
+			Instructions::Ping if instruction.id == packet_id::BROADCAST => Replies::Scan,
+			Instructions::Ping => unicast(3),
+			Instructions::Read { length, .. } => unicast(length),
+			Instructions::Write { .. }
+			| Instructions::RegWrite { .. }
+			| Instructions::Action
+			| Instructions::FactoryReset(_)
+			| Instructions::Reboot
+			| Instructions::Clear(_) => unicast(0),
+			Instructions::SyncRead { length, ids, .. } => Replies::Motors(ids.iter().map(|&id| (id, length)).collect()),
+			Instructions::BulkRead { parameters } => {
+				if parameters.len() % 5 != 0 {
+					return Err(format!(
+						"bulk read parameter count must be a multiple of 5, got {}",
+						parameters.len()
+					));
+				}
+				Replies::Motors(
+					parameters
+						.chunks_exact(5)
+						.map(|read| (read[0], u16::from_le_bytes([read[3], read[4]])))
+						.collect(),
+				)
+			},
+			Instructions::SyncWrite { .. } | Instructions::BulkWrite { .. } => Replies::None,
+			Instructions::StatusPacket { .. } => return Err(String::from("received a status packet instead of an instruction")),
+			// The replies of unknown instructions, such as fast sync read, can not be forwarded reliably.
+			Instructions::Unknown { instruction, .. } => return Err(format!("unsupported instruction 0x{:02X}", instruction)),
+		};
+
+		Ok(Self {
+			packet_id: packet.packet_id(),
+			instruction_id: packet.instruction_id(),
+			parameters: packet.parameters().to_vec(),
+			replies,
+		})
+	}
+
+	/// Forward the instruction to the bus and forward all replies back to the connection.
+	///
+	/// Errors on the bus are logged and are not returned:
+	/// the TCP client will notice missing replies the same way it would on a local bus.
+	/// Only errors writing to the connection are returned.
+	/// Status packets that report an error are forwarded as they are.
+	fn execute<Port>(&self, client: &mut dynamixel2::Client<Port>, connection: &mut Connection) -> Result<(), dynamixel2::WriteError<std::io::Error>>
+	where
+		Port: SerialPort,
+		Port::Error: std::fmt::Display,
+	{
+		if let Replies::Scan = self.replies {
+			return scan(client, connection);
+		}
+
+		let written = client.write_instruction(self.packet_id, self.instruction_id, self.parameters.len(), |buffer| {
+			buffer.copy_from_slice(&self.parameters);
+			Ok(())
+		});
+		if let Err(e) = written {
+			log::error!("Failed to forward instruction to the bus: {}", e);
+			return Ok(());
+		}
+
+		let Replies::Motors(motors) = &self.replies else {
+			return Ok(());
+		};
+
+		for &(motor_id, count) in motors {
+			match client.read_status_packet(count) {
+				Ok(status) => forward_status(connection, status)?,
+				Err(ReadError::Io(e)) if Port::is_timeout_error(&e) => {
+					log::debug!("No reply from motor {}", motor_id);
+				},
+				Err(e) => log::warn!("Dropping invalid reply for motor {}: {}", motor_id, e),
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Perform a broadcast ping and forward the reply of each motor.
+///
+/// Replies that report an error are forwarded as they are.
+fn scan<Port>(client: &mut dynamixel2::Client<Port>, connection: &mut Connection) -> Result<(), dynamixel2::WriteError<std::io::Error>>
+where
+	Port: SerialPort,
+	Port::Error: std::fmt::Display,
+{
+	let mut scan = match client.scan() {
+		Ok(scan) => scan,
+		Err(e) => {
+			log::error!("Failed to forward instruction to the bus: {}", e);
+			return Ok(());
+		},
+	};
+
+	while let Some(reply) = scan.scan_next_packet() {
+		match reply {
+			Ok(status) => forward_status(connection, status)?,
+			Err(e) => log::warn!("Dropping invalid ping reply: {}", e),
+		}
+	}
+	Ok(())
+}
+
+/// Forward a status packet from the bus to a TCP client.
+fn forward_status(connection: &mut Connection, status: StatusPacket) -> Result<(), dynamixel2::WriteError<std::io::Error>> {
+	debug_assert_eq!(status.instruction_id(), instruction_id::STATUS);
+	let parameters = status.parameters();
+	connection.write_status(status.packet_id(), status.error(), parameters.len(), |buffer| {
+		buffer.copy_from_slice(parameters);
+		Ok(())
+	})
+}
+
+// The mock bus of the library tests.
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../../../tests/common/mock/mod.rs"]
+mod mock;
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+	use dynamixel2::{MotorError, TransferError};
+	use std::collections::BTreeMap;
+
+	/// Run a test with a client that talks to the mock bus through the bridge.
+	fn run_bridge<F>(test: F)
+	where
+		F: FnOnce(&[u8], dynamixel2::Client<TcpPort>),
+	{
+		mock::run_mock(|ids, bus| {
+			let bus = Arc::new(Mutex::new(bus));
+			let baud_rate = lock(&bus).baud_rate();
+			let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+			let address = listener.local_addr().unwrap();
+			let bridge = std::thread::spawn(move || {
+				let (stream, _) = listener.accept().unwrap();
+				handle_connection(stream, bus, 256);
+			});
+
+			let stream = TcpStream::connect(address).unwrap();
+			let client = dynamixel2::Client::new(TcpPort::new(stream, baud_rate)).unwrap();
+			test(ids, client);
+			bridge.join().unwrap();
+		})
+	}
+
+	#[test]
+	fn forward_instructions() {
+		run_bridge(|ids, mut client| {
+			let_assert!(Ok(response) = client.ping(ids[0]));
+			assert!(response.motor_id == ids[0]);
+			assert!(response.data.model == 5130);
+
+			let_assert!(Ok(_) = client.write(ids[1], 116, &1234i32));
+			let_assert!(Ok(response) = client.read::<i32>(ids[1], 116));
+			assert!(response.data == 1234);
+
+			let responses: BTreeMap<_, _> = client.sync_read_all::<i32, _>(ids, 116).unwrap();
+			assert!(responses.keys().copied().eq(ids.iter().copied()));
+		})
+	}
+
+	#[test]
+	fn forward_error_status() {
+		run_bridge(|ids, mut client| {
+			// The mock rejects reads past the end of its control table with a Data Range error.
+			let result = client.read::<u32>(ids[0], 698);
+			let_assert!(Err(TransferError::ReadError(ReadError::MotorError(MotorError { raw: 0x07 }))) = result);
+		})
+	}
+
+	#[test]
+	fn reject_unknown_instruction() {
+		run_bridge(|ids, mut client| {
+			// A fast sync read is not forwarded, so nothing replies.
+			let_assert!(Ok(()) = client.write_instruction(packet_id::BROADCAST, 0x8A, 7, |buffer| {
+				buffer.copy_from_slice(&[0x84, 0x00, 0x04, 0x00, ids[0], ids[1], 0x00]);
+				Ok(())
+			}));
+			let_assert!(Err(ReadError::Io(e)) = client.read_status_response(0));
+			assert!(TcpPort::is_timeout_error(&e));
+
+			let_assert!(Ok(response) = client.ping(ids[0]));
+			assert!(response.motor_id == ids[0]);
+		})
+	}
+
+	#[test]
+	fn forward_scan() {
+		run_bridge(|ids, mut client| {
+			let motors: BTreeMap<_, _> = client.scan_all().unwrap();
+			assert!(motors.keys().copied().eq(ids.iter().copied()));
+		})
+	}
+}
@@ -0,0 +1,38 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+/// Expose a Dynamixel protocol 2.0 bus over TCP.
+///
+/// TCP clients send framed instruction packets and receive the status packets of the motors on the bus.
+/// Each instruction and all replies it triggers are handled as one transaction,
+/// so replies for different clients are never interleaved.
+#[derive(clap::Parser)]
+pub struct Options {
+	/// Print more verbose messages. Can be used multiple times.
+	#[clap(long, short)]
+	#[clap(action = clap::ArgAction::Count)]
+	pub verbose: u8,
+
+	/// The serial port to use.
+	#[clap(long, short)]
+	#[cfg_attr(target_os = "windows", clap(default_value = "COM1"))]
+	#[cfg_attr(not(target_os = "windows"), clap(default_value = "/dev/ttyUSB0"))]
+	pub serial_port: PathBuf,
+
+	/// The baud rate for the serial port.
+	#[clap(long, short)]
+	#[clap(default_value = "57600")]
+	pub baud_rate: u32,
+
+	/// The address to listen on for TCP connections.
+	#[clap(long, short)]
+	#[clap(default_value = "0.0.0.0:4400")]
+	pub listen: SocketAddr,
+
+	/// The size of the read and write buffers used for each connection and for the serial port.
+	///
+	/// The buffers must be large enough to hold the largest (byte-stuffed) packet sent over the bridge.
+	#[clap(long)]
+	#[clap(default_value = "1024")]
+	pub buffer_size: usize,
+}
@@ -0,0 +1,64 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+/// A TCP stream that can be used as the transport of a [`dynamixel2::Device`].
+///
+/// This allows the bridge to reuse the packet decoder of the library for the TCP side of the bridge.
+pub struct TcpPort {
+	stream: TcpStream,
+
+	/// The baud rate of the bridged bus.
+	///
+	/// A TCP stream has no baud rate, but the trait requires one.
+	baud_rate: u32,
+}
+
+impl TcpPort {
+	pub fn new(stream: TcpStream, baud_rate: u32) -> Self {
+		Self { stream, baud_rate }
+	}
+}
+
+impl dynamixel2::SerialPort for TcpPort {
+	type Error = std::io::Error;
+	type Instant = Instant;
+
+	fn baud_rate(&self) -> Result<u32, Self::Error> {
+		Ok(self.baud_rate)
+	}
+
+	fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), Self::Error> {
+		self.baud_rate = baud_rate;
+		Ok(())
+	}
+
+	fn discard_input_buffer(&mut self) -> Result<(), Self::Error> {
+		// Never discard data from a TCP client: it could be the start of the next instruction.
+		Ok(())
+	}
+
+	fn read(&mut self, buffer: &mut [u8], deadline: &Self::Instant) -> Result<usize, Self::Error> {
+		let timeout = deadline
+			.checked_duration_since(Instant::now())
+			.filter(|timeout| !timeout.is_zero())
+			.ok_or(std::io::ErrorKind::TimedOut)?;
+		self.stream.set_read_timeout(Some(timeout))?;
+		match self.stream.read(buffer)? {
+			0 => Err(std::io::ErrorKind::UnexpectedEof.into()),
+			n => Ok(n),
+		}
+	}
+
+	fn write_all(&mut self, buffer: &[u8]) -> Result<(), Self::Error> {
+		self.stream.write_all(buffer)
+	}
+
+	fn make_deadline(&self, timeout: Duration) -> Self::Instant {
+		Instant::now() + timeout
+	}
+
+	fn is_timeout_error(error: &Self::Error) -> bool {
+		matches!(error.kind(), std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock)
+	}
+}
@@ -0,0 +1,472 @@
+//! Interactive terminal dashboard to monitor and control all motors on the bus.
+
+use dynamixel2::control_table::{self, ControlTable, Register};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use crate::output::ErrorClass;
+use crate::Client;
+
+/// Scan the bus and run the dashboard until the user quits.
+pub fn run(client: &mut Client, interval: Duration) -> Result<(), ErrorClass> {
+	let mut dashboard = Dashboard::new(interval);
+	dashboard.scan(client);
+	if dashboard.motors.is_empty() {
+		log::warn!("No motors found, press 's' in the dashboard to scan again");
+	}
+
+	let mut terminal = ratatui::init();
+	let result = dashboard.run(client, &mut terminal);
+	ratatui::restore();
+	result.map_err(|e| {
+		log::error!("Failed to update terminal: {}", e);
+		ErrorClass::Other
+	})
+}
+
+/// The registers shown and controlled by the dashboard for a control table layout.
+///
+/// Not all model families have all registers, and some use different names for similar registers.
+struct Registers {
+	torque_enable: Register,
+	position: Option<&'static Register>,
+	velocity: Option<&'static Register>,
+	current: Option<&'static Register>,
+	voltage: Option<&'static Register>,
+	temperature: Option<&'static Register>,
+	hardware_error: Option<&'static Register>,
+	goal_position: Option<&'static Register>,
+	goal_velocity: Option<&'static Register>,
+	goal_current: Option<&'static Register>,
+}
+
+impl Registers {
+	fn new(control_table: &'static ControlTable) -> Self {
+		let find = |names: &[&str]| names.iter().find_map(|name| control_table.register(name));
+		Self {
+			torque_enable: control_table.torque_enable,
+			position: find(&["Present Position"]),
+			velocity: find(&["Present Velocity", "Present Speed"]),
+			current: find(&["Present Current", "Present Load"]),
+			voltage: find(&["Present Input Voltage", "Present Voltage"]),
+			temperature: find(&["Present Temperature"]),
+			hardware_error: find(&["Hardware Error Status"]),
+			goal_position: find(&["Goal Position"]),
+			goal_velocity: find(&["Goal Velocity", "Moving Speed"]),
+			goal_current: find(&["Goal Current"]),
+		}
+	}
+
+	/// Get the smallest address range that contains all monitored registers.
+	fn read_range(&self) -> Range<u16> {
+		let monitored = [
+			self.position,
+			self.velocity,
+			self.current,
+			self.voltage,
+			self.temperature,
+			self.hardware_error,
+		];
+		monitored
+			.into_iter()
+			.flatten()
+			.fold(self.torque_enable.range(), |range, register| {
+				range.start.min(register.address)..range.end.max(register.range().end)
+			})
+	}
+
+	fn goal(&self, goal: Goal) -> Option<&'static Register> {
+		match goal {
+			Goal::Position => self.goal_position,
+			Goal::Velocity => self.goal_velocity,
+			Goal::Current => self.goal_current,
+		}
+	}
+}
+
+/// The last values read from a motor.
+#[derive(Default)]
+struct Values {
+	torque_enable: bool,
+	position: Option<i64>,
+	velocity: Option<i64>,
+	current: Option<i64>,
+	voltage: Option<i64>,
+	temperature: Option<i64>,
+	hardware_error: Option<i64>,
+}
+
+struct Motor {
+	motor_id: u8,
+	model: u16,
+	control_table: &'static ControlTable,
+	registers: Registers,
+	values: Option<Values>,
+}
+
+/// Motors with the same control table layout, polled with a single sync read.
+struct PollGroup {
+	motor_ids: Vec<u8>,
+	range: Range<u16>,
+}
+
+#[derive(Default)]
+struct Statistics {
+	cycles: u64,
+	replies: u64,
+	failed_replies: u64,
+	failed_instructions: u64,
+	last_cycle: Duration,
+	started: Option<Instant>,
+}
+
+#[derive(Copy, Clone)]
+enum Goal {
+	Position,
+	Velocity,
+	Current,
+}
+
+impl Goal {
+	fn name(self) -> &'static str {
+		match self {
+			Self::Position => "goal position",
+			Self::Velocity => "goal velocity",
+			Self::Current => "goal current",
+		}
+	}
+}
+
+enum Mode {
+	Normal,
+	Edit { goal: Goal, input: String },
+}
+
+struct Dashboard {
+	interval: Duration,
+	motors: Vec<Motor>,
+	groups: Vec<PollGroup>,
+	table_state: TableState,
+	statistics: Statistics,
+	mode: Mode,
+	message: String,
+	quit: bool,
+}
+
+impl Dashboard {
+	fn new(interval: Duration) -> Self {
+		Self {
+			interval,
+			motors: Vec::new(),
+			groups: Vec::new(),
+			table_state: TableState::default(),
+			statistics: Statistics::default(),
+			mode: Mode::Normal,
+			message: String::new(),
+			quit: false,
+		}
+	}
+
+	/// Scan the bus for motors and prepare the sync reads to poll them.
+	fn scan(&mut self, client: &mut Client) {
+		self.motors.clear();
+		self.groups.clear();
+		match client.scan() {
+			Ok(scan) => {
+				for response in scan {
+					match response {
+						Ok(response) => {
+							let control_table = control_table::control_table_or_default(response.data.model);
+							self.motors.push(Motor {
+								motor_id: response.motor_id,
+								model: response.data.model,
+								control_table,
+								registers: Registers::new(control_table),
+								values: None,
+							});
+						},
+						Err(e) => self.message = format!("Error during scan: {}", e),
+					}
+				}
+			},
+			Err(e) => self.message = format!("Failed to scan the bus: {}", e),
+		}
+		self.motors.sort_by_key(|motor| motor.motor_id);
+
+		for motor in &self.motors {
+			let range = motor.registers.read_range();
+			match self.groups.iter_mut().find(|group| group.range == range) {
+				Some(group) => group.motor_ids.push(motor.motor_id),
+				None => self.groups.push(PollGroup {
+					motor_ids: vec![motor.motor_id],
+					range,
+				}),
+			}
+		}
+
+		self.table_state.select((!self.motors.is_empty()).then_some(0));
+		self.statistics = Statistics::default();
+		if self.message.is_empty() {
+			self.message = format!("Found {} motors", self.motors.len());
+		}
+	}
+
+	fn run(&mut self, client: &mut Client, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+		while !self.quit {
+			let start = Instant::now();
+			self.poll(client);
+			self.statistics.last_cycle = start.elapsed();
+			terminal.draw(|frame| self.draw(frame))?;
+
+			let deadline = start + self.interval;
+			loop {
+				let timeout = deadline.saturating_duration_since(Instant::now());
+				if !event::poll(timeout)? {
+					break;
+				}
+				if let Event::Key(key) = event::read()? {
+					if key.kind == KeyEventKind::Press {
+						self.handle_key(client, key.code);
+						terminal.draw(|frame| self.draw(frame))?;
+					}
+				}
+				if self.quit {
+					break;
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Read the monitored registers of all motors.
+	fn poll(&mut self, client: &mut Client) {
+		self.statistics.started.get_or_insert_with(Instant::now);
+		self.statistics.cycles += 1;
+		for group in &self.groups {
+			let count = group.range.end - group.range.start;
+			let responses = match client.sync_read_bytes::<Vec<u8>>(&group.motor_ids, group.range.start, count) {
+				Ok(responses) => responses,
+				Err(e) => {
+					self.statistics.failed_instructions += 1;
+					self.message = format!("Sync read failed: {}", e);
+					continue;
+				},
+			};
+			for (response, motor_id) in responses.zip(&group.motor_ids) {
+				let Some(motor) = self.motors.iter_mut().find(|motor| motor.motor_id == *motor_id) else {
+					continue;
+				};
+				match response {
+					Ok(response) => {
+						self.statistics.replies += 1;
+						motor.values = Some(decode_values(&motor.registers, group.range.start, &response.data));
+					},
+					Err(e) => {
+						self.statistics.failed_replies += 1;
+						self.message = format!("Failed to read motor {}: {}", motor_id, e);
+						motor.values = None;
+					},
+				}
+			}
+		}
+	}
+
+	fn handle_key(&mut self, client: &mut Client, key: KeyCode) {
+		match &mut self.mode {
+			Mode::Normal => match key {
+				KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+				KeyCode::Up => self.table_state.select_previous(),
+				KeyCode::Down => self.table_state.select_next(),
+				KeyCode::Char('s') => self.scan(client),
+				KeyCode::Char('t') => self.toggle_torque(client),
+				KeyCode::Char('p') => self.start_edit(Goal::Position),
+				KeyCode::Char('v') => self.start_edit(Goal::Velocity),
+				KeyCode::Char('c') => self.start_edit(Goal::Current),
+				_ => (),
+			},
+			Mode::Edit { goal, input } => match key {
+				KeyCode::Esc => self.mode = Mode::Normal,
+				KeyCode::Backspace => {
+					input.pop();
+				},
+				KeyCode::Char(c @ ('0'..='9' | '-')) => input.push(c),
+				KeyCode::Enter => {
+					let goal = *goal;
+					let input = std::mem::take(input);
+					self.mode = Mode::Normal;
+					self.write_goal(client, goal, &input);
+				},
+				_ => (),
+			},
+		}
+	}
+
+	fn selected(&self) -> Option<&Motor> {
+		self.motors.get(self.table_state.selected()?)
+	}
+
+	fn toggle_torque(&mut self, client: &mut Client) {
+		let Some(motor) = self.selected() else {
+			return;
+		};
+		let Some(values) = &motor.values else {
+			self.message = format!("Torque state of motor {} is unknown", motor.motor_id);
+			return;
+		};
+		let motor_id = motor.motor_id;
+		let enable = !values.torque_enable;
+		self.message = match client.write(motor_id, motor.registers.torque_enable.address, &u8::from(enable)) {
+			Ok(_) if enable => format!("Enabled torque of motor {}", motor_id),
+			Ok(_) => format!("Disabled torque of motor {}", motor_id),
+			Err(e) => format!("Failed to write torque enable of motor {}: {}", motor_id, e),
+		};
+	}
+
+	fn start_edit(&mut self, goal: Goal) {
+		let Some(motor) = self.selected() else {
+			return;
+		};
+		if motor.registers.goal(goal).is_none() {
+			self.message = format!("Motor {} has no {} register", motor.motor_id, goal.name());
+			return;
+		}
+		self.mode = Mode::Edit {
+			goal,
+			input: String::new(),
+		};
+	}
+
+	fn write_goal(&mut self, client: &mut Client, goal: Goal, input: &str) {
+		let Some(motor) = self.selected() else {
+			return;
+		};
+		let Some(register) = motor.registers.goal(goal) else {
+			return;
+		};
+		let motor_id = motor.motor_id;
+		let Some(data) = input.parse().ok().and_then(|value| register.encode_value(value)) else {
+			self.message = format!("Invalid value for {}: {:?}", goal.name(), input);
+			return;
+		};
+		self.message = match client.write_bytes(motor_id, register.address, &data[..usize::from(register.size)]) {
+			Ok(_) => format!("Set {} of motor {} to {}", goal.name(), motor_id, input),
+			Err(e) => format!("Failed to write {} of motor {}: {}", goal.name(), motor_id, e),
+		};
+	}
+
+	fn draw(&mut self, frame: &mut Frame) {
+		let [motors_area, bottom_area, message_area] =
+			Layout::vertical([Constraint::Min(5), Constraint::Length(8), Constraint::Length(1)]).areas(frame.area());
+		let [statistics_area, help_area] = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(bottom_area);
+
+		let header = Row::new([
+			"ID", "Model", "Torque", "Position", "Velocity", "Current", "Voltage", "Temp", "HW error",
+		])
+		.style(Style::new().bold());
+		let rows = self.motors.iter().map(|motor| {
+			let model = match control_table::model(motor.model) {
+				Some(model) => model.name.to_string(),
+				None => format!("{} ({})", motor.model, motor.control_table.name),
+			};
+			let Some(values) = &motor.values else {
+				return Row::new([motor.motor_id.to_string(), model, "-".into()]).dim();
+			};
+			let hardware_error = values.hardware_error.unwrap_or_default();
+			let row = Row::new([
+				motor.motor_id.to_string(),
+				model,
+				if values.torque_enable { "on" } else { "off" }.into(),
+				format_value(values.position),
+				format_value(values.velocity),
+				format_value(values.current),
+				values.voltage.map_or("-".into(), |voltage| format!("{:.1} V", voltage as f64 / 10.0)),
+				values.temperature.map_or("-".into(), |temperature| format!("{} °C", temperature)),
+				values.hardware_error.map_or("-".into(), |error| format!("0x{:02X}", error)),
+			]);
+			if hardware_error != 0 {
+				row.red()
+			} else {
+				row
+			}
+		});
+		let widths = [
+			Constraint::Length(4),
+			Constraint::Length(16),
+			Constraint::Length(7),
+			Constraint::Length(11),
+			Constraint::Length(11),
+			Constraint::Length(9),
+			Constraint::Length(9),
+			Constraint::Length(7),
+			Constraint::Length(9),
+		];
+		let table = Table::new(rows, widths)
+			.header(header)
+			.block(Block::bordered().title("Motors"))
+			.row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+		frame.render_stateful_widget(table, motors_area, &mut self.table_state);
+
+		let statistics = &self.statistics;
+		let elapsed = statistics.started.map_or(Duration::ZERO, |started| started.elapsed());
+		let rate = statistics.cycles as f64 / elapsed.as_secs_f64().max(1e-3);
+		let statistics_lines = vec![
+			Line::from(format!("Motors: {} in {} sync reads", self.motors.len(), self.groups.len())),
+			Line::from(format!("Poll cycles: {} ({:.1} Hz)", statistics.cycles, rate)),
+			Line::from(format!("Last cycle: {:?}", statistics.last_cycle)),
+			Line::from(format!("Replies: {}", statistics.replies)),
+			Line::from(format!("Failed replies: {}", statistics.failed_replies)),
+			Line::from(format!("Failed instructions: {}", statistics.failed_instructions)),
+		];
+		frame.render_widget(
+			Paragraph::new(statistics_lines).block(Block::bordered().title("Bus statistics")),
+			statistics_area,
+		);
+
+		let help_lines = match &self.mode {
+			Mode::Normal => vec![
+				Line::from("↑/↓: select motor"),
+				Line::from("t: toggle torque"),
+				Line::from("p/v/c: edit goal position/velocity/current"),
+				Line::from("s: scan the bus again"),
+				Line::from("q: quit"),
+			],
+			Mode::Edit { goal, input } => vec![
+				Line::from(format!("New {}: {}_", goal.name(), input)),
+				Line::from(""),
+				Line::from("Enter: write value"),
+				Line::from("Esc: cancel"),
+			],
+		};
+		frame.render_widget(Paragraph::new(help_lines).block(Block::bordered().title("Keys")), help_area);
+		frame.render_widget(Paragraph::new(self.message.as_str()), message_area);
+	}
+}
+
+/// Decode the monitored registers from the data of a sync read starting at `address`.
+fn decode_values(registers: &Registers, address: u16, data: &[u8]) -> Values {
+	let get = |register: Option<&Register>, signed: bool| {
+		let register = register?;
+		let start = usize::from(register.address.checked_sub(address)?);
+		let bytes = data.get(start..start + usize::from(register.size))?;
+		register.decode_value(bytes, signed)
+	};
+	Values {
+		torque_enable: get(Some(&registers.torque_enable), false).is_some_and(|value| value != 0),
+		position: get(registers.position, true),
+		velocity: get(registers.velocity, true),
+		current: get(registers.current, true),
+		voltage: get(registers.voltage, false),
+		temperature: get(registers.temperature, false),
+		hardware_error: get(registers.hardware_error, false),
+	}
+}
+
+fn format_value(value: Option<i64>) -> String {
+	value.map_or("-".into(), |value| value.to_string())
+}
@@ -1,112 +1,160 @@
 use std::path::Path;
 use std::time::{Duration, Instant};
 
+mod dashboard;
 mod logging;
 mod options;
+mod output;
+mod script;
+mod snapshot;
 
-use options::{Command, MotorId, Options};
+use options::{ClearTarget, Command, MotorId, Options};
+use output::{ErrorClass, Output};
+use serde_json::{json, Value};
 
 type Client = dynamixel2::Client<serial2::SerialPort>;
 
 fn main() {
-	if let Err(()) = do_main(clap::Parser::parse()) {
-		std::process::exit(1);
+	if let Err(class) = do_main(clap::Parser::parse()) {
+		std::process::exit(class.exit_code());
 	}
 }
 
-fn do_main(options: Options) -> Result<(), ()> {
+fn do_main(options: Options) -> Result<(), ErrorClass> {
 	logging::init(module_path!(), options.verbose as i8);
+	let output = Output::new(options.format);
 	match &options.command {
+		Command::Dashboard { interval } => {
+			// Use larger buffers than the default, since the dashboard reads many registers from many motors at once.
+			let mut client = Client::open_with_buffers(&options.serial_port, options.baud_rate, vec![0; 1024], vec![0; 1024])
+				.map_err(|e| open_error(&options, &output, e))?;
+			dashboard::run(&mut client, Duration::from_millis(*interval))?;
+		},
+		Command::ShellCompletion { shell, output: file } => {
+			write_shell_completion(&output, *shell, file.as_deref())?;
+		},
+		Command::Run { script } => {
+			let script = script::load(&output, script)?;
+			let mut client = open_client(&options, &output)?;
+			script::run(&mut client, &output, &script)?;
+		},
+		command => {
+			let mut client = open_client(&options, &output)?;
+			run_command(&mut client, &output, command)?;
+		},
+	}
+
+	Ok(())
+}
+
+/// Run a command that communicates with the motors, using an already opened client.
+fn run_command(client: &mut Client, output: &Output, command: &Command) -> Result<(), ErrorClass> {
+	match command {
 		Command::Ping { motor_id } => {
-			let mut client = open_client(&options)?;
 			match motor_id {
 				&MotorId::Id(motor_id) => {
 					log::debug!("Sending ping command to motor {}", motor_id);
 					let start = Instant::now();
-					let response = client.ping(motor_id).map_err(|e| log::error!("Command failed: {}", e))?;
-					if response.alert {
-						log::warn!("Alert bit set in response from motor!")
-					}
-					log::info!("{:?}: {:?}", start.elapsed(), response.data);
+					let response = client.ping(motor_id).map_err(|e| output.transfer_error(Some(motor_id), e))?;
+					output.response(
+						response.motor_id,
+						response.alert,
+						start.elapsed(),
+						ping_json(&response.data),
+						format_args!("{:?}", response.data),
+					);
 				},
 				MotorId::Broadcast => {
 					let start = Instant::now();
-					let scan = client.scan().map_err(|e| log::error!("Command failed: {}", e))?;
+					let scan = client.scan().map_err(|e| output.write_error(e))?;
+					let mut result = Ok(());
 					for s in scan {
 						match s {
-							Ok(r) => log_ping_response(&r, start.elapsed()),
-							Err(e) => log::error!("Error pinging motor: {}", e),
+							Ok(r) => log_ping_response(output, &r, start.elapsed()),
+							Err(e) => {
+								let class = ErrorClass::from_read_error(&e);
+								result = result.and(Err(output.error(class, None, format_args!("Error pinging motor: {}", e))));
+							},
 						}
 					}
+					result?;
 				},
 			}
 		},
 		Command::Reboot { motor_id } => {
-			let mut client = open_client(&options)?;
 			log::debug!("Sending reboot command with motor ID {}", motor_id.raw());
 			let start = Instant::now();
-			let response = client.reboot(motor_id.raw()).map_err(|e| log::error!("Command failed: {}", e))?;
-			if response.alert {
-				log::warn!("Alert bit set in response from motor!")
-			}
-			log::info!("{:?}: Ok", start.elapsed());
+			let response = client
+				.reboot(motor_id.raw())
+				.map_err(|e| output.transfer_error(Some(motor_id.raw()), e))?;
+			output.response(response.motor_id, response.alert, start.elapsed(), Value::Null, "Ok");
 		},
 		Command::Read8 { motor_id, address } => {
-			let mut client = open_client(&options)?;
-			log::debug!("Reading an 8-bit value from motor {} at address {}", motor_id.raw(), address);
+			let motor_id = motor_id.assume_unicast(output)?;
+			log::debug!("Reading an 8-bit value from motor {} at address {}", motor_id, address);
 			let start = Instant::now();
 			let response = client
-				.read::<u8>(motor_id.assume_unicast()?, *address)
-				.map_err(|e| log::error!("Command failed: {}", e))?;
-			if response.alert {
-				log::warn!("Alert bit set in response from motor!")
-			}
-			log::info!("{:?}: {:?} (0x{:02X})", start.elapsed(), response.data, response.data);
+				.read::<u8>(motor_id, *address)
+				.map_err(|e| output.transfer_error(Some(motor_id), e))?;
+			output.response(
+				response.motor_id,
+				response.alert,
+				start.elapsed(),
+				response.data.into(),
+				format_args!("{:?} (0x{:02X})", response.data, response.data),
+			);
 		},
 		Command::Read16 { motor_id, address } => {
-			let mut client = open_client(&options)?;
-			log::debug!("Reading a 16-bit value from motor {} at address {}", motor_id.raw(), address);
+			let motor_id = motor_id.assume_unicast(output)?;
+			log::debug!("Reading a 16-bit value from motor {} at address {}", motor_id, address);
 			let start = Instant::now();
 			let response = client
-				.read::<u16>(motor_id.assume_unicast()?, *address)
-				.map_err(|e| log::error!("Command failed: {}", e))?;
-			if response.alert {
-				log::warn!("Alert bit set in response from motor!")
-			}
-			log::info!("{:?}: {:?} (0x{:04X})", start.elapsed(), response.data, response.data);
+				.read::<u16>(motor_id, *address)
+				.map_err(|e| output.transfer_error(Some(motor_id), e))?;
+			output.response(
+				response.motor_id,
+				response.alert,
+				start.elapsed(),
+				response.data.into(),
+				format_args!("{:?} (0x{:04X})", response.data, response.data),
+			);
 		},
 		Command::Read32 { motor_id, address } => {
-			let mut client = open_client(&options)?;
-			log::debug!("Reading a 32-bit value from motor {} at address {}", motor_id.raw(), address);
+			let motor_id = motor_id.assume_unicast(output)?;
+			log::debug!("Reading a 32-bit value from motor {} at address {}", motor_id, address);
 			let start = Instant::now();
 			let response = client
-				.read::<u32>(motor_id.assume_unicast()?, *address)
-				.map_err(|e| log::error!("Command failed: {}", e))?;
-			if response.alert {
-				log::warn!("Alert bit set in response from motor!")
-			}
-			log::info!(
-				"{:?}: {:?} (0x{:04X} {:04X})",
+				.read::<u32>(motor_id, *address)
+				.map_err(|e| output.transfer_error(Some(motor_id), e))?;
+			output.response(
+				response.motor_id,
+				response.alert,
 				start.elapsed(),
-				response.data,
-				(response.data >> 16) & 0xFFFF,
-				response.data & 0xFFFF
+				response.data.into(),
+				format_args!(
+					"{:?} (0x{:04X} {:04X})",
+					response.data,
+					(response.data >> 16) & 0xFFFF,
+					response.data & 0xFFFF
+				),
 			);
 		},
 		Command::Read { motor_id, address, count } => {
-			let mut client = open_client(&options)?;
-			log::debug!("Reading a {} bytes from motor {} at address {}", count, motor_id.raw(), address);
+			let motor_id = motor_id.assume_unicast(output)?;
+			log::debug!("Reading a {} bytes from motor {} at address {}", count, motor_id, address);
 			let start = Instant::now();
 			let response = client
-				.read_bytes::<&[u8]>(motor_id.assume_unicast()?, *address, *count)
-				.map_err(|e| log::error!("Command failed: {}", e))?;
-			if response.alert {
-				log::warn!("Alert bit set in response from motor!")
-			}
-			log::info!("{:?}: {:?}", start.elapsed(), response.data,);
+				.read_bytes::<Vec<u8>>(motor_id, *address, *count)
+				.map_err(|e| output.transfer_error(Some(motor_id), e))?;
+			output.response(
+				response.motor_id,
+				response.alert,
+				start.elapsed(),
+				response.data.as_slice().into(),
+				format_args!("{:?}", response.data),
+			);
 		},
 		Command::Write8 { motor_id, address, value } => {
-			let mut client = open_client(&options)?;
 			log::debug!(
 				"Writing 8-bit value {} (0x{:02X}) to motor {} at address {}",
 				value,
@@ -117,14 +165,10 @@ fn do_main(options: Options) -> Result<(), ()> {
 			let start = Instant::now();
 			let response = client
 				.write(motor_id.raw(), *address, value)
-				.map_err(|e| log::error!("Write failed: {}", e))?;
-			if response.alert {
-				log::warn!("Alert bit set in response from motor!")
-			}
-			log::info!("{:?}: Ok", start.elapsed());
+				.map_err(|e| output.transfer_error(Some(motor_id.raw()), e))?;
+			output.response(response.motor_id, response.alert, start.elapsed(), Value::Null, "Ok");
 		},
 		Command::Write16 { motor_id, address, value } => {
-			let mut client = open_client(&options)?;
 			log::debug!(
 				"Writing 16-bit value {} (0x{:04X}) to motor {} at address {}",
 				value,
@@ -135,14 +179,10 @@ fn do_main(options: Options) -> Result<(), ()> {
 			let start = Instant::now();
 			let response = client
 				.write(motor_id.raw(), *address, value)
-				.map_err(|e| log::error!("Command failed: {}", e))?;
-			if response.alert {
-				log::warn!("Alert bit set in response from motor!")
-			}
-			log::info!("{:?}: Ok", start.elapsed());
+				.map_err(|e| output.transfer_error(Some(motor_id.raw()), e))?;
+			output.response(response.motor_id, response.alert, start.elapsed(), Value::Null, "Ok");
 		},
 		Command::Write32 { motor_id, address, value } => {
-			let mut client = open_client(&options)?;
 			log::debug!(
 				"Writing 32-bit value {} (0x{:04X} {:04X}) to motor {} at address {}",
 				value,
@@ -154,35 +194,137 @@ fn do_main(options: Options) -> Result<(), ()> {
 			let start = Instant::now();
 			let response = client
 				.write(motor_id.raw(), *address, value)
-				.map_err(|e| log::error!("Command failed: {}", e))?;
-			if response.alert {
-				log::warn!("Alert bit set in response from motor!")
-			}
-			log::info!("{:?}: Ok", start.elapsed());
+				.map_err(|e| output.transfer_error(Some(motor_id.raw()), e))?;
+			output.response(response.motor_id, response.alert, start.elapsed(), Value::Null, "Ok");
 		},
 		Command::Write { motor_id, address, data } => {
-			let mut client = open_client(&options)?;
 			log::debug!("Writing {} bytes to motor {} at address {}", data.len(), motor_id.raw(), address);
 			let start = Instant::now();
 			let response = client
 				.write_bytes(motor_id.raw(), *address, data)
-				.map_err(|e| log::error!("Command failed: {}", e))?;
-			if response.alert {
-				log::warn!("Alert bit set in response from motor!")
+				.map_err(|e| output.transfer_error(Some(motor_id.raw()), e))?;
+			output.response(response.motor_id, response.alert, start.elapsed(), Value::Null, "Ok");
+		},
+		Command::FactoryReset { motor_id, keep } => {
+			log::debug!("Sending factory reset command with motor ID {}", motor_id.raw());
+			let start = Instant::now();
+			let response = client
+				.factory_reset(motor_id.raw(), keep.reset_kind())
+				.map_err(|e| output.transfer_error(Some(motor_id.raw()), e))?;
+			output.response(response.motor_id, response.alert, start.elapsed(), Value::Null, "Ok");
+		},
+		Command::Clear { motor_id, target } => {
+			let start = Instant::now();
+			let response = match target {
+				ClearTarget::RevolutionCounter => {
+					log::debug!("Clearing the revolution counter of motor {}", motor_id.raw());
+					client.clear_revolution_counter(motor_id.raw())
+				},
+				ClearTarget::Error => {
+					log::debug!("Clearing the error state of motor {}", motor_id.raw());
+					client.clear_error(motor_id.raw())
+				},
 			}
-			log::info!("{:?}: Ok", start.elapsed());
+			.map_err(|e| output.transfer_error(Some(motor_id.raw()), e))?;
+			output.response(response.motor_id, response.alert, start.elapsed(), Value::Null, "Ok");
 		},
-		Command::ShellCompletion { shell, output } => {
-			write_shell_completion(*shell, output.as_deref())?;
+		Command::RegWrite { motor_id, address, data } => {
+			log::debug!("Registering a write of {} bytes to motor {} at address {}", data.len(), motor_id.raw(), address);
+			let start = Instant::now();
+			let response = client
+				.reg_write_bytes(motor_id.raw(), *address, data)
+				.map_err(|e| output.transfer_error(Some(motor_id.raw()), e))?;
+			output.response(response.motor_id, response.alert, start.elapsed(), Value::Null, "Ok");
+		},
+		Command::Action { motor_id } => {
+			log::debug!("Sending action command with motor ID {}", motor_id.raw());
+			let start = Instant::now();
+			let response = client
+				.action(motor_id.raw())
+				.map_err(|e| output.transfer_error(Some(motor_id.raw()), e))?;
+			output.response(response.motor_id, response.alert, start.elapsed(), Value::Null, "Ok");
+		},
+		Command::SyncRead { motor_ids, address, count } => {
+			log::debug!("Reading {} bytes from motors {:?} at address {}", count, motor_ids.0, address);
+			let start = Instant::now();
+			let responses = client
+				.sync_read_bytes::<Vec<u8>>(&motor_ids.0, *address, *count)
+				.map_err(|e| output.write_error(e))?;
+			let mut result = Ok(());
+			for (response, motor_id) in responses.zip(&motor_ids.0) {
+				match response {
+					Ok(response) => log_read_response(output, &response, start.elapsed()),
+					Err(e) => result = result.and(Err(output.read_error(*motor_id, e))),
+				}
+			}
+			result?;
+		},
+		Command::SyncWrite { address, data } => {
+			check_unique_ids(output, data.iter().map(|write| write.motor_id))?;
+			let count = data[0].data.len();
+			if let Some(other) = data.iter().find(|write| write.data.len() != count) {
+				return Err(output.error(
+					ErrorClass::Usage,
+					None,
+					format_args!(
+						"All motors must receive the same number of bytes, but motor {} has {} bytes and motor {} has {} bytes",
+						data[0].motor_id,
+						count,
+						other.motor_id,
+						other.data.len()
+					),
+				));
+			}
+			log::debug!("Writing {} bytes to {} motors at address {}", count, data.len(), address);
+			let start = Instant::now();
+			let data = data.iter().map(|write| dynamixel2::client::SyncWriteData {
+				motor_id: write.motor_id,
+				data: write.data.as_slice(),
+			});
+			client
+				.sync_write_bytes(*address, count as u16, data)
+				.map_err(|e| output.write_error(e))?;
+			output.done(start.elapsed());
+		},
+		Command::BulkRead { reads } => {
+			check_unique_ids(output, reads.iter().map(|read| read.0.motor_id))?;
+			let reads: Vec<_> = reads.iter().map(|read| read.0).collect();
+			log::debug!("Reading from {} motors", reads.len());
+			let start = Instant::now();
+			let responses = client.bulk_read_bytes::<Vec<u8>>(&reads).map_err(|e| output.write_error(e))?;
+			let mut result = Ok(());
+			for (response, read) in responses.zip(&reads) {
+				match response {
+					Ok(response) => log_read_response(output, &response, start.elapsed()),
+					Err(e) => result = result.and(Err(output.read_error(read.motor_id, e))),
+				}
+			}
+			result?;
+		},
+		Command::BulkWrite { writes } => {
+			check_unique_ids(output, writes.iter().map(|write| write.0.motor_id))?;
+			let writes: Vec<_> = writes.iter().map(|write| write.0.clone()).collect();
+			log::debug!("Writing to {} motors", writes.len());
+			let start = Instant::now();
+			client.bulk_write(&writes).map_err(|e| output.write_error(e))?;
+			output.done(start.elapsed());
+		},
+		Command::Dump { motor_id, output: file } => {
+			snapshot::dump(client, output, motor_id.assume_unicast(output)?, file.as_deref())?;
+		},
+		Command::Restore { motor_id, file, dry_run } => {
+			snapshot::restore(client, output, motor_id.assume_unicast(output)?, file, *dry_run)?;
+		},
+		Command::Dashboard { .. } | Command::Run { .. } | Command::ShellCompletion { .. } => {
+			return Err(output.error(ErrorClass::Usage, None, "This command can not be used here."));
 		},
 	}
 
 	Ok(())
 }
 
-fn open_client(options: &Options) -> Result<Client, ()> {
-	let client = Client::open(&options.serial_port, options.baud_rate)
-		.map_err(|e| log::error!("Failed to open serial port: {}: {}", options.serial_port.display(), e))?;
+fn open_client(options: &Options, output: &Output) -> Result<Client, ErrorClass> {
+	let client = Client::open(&options.serial_port, options.baud_rate).map_err(|e| open_error(options, output, e))?;
 	log::debug!(
 		"Using serial port {} with baud rate {}",
 		options.serial_port.display(),
@@ -191,7 +333,27 @@ fn open_client(options: &Options) -> Result<Client, ()> {
 	Ok(client)
 }
 
-fn log_ping_response(response: &dynamixel2::Response<dynamixel2::client::Ping>, elapsed: Duration) {
+fn open_error(options: &Options, output: &Output, error: std::io::Error) -> ErrorClass {
+	output.error(
+		ErrorClass::SerialPort,
+		None,
+		format_args!("Failed to open serial port: {}: {}", options.serial_port.display(), error),
+	)
+}
+
+fn ping_json(ping: &dynamixel2::client::Ping) -> Value {
+	json!({
+		"model": ping.model,
+		"model_name": dynamixel2::control_table::model(ping.model).map(|model| model.name),
+		"firmware": ping.firmware,
+	})
+}
+
+fn log_ping_response(output: &Output, response: &dynamixel2::Response<dynamixel2::client::Ping>, elapsed: Duration) {
+	if output.is_json() {
+		output.response(response.motor_id, response.alert, elapsed, ping_json(&response.data), "");
+		return;
+	}
 	log::info!("Motor ID: {}", response.motor_id);
 	log::info!(" ├─ Response time: {:?}", elapsed);
 	if response.alert {
@@ -201,7 +363,32 @@ fn log_ping_response(response: &dynamixel2::Response<dynamixel2::client::Ping>,
 	log::info!(" └─ Firmware: {}", response.data.firmware);
 }
 
-fn write_shell_completion(shell: clap_complete::Shell, path: Option<&Path>) -> Result<(), ()> {
+/// Check that no motor ID is used more than once, as required by the sync and bulk instructions.
+fn check_unique_ids(output: &Output, motor_ids: impl Iterator<Item = u8>) -> Result<(), ErrorClass> {
+	let mut seen = std::collections::BTreeSet::new();
+	for motor_id in motor_ids {
+		if !seen.insert(motor_id) {
+			return Err(output.error(
+				ErrorClass::Usage,
+				Some(motor_id),
+				format_args!("Motor ID {} is used more than once", motor_id),
+			));
+		}
+	}
+	Ok(())
+}
+
+fn log_read_response(output: &Output, response: &dynamixel2::Response<Vec<u8>>, elapsed: Duration) {
+	output.response(
+		response.motor_id,
+		response.alert,
+		elapsed,
+		response.data.as_slice().into(),
+		format_args!("motor {}: {:?}", response.motor_id, response.data),
+	);
+}
+
+fn write_shell_completion(output: &Output, shell: clap_complete::Shell, path: Option<&Path>) -> Result<(), ErrorClass> {
 	use clap::CommandFactory;
 	use std::io::Write;
 
@@ -220,13 +407,13 @@ fn write_shell_completion(shell: clap_complete::Shell, path: Option<&Path>) -> R
 		stdout
 			.lock()
 			.write_all(&buffer)
-			.map_err(|e| log::error!("Failed to write to stdout: {}", e))?;
+			.map_err(|e| output.error(ErrorClass::File, None, format_args!("Failed to write to stdout: {}", e)))?;
 	} else {
 		log::debug!("Writing shell completion for {} to {}", shell, path.display());
-		let mut output = std::fs::File::create(path).map_err(|e| log::error!("Failed to create {}: {}", path.display(), e))?;
-		output
-			.write_all(&buffer)
-			.map_err(|e| log::error!("Failed to write to {}: {}", path.display(), e))?;
+		let mut file = std::fs::File::create(path)
+			.map_err(|e| output.error(ErrorClass::File, None, format_args!("Failed to create {}: {}", path.display(), e)))?;
+		file.write_all(&buffer)
+			.map_err(|e| output.error(ErrorClass::File, None, format_args!("Failed to write to {}: {}", path.display(), e)))?;
 	}
 
 	Ok(())
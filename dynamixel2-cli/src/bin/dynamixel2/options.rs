@@ -1,9 +1,15 @@
 use std::path::PathBuf;
 
+use crate::output::{ErrorClass, Format, Output};
+
 /// Communicate with Dynamixel protocol 2.0 motors.
 ///
 /// Most commands that take a motor ID can also take the special value "broadcast".
 /// The only exceptions are the read commands, which can not be broadcasted.
+///
+/// The exit code is 0 on success, or indicates the class of the first error:
+/// 1 for other errors, 2 for invalid arguments, 3 for serial port errors, 4 for timeouts,
+/// 5 for invalid responses, 6 for errors reported by a motor, 7 for file errors and 8 for failed script assertions.
 #[derive(clap::Parser)]
 pub struct Options {
 	/// Print more verbose messages. Can be used multiple times.
@@ -25,6 +31,15 @@ pub struct Options {
 	#[clap(default_value = "57600")]
 	pub baud_rate: u32,
 
+	/// The output format.
+	///
+	/// In JSON mode, every result and error is printed as a single line JSON object on standard output.
+	/// The exit code indicates the class of the first error.
+	#[clap(long, value_enum)]
+	#[clap(global = true)]
+	#[clap(default_value = "text")]
+	pub format: Format,
+
 	#[clap(subcommand)]
 	pub command: Command,
 }
@@ -159,6 +174,174 @@ pub enum Command {
 		data: Vec<u8>,
 	},
 
+	/// Reset the settings of a motor to the factory defaults.
+	FactoryReset {
+		/// The motor to reset.
+		///
+		/// You may specify the broadcast address to reset all connected motors.
+		#[clap(value_name = "MOTOR_ID|broadcast")]
+		motor_id: MotorId,
+
+		/// The settings to keep.
+		#[clap(long, value_enum)]
+		#[clap(default_value = "id-and-baud-rate")]
+		keep: KeepSettings,
+	},
+
+	/// Clear the multi-turn revolution counter or the error state of a motor.
+	Clear {
+		/// The motor to clear.
+		///
+		/// You may specify the broadcast address to clear all connected motors.
+		#[clap(value_name = "MOTOR_ID|broadcast")]
+		motor_id: MotorId,
+
+		/// What to clear.
+		#[clap(value_enum)]
+		target: ClearTarget,
+	},
+
+	/// Register a write of an arbitrary number of bytes, to be executed by the action command.
+	///
+	/// Keep in mind that registers on Dynamixel motors are little endian.
+	RegWrite {
+		/// The motor to write to.
+		#[clap(value_name = "MOTOR_ID|broadcast")]
+		motor_id: MotorId,
+
+		/// The address to write to.
+		#[clap(value_name = "ADDRESS")]
+		address: u16,
+
+		/// The data to write.
+		#[clap(value_name = "DATA")]
+		data: Vec<u8>,
+	},
+
+	/// Execute a write registered with the reg-write command.
+	Action {
+		/// The motor to trigger.
+		///
+		/// You may specify the broadcast address to trigger all connected motors at the same time.
+		#[clap(value_name = "MOTOR_ID|broadcast")]
+		motor_id: MotorId,
+	},
+
+	/// Read the same bytes from multiple motors with a single instruction.
+	SyncRead {
+		/// The motors to read from, as comma separated list of IDs and ranges (for example: 1,2,5-8).
+		#[clap(value_name = "MOTOR_IDS")]
+		motor_ids: MotorIds,
+
+		/// The address to read from.
+		#[clap(value_name = "ADDRESS")]
+		address: u16,
+
+		/// The number of bytes to read.
+		#[clap(value_name = "COUNT")]
+		count: u16,
+	},
+
+	/// Write different data to the same address of multiple motors with a single instruction.
+	///
+	/// The data for each motor is given as MOTOR_ID:DATA, where DATA is a comma separated list of bytes.
+	/// The data for all motors must have the same length.
+	/// Keep in mind that registers on Dynamixel motors are little endian.
+	SyncWrite {
+		/// The address to write to.
+		#[clap(value_name = "ADDRESS")]
+		address: u16,
+
+		/// The data to write for each motor (for example: 1:0,8,0,0 2:0,4,0,0).
+		#[clap(value_name = "MOTOR_ID:DATA", required = true)]
+		data: Vec<SyncWriteArg>,
+	},
+
+	/// Read from different addresses of multiple motors with a single instruction.
+	BulkRead {
+		/// The reads to perform, as MOTOR_ID:ADDRESS:COUNT (for example: 1:132:4 2:146:1).
+		#[clap(value_name = "MOTOR_ID:ADDRESS:COUNT", required = true)]
+		reads: Vec<BulkReadArg>,
+	},
+
+	/// Write data to different addresses of multiple motors with a single instruction.
+	///
+	/// Keep in mind that registers on Dynamixel motors are little endian.
+	BulkWrite {
+		/// The writes to perform, as MOTOR_ID:ADDRESS:DATA, where DATA is a comma separated list of bytes (for example: 1:65:1 2:116:0,8,0,0).
+		#[clap(value_name = "MOTOR_ID:ADDRESS:DATA", required = true)]
+		writes: Vec<BulkWriteArg>,
+	},
+
+	/// Read and print all registers of a motor.
+	///
+	/// The registers are decoded using the control table layout of the motor model.
+	/// The raw control table can also be saved to a file, to restore it later with the restore command.
+	Dump {
+		/// The motor to read from (no broadcast ID allowed).
+		#[clap(value_name = "MOTOR_ID")]
+		motor_id: MotorId,
+
+		/// Save the control table to a file.
+		#[clap(long, short)]
+		output: Option<PathBuf>,
+	},
+
+	/// Restore the registers of a motor from a file created with the dump command.
+	///
+	/// Only writable registers are restored, except for the motor ID and baud rate.
+	/// Only the registers that differ from the snapshot are written, and they are printed first.
+	Restore {
+		/// The motor to restore (no broadcast ID allowed).
+		#[clap(value_name = "MOTOR_ID")]
+		motor_id: MotorId,
+
+		/// The file to restore from.
+		#[clap(value_name = "FILE")]
+		file: PathBuf,
+
+		/// Only print the changed registers, without writing them.
+		#[clap(long)]
+		dry_run: bool,
+	},
+
+	/// Show an interactive dashboard to monitor and control all motors on the bus.
+	///
+	/// The dashboard scans the bus for motors and continuously polls their position, velocity, current, voltage, temperature and hardware error status.
+	/// It also allows you to toggle torque and to edit the goal values of the selected motor.
+	Dashboard {
+		/// The interval between two polls of the motors, in milliseconds.
+		#[clap(long)]
+		#[clap(default_value = "100")]
+		interval: u64,
+	},
+
+	/// Run a sequence of commands from a script file.
+	///
+	/// Each line of the script contains a command, with the same syntax as on the command line.
+	/// Empty lines and lines starting with # are ignored.
+	/// The script can also use the following statements:
+	///
+	///   sleep DURATION               Wait for a duration, such as 500ms or 2s.
+	///
+	///   repeat COUNT ... end         Repeat the enclosed lines COUNT times.
+	///
+	///   for NAME in IDS ... end      Repeat the enclosed lines for each ID in a list like 1,2,5-8,
+	///                                replacing $NAME with the ID.
+	///
+	///   assert COMMAND OP VALUE      Run a read8, read16, read32 or read command and compare the result,
+	///                                where OP is one of ==, !=, <, <=, > or >=.
+	///                                For the read command, VALUE is a comma separated list of bytes.
+	///                                A negative VALUE compares the result as a signed number.
+	///
+	/// The script is aborted on the first failed command or assertion.
+	#[clap(verbatim_doc_comment)]
+	Run {
+		/// The script to run.
+		#[clap(value_name = "SCRIPT")]
+		script: PathBuf,
+	},
+
 	/// Write shell completions to standard output or a file.
 	ShellCompletion {
 		/// The shell for which to generate completions.
@@ -184,13 +367,14 @@ impl MotorId {
 		}
 	}
 
-	pub fn assume_unicast(self) -> Result<u8, ()> {
+	pub fn assume_unicast(self, output: &Output) -> Result<u8, ErrorClass> {
 		match self {
 			Self::Id(raw) => Ok(raw),
-			Self::Broadcast => {
-				log::error!("Invalid motor ID: this command can not be broadcasted.");
-				Err(())
-			},
+			Self::Broadcast => Err(output.error(
+				ErrorClass::Usage,
+				None,
+				"Invalid motor ID: this command can not be broadcasted.",
+			)),
 		}
 	}
 }
@@ -212,3 +396,142 @@ impl std::str::FromStr for MotorId {
 		}
 	}
 }
+
+/// The settings to keep during a factory reset.
+#[derive(Copy, Clone, clap::ValueEnum)]
+pub enum KeepSettings {
+	/// Reset all settings, including the motor ID and baud rate.
+	None,
+
+	/// Keep the motor ID.
+	Id,
+
+	/// Keep the motor ID and baud rate.
+	IdAndBaudRate,
+}
+
+impl KeepSettings {
+	pub fn reset_kind(self) -> dynamixel2::client::FactoryResetKind {
+		use dynamixel2::client::FactoryResetKind;
+		match self {
+			Self::None => FactoryResetKind::ResetAll,
+			Self::Id => FactoryResetKind::KeepId,
+			Self::IdAndBaudRate => FactoryResetKind::KeepIdAndBaudRate,
+		}
+	}
+}
+
+/// The state to clear with the clear command.
+#[derive(Copy, Clone, clap::ValueEnum)]
+pub enum ClearTarget {
+	/// The multi-turn revolution counter.
+	RevolutionCounter,
+
+	/// The error state of the motor.
+	Error,
+}
+
+/// A list of unicast motor IDs.
+#[derive(Clone)]
+pub struct MotorIds(pub Vec<u8>);
+
+impl std::str::FromStr for MotorIds {
+	type Err = String;
+
+	fn from_str(data: &str) -> Result<Self, Self::Err> {
+		let mut ids = Vec::new();
+		for part in data.split(',') {
+			let (start, end) = match part.split_once('-') {
+				Some((start, end)) => (parse_unicast_id(start)?, parse_unicast_id(end)?),
+				None => {
+					let id = parse_unicast_id(part)?;
+					(id, id)
+				},
+			};
+			if start > end {
+				return Err(format!("invalid motor ID range: {}", part));
+			}
+			for id in start..=end {
+				if ids.contains(&id) {
+					return Err(format!("duplicate motor ID: {}", id));
+				}
+				ids.push(id);
+			}
+		}
+		Ok(Self(ids))
+	}
+}
+
+/// The data to write to a single motor with a sync write.
+#[derive(Clone)]
+pub struct SyncWriteArg {
+	pub motor_id: u8,
+	pub data: Vec<u8>,
+}
+
+impl std::str::FromStr for SyncWriteArg {
+	type Err = String;
+
+	fn from_str(data: &str) -> Result<Self, Self::Err> {
+		let (motor_id, bytes) = data
+			.split_once(':')
+			.ok_or_else(|| format!("invalid sync write data: expected MOTOR_ID:DATA, got {:?}", data))?;
+		Ok(Self {
+			motor_id: parse_unicast_id(motor_id)?,
+			data: parse_bytes(bytes)?,
+		})
+	}
+}
+
+/// A single read of a bulk read.
+#[derive(Clone)]
+pub struct BulkReadArg(pub dynamixel2::client::BulkReadData);
+
+impl std::str::FromStr for BulkReadArg {
+	type Err = String;
+
+	fn from_str(data: &str) -> Result<Self, Self::Err> {
+		let mut parts = data.split(':');
+		let (Some(motor_id), Some(address), Some(count), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+			return Err(format!("invalid bulk read: expected MOTOR_ID:ADDRESS:COUNT, got {:?}", data));
+		};
+		Ok(Self(dynamixel2::client::BulkReadData {
+			motor_id: parse_unicast_id(motor_id)?,
+			address: address.parse().map_err(|_| format!("invalid address: {:?}", address))?,
+			count: count.parse().map_err(|_| format!("invalid count: {:?}", count))?,
+		}))
+	}
+}
+
+/// A single write of a bulk write.
+#[derive(Clone)]
+pub struct BulkWriteArg(pub dynamixel2::client::BulkWriteData<Vec<u8>>);
+
+impl std::str::FromStr for BulkWriteArg {
+	type Err = String;
+
+	fn from_str(data: &str) -> Result<Self, Self::Err> {
+		let mut parts = data.split(':');
+		let (Some(motor_id), Some(address), Some(bytes), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+			return Err(format!("invalid bulk write: expected MOTOR_ID:ADDRESS:DATA, got {:?}", data));
+		};
+		Ok(Self(dynamixel2::client::BulkWriteData {
+			motor_id: parse_unicast_id(motor_id)?,
+			address: address.parse().map_err(|_| format!("invalid address: {:?}", address))?,
+			data: parse_bytes(bytes)?,
+		}))
+	}
+}
+
+fn parse_unicast_id(data: &str) -> Result<u8, String> {
+	match data.parse() {
+		Ok(id) if id != dynamixel2::bus::packet_id::BROADCAST => Ok(id),
+		_ => Err(format!("invalid motor ID: expected a number in the range 0..254, got {:?}", data)),
+	}
+}
+
+fn parse_bytes(data: &str) -> Result<Vec<u8>, String> {
+	data.split(',')
+		.map(|byte| byte.parse().map_err(|_| format!("invalid byte: {:?}", byte)))
+		.collect()
+}
@@ -0,0 +1,199 @@
+//! Reporting of command results and errors, as human readable text or as JSON.
+
+use dynamixel2::{ReadError, TransferError, WriteError};
+use serde_json::{json, Value};
+use std::fmt::Display;
+use std::time::Duration;
+
+/// The output format of the command line tool.
+#[derive(Copy, Clone, Eq, PartialEq, clap::ValueEnum)]
+pub enum Format {
+	/// Human readable messages on standard error.
+	Text,
+
+	/// One JSON object per result or error on standard output.
+	Json,
+}
+
+/// The class of an error, used to determine the exit code.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ErrorClass {
+	/// An error that does not fit in any other class.
+	Other,
+
+	/// The command line arguments are not valid.
+	Usage,
+
+	/// Failed to open or communicate with the serial port.
+	SerialPort,
+
+	/// A motor did not respond in time.
+	Timeout,
+
+	/// A motor sent an invalid response.
+	InvalidResponse,
+
+	/// A motor reported an error in the response.
+	MotorError,
+
+	/// Failed to read or write a file.
+	File,
+
+	/// An assertion in a script failed.
+	AssertionFailed,
+}
+
+impl ErrorClass {
+	/// Get the exit code of the process for this error class.
+	///
+	/// Exit code 2 is also used by the argument parser for invalid arguments.
+	pub fn exit_code(self) -> i32 {
+		match self {
+			Self::Other => 1,
+			Self::Usage => 2,
+			Self::SerialPort => 3,
+			Self::Timeout => 4,
+			Self::InvalidResponse => 5,
+			Self::MotorError => 6,
+			Self::File => 7,
+			Self::AssertionFailed => 8,
+		}
+	}
+
+	/// Get the name of the error class, as used in JSON output.
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Other => "other",
+			Self::Usage => "usage",
+			Self::SerialPort => "serial_port",
+			Self::Timeout => "timeout",
+			Self::InvalidResponse => "invalid_response",
+			Self::MotorError => "motor_error",
+			Self::File => "file",
+			Self::AssertionFailed => "assertion_failed",
+		}
+	}
+
+	pub fn from_write_error(error: &WriteError<std::io::Error>) -> Self {
+		match error {
+			WriteError::BufferTooSmall(_) => Self::Usage,
+			WriteError::DiscardBuffer(_) | WriteError::Write(_) => Self::SerialPort,
+		}
+	}
+
+	pub fn from_read_error(error: &ReadError<std::io::Error>) -> Self {
+		match error {
+			ReadError::Io(e) if <serial2::SerialPort as dynamixel2::SerialPort>::is_timeout_error(e) => Self::Timeout,
+			ReadError::Io(_) => Self::SerialPort,
+			ReadError::BufferFull(_) | ReadError::InvalidMessage(_) => Self::InvalidResponse,
+			ReadError::MotorError(_) => Self::MotorError,
+		}
+	}
+
+	pub fn from_transfer_error(error: &TransferError<std::io::Error>) -> Self {
+		match error {
+			TransferError::WriteError(e) => Self::from_write_error(e),
+			TransferError::ReadError(e) => Self::from_read_error(e),
+		}
+	}
+}
+
+/// Reports results and errors in the selected output format.
+#[derive(Copy, Clone)]
+pub struct Output {
+	format: Format,
+}
+
+impl Output {
+	pub fn new(format: Format) -> Self {
+		Self { format }
+	}
+
+	pub fn is_json(&self) -> bool {
+		self.format == Format::Json
+	}
+
+	/// Report a response from a motor.
+	///
+	/// In text mode, `text` is logged together with the elapsed time.
+	/// In JSON mode, `data` is printed together with the motor ID, the alert bit and the elapsed time.
+	pub fn response(&self, motor_id: u8, alert: bool, elapsed: Duration, data: Value, text: impl Display) {
+		match self.format {
+			Format::Text => {
+				if alert {
+					log::warn!("Alert bit set in response from motor {}!", motor_id)
+				}
+				log::info!("{:?}: {}", elapsed, text);
+			},
+			Format::Json => self.print(json!({
+				"motor_id": motor_id,
+				"alert": alert,
+				"elapsed_us": elapsed.as_micros() as u64,
+				"data": data,
+			})),
+		}
+	}
+
+	/// Report the successful execution of an instruction that has no response, such as a broadcast.
+	pub fn done(&self, elapsed: Duration) {
+		match self.format {
+			Format::Text => log::info!("{:?}: Ok", elapsed),
+			Format::Json => self.print(json!({
+				"elapsed_us": elapsed.as_micros() as u64,
+			})),
+		}
+	}
+
+	/// Report an arbitrary result.
+	///
+	/// In text mode, `text` is logged.
+	/// In JSON mode, `value` is printed.
+	pub fn result(&self, value: Value, text: impl Display) {
+		match self.format {
+			Format::Text => log::info!("{}", text),
+			Format::Json => self.print(value),
+		}
+	}
+
+	/// Report an error and return the error class.
+	pub fn error(&self, class: ErrorClass, motor_id: Option<u8>, message: impl Display) -> ErrorClass {
+		match self.format {
+			Format::Text => log::error!("{}", message),
+			Format::Json => {
+				let mut value = json!({
+					"error": {
+						"class": class.name(),
+						"message": message.to_string(),
+					},
+				});
+				if let Some(motor_id) = motor_id {
+					value["motor_id"] = motor_id.into();
+				}
+				self.print(value)
+			},
+		}
+		class
+	}
+
+	/// Report a failed transfer and return the error class.
+	pub fn transfer_error(&self, motor_id: Option<u8>, error: TransferError<std::io::Error>) -> ErrorClass {
+		let class = ErrorClass::from_transfer_error(&error);
+		self.error(class, motor_id, format_args!("Command failed: {}", error))
+	}
+
+	/// Report a failed write and return the error class.
+	pub fn write_error(&self, error: WriteError<std::io::Error>) -> ErrorClass {
+		let class = ErrorClass::from_write_error(&error);
+		self.error(class, None, format_args!("Command failed: {}", error))
+	}
+
+	/// Report a failed read from a specific motor and return the error class.
+	pub fn read_error(&self, motor_id: u8, error: ReadError<std::io::Error>) -> ErrorClass {
+		let class = ErrorClass::from_read_error(&error);
+		self.error(class, Some(motor_id), format_args!("Failed to read from motor {}: {}", motor_id, error))
+	}
+
+	fn print(&self, value: Value) {
+		println!("{}", value);
+	}
+}
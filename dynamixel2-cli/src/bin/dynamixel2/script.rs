@@ -0,0 +1,513 @@
+//! Scripts with a sequence of commands, executed by the run command.
+
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::options::{Command, MotorIds};
+use crate::output::{ErrorClass, Output};
+use crate::Client;
+
+/// A parsed script.
+pub struct Script {
+	path: PathBuf,
+	steps: Vec<Step>,
+}
+
+/// A single step of a script, with the line number and the text of the line it was parsed from.
+struct Step {
+	line: usize,
+	text: String,
+	kind: StepKind,
+}
+
+enum StepKind {
+	Command(Command),
+	Sleep(Duration),
+	Repeat(u64, Vec<Step>),
+	Assert(Assertion),
+}
+
+/// A read command with the expected result.
+struct Assertion {
+	command: Command,
+	op: Op,
+	expected: Value,
+}
+
+#[derive(Copy, Clone)]
+enum Op {
+	Eq,
+	Ne,
+	Lt,
+	Le,
+	Gt,
+	Ge,
+}
+
+/// The value read by an assertion, or the expected value.
+#[derive(Debug, PartialEq, PartialOrd)]
+enum Value {
+	Number(i64),
+	Bytes(Vec<u8>),
+}
+
+/// A script line parsed as command line arguments.
+#[derive(clap::Parser)]
+#[clap(no_binary_name = true)]
+struct ScriptCommand {
+	#[clap(subcommand)]
+	command: Command,
+}
+
+/// Read and parse a script file.
+///
+/// The whole script is parsed before anything is executed, so syntax errors are reported early.
+pub fn load(output: &Output, path: &Path) -> Result<Script, ErrorClass> {
+	let data = std::fs::read_to_string(path)
+		.map_err(|e| output.error(ErrorClass::File, None, format_args!("Failed to read {}: {}", path.display(), e)))?;
+	let steps =
+		parse(&data).map_err(|(line, message)| output.error(ErrorClass::Usage, None, format_args!("{}:{}: {}", path.display(), line, message)))?;
+	Ok(Script {
+		path: path.to_owned(),
+		steps,
+	})
+}
+
+/// Parse the text of a script, or return the line number and message of the first error.
+fn parse(data: &str) -> Result<Vec<Step>, (usize, String)> {
+	let lines: Vec<(usize, &str)> = data
+		.lines()
+		.enumerate()
+		.map(|(index, line)| (index + 1, line.trim()))
+		.filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+		.collect();
+
+	let mut parser = Parser {
+		lines: &lines,
+		position: 0,
+		variables: Vec::new(),
+	};
+	parser.parse_block(None)
+}
+
+/// Run all steps of a script, and abort on the first failure.
+pub fn run(client: &mut Client, output: &Output, script: &Script) -> Result<(), ErrorClass> {
+	let mut count = 0;
+	if let Err((step, class)) = run_steps(client, output, &script.steps, &mut count) {
+		return Err(output.error(
+			class,
+			None,
+			format_args!(
+				"Script aborted at {}:{} (step {}): {}",
+				script.path.display(),
+				step.line,
+				count,
+				step.text
+			),
+		));
+	}
+	output.result(
+		json!({
+			"script": script.path,
+			"steps": count,
+		}),
+		format_args!("Script finished after {} steps", count),
+	);
+	Ok(())
+}
+
+fn run_steps<'a>(client: &mut Client, output: &Output, steps: &'a [Step], count: &mut u64) -> Result<(), (&'a Step, ErrorClass)> {
+	for step in steps {
+		if let StepKind::Repeat(repeat, body) = &step.kind {
+			for _ in 0..*repeat {
+				run_steps(client, output, body, count)?;
+			}
+			continue;
+		}
+
+		*count += 1;
+		log::info!("Line {}: {}", step.line, step.text);
+		let result = match &step.kind {
+			StepKind::Command(command) => crate::run_command(client, output, command),
+			StepKind::Sleep(duration) => {
+				std::thread::sleep(*duration);
+				Ok(())
+			},
+			StepKind::Assert(assertion) => check_assertion(client, output, step, assertion),
+			StepKind::Repeat(..) => unreachable!(),
+		};
+		result.map_err(|class| (step, class))?;
+	}
+	Ok(())
+}
+
+fn check_assertion(client: &mut Client, output: &Output, step: &Step, assertion: &Assertion) -> Result<(), ErrorClass> {
+	let transfer_error = |motor_id, e| output.transfer_error(Some(motor_id), e);
+	// Compare with a negative value as a signed number of the same width, so two's complement values match.
+	let signed = matches!(assertion.expected, Value::Number(expected) if expected < 0);
+	let (motor_id, actual) = match &assertion.command {
+		Command::Read8 { motor_id, address } => {
+			let motor_id = motor_id.assume_unicast(output)?;
+			let response = client.read::<u8>(motor_id, *address).map_err(|e| transfer_error(motor_id, e))?;
+			(motor_id, Value::number(response.data.into(), 8, signed))
+		},
+		Command::Read16 { motor_id, address } => {
+			let motor_id = motor_id.assume_unicast(output)?;
+			let response = client.read::<u16>(motor_id, *address).map_err(|e| transfer_error(motor_id, e))?;
+			(motor_id, Value::number(response.data.into(), 16, signed))
+		},
+		Command::Read32 { motor_id, address } => {
+			let motor_id = motor_id.assume_unicast(output)?;
+			let response = client.read::<u32>(motor_id, *address).map_err(|e| transfer_error(motor_id, e))?;
+			(motor_id, Value::number(response.data, 32, signed))
+		},
+		Command::Read { motor_id, address, count } => {
+			let motor_id = motor_id.assume_unicast(output)?;
+			let response = client
+				.read_bytes::<Vec<u8>>(motor_id, *address, *count)
+				.map_err(|e| transfer_error(motor_id, e))?;
+			(motor_id, Value::Bytes(response.data))
+		},
+		// Other commands are rejected by the parser.
+		_ => unreachable!(),
+	};
+
+	if !assertion.op.compare(&actual, &assertion.expected) {
+		return Err(output.error(
+			ErrorClass::AssertionFailed,
+			Some(motor_id),
+			format_args!("Assertion failed: {}, but the value is {}", step.text, actual),
+		));
+	}
+	output.result(
+		json!({
+			"motor_id": motor_id,
+			"line": step.line,
+			"assert": step.text,
+			"value": actual.to_json(),
+			"passed": true,
+		}),
+		format_args!("Assertion passed: the value is {}", actual),
+	);
+	Ok(())
+}
+
+/// Parser for the lines of a script.
+struct Parser<'a> {
+	lines: &'a [(usize, &'a str)],
+	position: usize,
+	variables: Vec<(String, u8)>,
+}
+
+impl Parser<'_> {
+	/// Parse lines until the end of the script, or until the `end` of a block opened at line `opened_at`.
+	fn parse_block(&mut self, opened_at: Option<usize>) -> Result<Vec<Step>, (usize, String)> {
+		let mut steps = Vec::new();
+		while let Some(&(line, text)) = self.lines.get(self.position) {
+			self.position += 1;
+			let text = self.substitute(text).map_err(|e| (line, e))?;
+			let words: Vec<&str> = text.split_whitespace().collect();
+			match words[..] {
+				["end"] if opened_at.is_some() => return Ok(steps),
+				["end"] => return Err((line, "unexpected end".into())),
+				["sleep", duration] => {
+					let duration = parse_duration(duration).map_err(|e| (line, e))?;
+					steps.push(Step {
+						line,
+						text,
+						kind: StepKind::Sleep(duration),
+					});
+				},
+				["repeat", count] => {
+					let count = count.parse().map_err(|_| (line, format!("invalid repeat count: {:?}", count)))?;
+					let body = self.parse_block(Some(line))?;
+					steps.push(Step {
+						line,
+						text,
+						kind: StepKind::Repeat(count, body),
+					});
+				},
+				["for", name, "in", motor_ids] => {
+					if name.is_empty() || !name.chars().all(is_identifier_char) {
+						return Err((line, format!("invalid variable name: {:?}", name)));
+					}
+					let motor_ids: MotorIds = motor_ids.parse().map_err(|e| (line, e))?;
+					let start = self.position;
+					for motor_id in motor_ids.0 {
+						self.position = start;
+						self.variables.push((name.to_string(), motor_id));
+						let body = self.parse_block(Some(line));
+						self.variables.pop();
+						steps.extend(body?);
+					}
+				},
+				["assert", ..] => {
+					let assertion = parse_assertion(&words[1..]).map_err(|e| (line, e))?;
+					steps.push(Step {
+						line,
+						text,
+						kind: StepKind::Assert(assertion),
+					});
+				},
+				_ => {
+					let command = parse_command(&words).map_err(|e| (line, e))?;
+					steps.push(Step {
+						line,
+						text,
+						kind: StepKind::Command(command),
+					});
+				},
+			}
+		}
+		match opened_at {
+			Some(line) => Err((line, "missing end for this block".into())),
+			None => Ok(steps),
+		}
+	}
+
+	/// Replace all variables in a line by their value.
+	///
+	/// A variable name extends over all following letters, digits and underscores,
+	/// so `$id` is not mistaken for a variable `$i` followed by `d`.
+	fn substitute(&self, text: &str) -> Result<String, String> {
+		let mut output = String::with_capacity(text.len());
+		let mut rest = text;
+		while let Some((before, after)) = rest.split_once('$') {
+			output.push_str(before);
+			let end = after.find(|c: char| !is_identifier_char(c)).unwrap_or(after.len());
+			let name = &after[..end];
+			// The innermost variable shadows outer variables with the same name.
+			let (_, value) = self
+				.variables
+				.iter()
+				.rev()
+				.find(|(variable, _)| variable == name)
+				.ok_or_else(|| format!("unknown variable: ${}", name))?;
+			output.push_str(&value.to_string());
+			rest = &after[end..];
+		}
+		output.push_str(rest);
+		Ok(output)
+	}
+}
+
+/// Check if a character can be used in the name of a variable.
+fn is_identifier_char(c: char) -> bool {
+	c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn parse_command(words: &[&str]) -> Result<Command, String> {
+	use clap::Parser;
+	let command = ScriptCommand::try_parse_from(words)
+		.map_err(|e| {
+			let message = e.render().to_string();
+			let message = message.lines().next().unwrap_or_default();
+			message.trim_start_matches("error: ").to_string()
+		})?
+		.command;
+	match command {
+		Command::Dashboard { .. } | Command::Run { .. } | Command::ShellCompletion { .. } => {
+			Err(format!("the {} command can not be used in a script", words[0]))
+		},
+		command => Ok(command),
+	}
+}
+
+fn parse_assertion(words: &[&str]) -> Result<Assertion, String> {
+	let (op, index) = words
+		.iter()
+		.enumerate()
+		.find_map(|(index, word)| Some((Op::parse(word)?, index)))
+		.ok_or("missing comparison operator in assertion")?;
+	let [expected] = words[index + 1..] else {
+		return Err("expected a single value after the comparison operator".into());
+	};
+	let command = parse_command(&words[..index])?;
+	let expected = match command {
+		Command::Read8 { .. } | Command::Read16 { .. } | Command::Read32 { .. } => {
+			let bits = match command {
+				Command::Read8 { .. } => 8,
+				Command::Read16 { .. } => 16,
+				_ => 32,
+			};
+			let value: i64 = expected.parse().map_err(|_| format!("invalid value: {:?}", expected))?;
+			// Allow both the signed and the unsigned range of the register.
+			if value < -(1 << (bits - 1)) || value >= 1 << bits {
+				return Err(format!("value does not fit in {} bits: {}", bits, value));
+			}
+			Value::Number(value)
+		},
+		Command::Read { .. } if matches!(op, Op::Eq | Op::Ne) => Value::Bytes(
+			expected
+				.split(',')
+				.map(|byte| byte.parse().map_err(|_| format!("invalid byte: {:?}", byte)))
+				.collect::<Result<_, _>>()?,
+		),
+		Command::Read { .. } => return Err("only == and != can be used to compare bytes".into()),
+		_ => return Err("assertions can only be used with the read8, read16, read32 and read commands".into()),
+	};
+	Ok(Assertion { command, op, expected })
+}
+
+/// Parse a duration like 500ms or 2s, where a number without unit is in milliseconds.
+fn parse_duration(data: &str) -> Result<Duration, String> {
+	let invalid = || format!("invalid duration: {:?}", data);
+	if let Some(millis) = data.strip_suffix("ms") {
+		Ok(Duration::from_millis(millis.parse().map_err(|_| invalid())?))
+	} else if let Some(seconds) = data.strip_suffix('s') {
+		Duration::try_from_secs_f64(seconds.parse().map_err(|_| invalid())?).map_err(|_| invalid())
+	} else {
+		Ok(Duration::from_millis(data.parse().map_err(|_| invalid())?))
+	}
+}
+
+impl Op {
+	fn parse(data: &str) -> Option<Self> {
+		match data {
+			"==" => Some(Self::Eq),
+			"!=" => Some(Self::Ne),
+			"<" => Some(Self::Lt),
+			"<=" => Some(Self::Le),
+			">" => Some(Self::Gt),
+			">=" => Some(Self::Ge),
+			_ => None,
+		}
+	}
+
+	fn compare(self, actual: &Value, expected: &Value) -> bool {
+		match self {
+			Self::Eq => actual == expected,
+			Self::Ne => actual != expected,
+			Self::Lt => actual < expected,
+			Self::Le => actual <= expected,
+			Self::Gt => actual > expected,
+			Self::Ge => actual >= expected,
+		}
+	}
+}
+
+impl Value {
+	/// Create a number from a value read from a register with the given number of bits, optionally sign-extending it.
+	fn number(raw: u32, bits: u32, signed: bool) -> Self {
+		let shift = 64 - bits;
+		if signed {
+			Self::Number((i64::from(raw) << shift) >> shift)
+		} else {
+			Self::Number(i64::from(raw))
+		}
+	}
+
+	fn to_json(&self) -> serde_json::Value {
+		match self {
+			Self::Number(value) => (*value).into(),
+			Self::Bytes(data) => data.as_slice().into(),
+		}
+	}
+}
+
+impl std::fmt::Display for Value {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::Number(value) => write!(f, "{}", value),
+			Self::Bytes(data) => write!(f, "{:?}", data),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+
+	fn texts(steps: &[Step]) -> Vec<&str> {
+		steps.iter().map(|step| step.text.as_str()).collect()
+	}
+
+	#[test]
+	fn parse_blocks() {
+		let_assert!(Ok(steps) = parse("# setup\nrepeat 2\n  ping 1\n  sleep 10ms\nend\n\nping 2\n"));
+		assert!(texts(&steps) == ["repeat 2", "ping 2"]);
+		assert!(steps[0].line == 2);
+		assert!(steps[1].line == 7);
+		let_assert!(StepKind::Repeat(2, body) = &steps[0].kind);
+		assert!(texts(body) == ["ping 1", "sleep 10ms"]);
+		assert!(body[0].line == 3);
+	}
+
+	#[test]
+	fn reject_mismatched_end() {
+		let_assert!(Err((3, message)) = parse("ping 1\n\nend\n"));
+		assert!(message == "unexpected end");
+		let_assert!(Err((2, message)) = parse("ping 1\nrepeat 2\nping 1\n"));
+		assert!(message == "missing end for this block");
+		let_assert!(Err((1, message)) = parse("for id in 1,2\nping $id\n"));
+		assert!(message == "missing end for this block");
+	}
+
+	#[test]
+	fn expand_for_loops() {
+		let_assert!(Ok(steps) = parse("for id in 1,3-4\nread8 $id 64\nend\n"));
+		assert!(texts(&steps) == ["read8 1 64", "read8 3 64", "read8 4 64"]);
+		assert!(steps.iter().all(|step| step.line == 2));
+
+		let_assert!(Ok(steps) = parse("for i in 1\nfor id in 2\nwrite8 $id 64 $i\nend\nend\n"));
+		assert!(texts(&steps) == ["write8 2 64 1"]);
+
+		// The inner variable shadows the outer variable with the same name.
+		let_assert!(Ok(steps) = parse("for id in 1\nfor id in 2\nping $id\nend\nping $id\nend\n"));
+		assert!(texts(&steps) == ["ping 2", "ping 1"]);
+	}
+
+	#[test]
+	fn substitute_whole_variable_names() {
+		let_assert!(Err((2, message)) = parse("for i in 1\nping $id\nend\n"));
+		assert!(message == "unknown variable: $id");
+		let_assert!(Err((1, message)) = parse("ping $\n"));
+		assert!(message == "unknown variable: $");
+		let_assert!(Err((1, message)) = parse("for motor-id in 1\nend\n"));
+		assert!(message == "invalid variable name: \"motor-id\"");
+	}
+
+	#[test]
+	fn parse_assertions() {
+		let_assert!(Ok(steps) = parse("assert read16 1 84 >= 800\nassert read32 1 20 == -10\nassert read 1 0 2 != 1,2\n"));
+		let_assert!(StepKind::Assert(assertion) = &steps[0].kind);
+		assert!(let Op::Ge = assertion.op);
+		assert!(assertion.expected == Value::Number(800));
+		let_assert!(StepKind::Assert(assertion) = &steps[1].kind);
+		assert!(assertion.expected == Value::Number(-10));
+		let_assert!(StepKind::Assert(assertion) = &steps[2].kind);
+		assert!(let Op::Ne = assertion.op);
+		assert!(assertion.expected == Value::Bytes(vec![1, 2]));
+
+		let_assert!(Err((1, message)) = parse("assert read8 1 64 1\n"));
+		assert!(message == "missing comparison operator in assertion");
+		let_assert!(Err((1, message)) = parse("assert read8 1 64 == 256\n"));
+		assert!(message == "value does not fit in 8 bits: 256");
+		let_assert!(Err((1, message)) = parse("assert read8 1 64 == -129\n"));
+		assert!(message == "value does not fit in 8 bits: -129");
+		let_assert!(Err((1, message)) = parse("assert read 1 0 2 < 1,2\n"));
+		assert!(message == "only == and != can be used to compare bytes");
+		let_assert!(Err((1, _)) = parse("assert ping 1 == 1\n"));
+	}
+
+	#[test]
+	fn sign_extend_numbers() {
+		assert!(Value::number(0xFFFF_FFF6, 32, true) == Value::Number(-10));
+		assert!(Value::number(0xFFFF_FFF6, 32, false) == Value::Number(0xFFFF_FFF6));
+		assert!(Value::number(0x80, 8, true) == Value::Number(-128));
+		assert!(Value::number(0x7F, 8, true) == Value::Number(127));
+		assert!(Value::number(0xFF, 8, false) == Value::Number(255));
+	}
+
+	#[test]
+	fn parse_durations() {
+		assert!(parse_duration("500ms") == Ok(Duration::from_millis(500)));
+		assert!(parse_duration("2s") == Ok(Duration::from_secs(2)));
+		assert!(parse_duration("0.25s") == Ok(Duration::from_millis(250)));
+		assert!(parse_duration("20") == Ok(Duration::from_millis(20)));
+		assert!(parse_duration("-1s") == Err("invalid duration: \"-1s\"".into()));
+		assert!(parse_duration("fast").is_err());
+	}
+}
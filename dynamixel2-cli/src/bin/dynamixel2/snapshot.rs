@@ -0,0 +1,167 @@
+use dynamixel2::control_table::{self, ControlTableSnapshot};
+use dynamixel2::RestoreError;
+use serde_json::{json, Value};
+use std::path::Path;
+
+use crate::output::{ErrorClass, Output};
+use crate::Client;
+
+/// Read the full control table of a motor, print all registers and optionally save the snapshot to a file.
+pub fn dump(client: &mut Client, output: &Output, motor_id: u8, file: Option<&Path>) -> Result<(), ErrorClass> {
+	let model = client
+		.ping(motor_id)
+		.map_err(|e| output.transfer_error(Some(motor_id), e))?
+		.data
+		.model;
+	let control_table = control_table::control_table_or_default(model);
+	log::debug!("Reading control table of motor {} using the {} layout", motor_id, control_table.name);
+	let snapshot = client
+		.dump_control_table(motor_id, control_table.address_range())
+		.map_err(|e| output.transfer_error(Some(motor_id), e))?;
+	let model_name = control_table::model(snapshot.model).map(|model| model.name);
+
+	if output.is_json() {
+		let registers: Vec<_> = control_table
+			.registers
+			.iter()
+			.filter_map(|register| {
+				let value = snapshot.get(register)?;
+				Some(json!({
+					"name": register.name,
+					"address": register.address,
+					"size": register.size,
+					"value": value_json(value),
+				}))
+			})
+			.collect();
+		output.result(
+			json!({
+				"motor_id": snapshot.motor_id,
+				"model": snapshot.model,
+				"model_name": model_name,
+				"firmware": snapshot.firmware,
+				"registers": registers,
+			}),
+			"",
+		);
+	} else {
+		log::info!("Motor ID: {}", snapshot.motor_id);
+		match model_name {
+			Some(name) => log::info!(" ├─ Model: {} ({})", snapshot.model, name),
+			None => log::info!(" ├─ Model: {} (unknown, assuming {} layout)", snapshot.model, control_table.name),
+		}
+		log::info!(" └─ Firmware: {}", snapshot.firmware);
+		for register in control_table.registers {
+			if let Some(value) = snapshot.get(register) {
+				log::info!("{:>4}  {:<28} {}", register.address, register.name, format_value(value));
+			}
+		}
+	}
+
+	if let Some(path) = file {
+		let data = serde_json::to_string_pretty(&snapshot)
+			.map_err(|e| output.error(ErrorClass::Other, None, format_args!("Failed to serialize snapshot: {}", e)))?;
+		std::fs::write(path, data)
+			.map_err(|e| output.error(ErrorClass::File, None, format_args!("Failed to write to {}: {}", path.display(), e)))?;
+		log::info!("Saved snapshot to {}", path.display());
+	}
+	Ok(())
+}
+
+/// Restore a snapshot from a file to a motor, or only show the differences if `dry_run` is true.
+pub fn restore(client: &mut Client, output: &Output, motor_id: u8, path: &Path, dry_run: bool) -> Result<(), ErrorClass> {
+	let data = std::fs::read_to_string(path)
+		.map_err(|e| output.error(ErrorClass::File, None, format_args!("Failed to read {}: {}", path.display(), e)))?;
+	let snapshot: ControlTableSnapshot = serde_json::from_str(&data)
+		.map_err(|e| output.error(ErrorClass::File, None, format_args!("Failed to parse {}: {}", path.display(), e)))?;
+	if snapshot.motor_id != motor_id {
+		log::warn!("Snapshot was taken from motor {}, restoring it to motor {}", snapshot.motor_id, motor_id);
+	}
+
+	let current = client
+		.dump_control_table(motor_id, snapshot.range())
+		.map_err(|e| output.transfer_error(Some(motor_id), e))?;
+	if current.model != snapshot.model {
+		return Err(output.error(
+			ErrorClass::Other,
+			Some(motor_id),
+			format_args!(
+				"Snapshot is for model {}, but motor {} is model {}",
+				snapshot.model, motor_id, current.model
+			),
+		));
+	}
+
+	let mut changes = Vec::new();
+	for (register, value) in snapshot.restorable_registers() {
+		let current = current.get(register).unwrap_or_default();
+		if current != value {
+			if !output.is_json() {
+				log::info!("{:<28} {} -> {}", register.name, format_value(current), format_value(value));
+			}
+			changes.push(json!({
+				"register": register.name,
+				"current": value_json(current),
+				"snapshot": value_json(value),
+			}));
+		}
+	}
+
+	let restore = !dry_run && !changes.is_empty();
+	if restore {
+		client
+			.restore_control_table_changes(motor_id, &snapshot, &current)
+			.map_err(|e| match e {
+				RestoreError::TransferError(e) => output.transfer_error(Some(motor_id), e),
+				e => output.error(ErrorClass::Other, Some(motor_id), format_args!("Command failed: {}", e)),
+			})?;
+	}
+
+	let text = if changes.is_empty() {
+		format!("Motor {} already matches the snapshot", motor_id)
+	} else if dry_run {
+		format!("Dry run: {} registers would be changed", changes.len())
+	} else {
+		format!("Restored {} registers", changes.len())
+	};
+	output.result(
+		json!({
+			"motor_id": motor_id,
+			"dry_run": dry_run,
+			"restored": restore,
+			"changes": changes,
+		}),
+		text,
+	);
+	Ok(())
+}
+
+/// Convert the raw value of a register to JSON.
+///
+/// Registers of 1, 2 or 4 bytes are converted to a little endian number, larger registers to an array of bytes.
+fn value_json(data: &[u8]) -> Value {
+	match *data {
+		[a] => a.into(),
+		[a, b] => u16::from_le_bytes([a, b]).into(),
+		[a, b, c, d] => u32::from_le_bytes([a, b, c, d]).into(),
+		_ => data.into(),
+	}
+}
+
+/// Format the raw value of a register.
+///
+/// Registers of 1, 2 or 4 bytes are shown as little endian number, larger registers as raw bytes.
+fn format_value(data: &[u8]) -> String {
+	match *data {
+		[a] => format!("{} (0x{:02X})", a, a),
+		[a, b] => {
+			let value = u16::from_le_bytes([a, b]);
+			format!("{} (0x{:04X})", value, value)
+		},
+		[a, b, c, d] => {
+			let value = u32::from_le_bytes([a, b, c, d]);
+			format!("{} (0x{:04X} {:04X})", value, value >> 16, value & 0xFFFF)
+		},
+		_ => format!("{:02X?}", data),
+	}
+}
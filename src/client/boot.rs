@@ -0,0 +1,67 @@
+use super::control_loop::sleep_until;
+use super::{Client, SerialPort};
+use crate::control_table::ControlTableSnapshot;
+use crate::{RestoreError, TransferError};
+use core::ops::Range;
+use core::time::Duration;
+use std::time::Instant;
+
+/// The timeout of each ping while waiting for a motor to boot after a reboot.
+const BOOT_PING_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// The default time to wait for a motor to boot after a reboot.
+pub(crate) const DEFAULT_BOOT_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[super::bisync]
+impl<Port, Buffer> Client<Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Reboot a motor and restore a range of its control table afterwards.
+	///
+	/// A reboot resets all RAM registers, such as the torque enable, goal values, indirect addresses and PID gains.
+	/// This function takes a snapshot of `registers` first, reboots the motor,
+	/// and pings it until it replies again or one second has passed.
+	/// Then the snapshot is written back with [`Self::restore_control_table_snapshot()`].
+	///
+	/// Use an address range in the RAM area of the control table for `registers`.
+	/// For the X-series, `64..208` covers the RAM registers from Torque Enable up to the first block of indirect addresses.
+	/// Leave out the indirect data: writing it back would also write the registers it is mapped to.
+	/// The Bus Watchdog is never restored (see [`ControlTableSnapshot::restorable_registers()`]),
+	/// so a tripped or armed watchdog is left disabled after the reboot.
+	///
+	/// Note that a motor disables torque when it detects a hardware error,
+	/// so if the motor is rebooted to clear a hardware error, the torque is restored in its disabled state.
+	/// Enable the torque explicitly afterwards, or take a snapshot before the error occurs
+	/// and restore it with [`Self::reboot()`] and [`Self::restore_control_table_snapshot()`].
+	pub async fn reboot_and_restore(&mut self, motor_id: u8, registers: Range<u16>) -> Result<ControlTableSnapshot, RestoreError<Port::Error>> {
+		let snapshot = self.dump_control_table(motor_id, registers).await?;
+		self.reboot(motor_id).await?;
+		self.wait_for_boot(motor_id, DEFAULT_BOOT_TIMEOUT).await?;
+		self.restore_control_table_snapshot(motor_id, &snapshot).await?;
+		Ok(snapshot)
+	}
+
+	/// Wait for a motor to reply to a ping after a reboot.
+	///
+	/// The motor is pinged with a short timeout until it replies or `boot_timeout` has passed.
+	/// Pings that fail because the motor is still booting are retried.
+	/// If a ping fails before its timeout, the next ping waits until the timeout would have passed,
+	/// so a motor that replies with garbage while booting is not flooded with pings.
+	/// If the motor does not reply in time, the error of the last ping is returned.
+	pub(crate) async fn wait_for_boot(&mut self, motor_id: u8, boot_timeout: Duration) -> Result<(), TransferError<Port::Error>> {
+		let deadline = Instant::now() + boot_timeout;
+		loop {
+			let attempt = Instant::now();
+			match self.ping_timeout(motor_id, BOOT_PING_TIMEOUT).await {
+				Ok(_) => return Ok(()),
+				Err(TransferError::ReadError(_)) if Instant::now() < deadline => {
+					trace!("motor {} did not reply to a ping after reboot", motor_id);
+					sleep_until((attempt + BOOT_PING_TIMEOUT).min(deadline)).await;
+				},
+				Err(e) => return Err(e),
+			}
+		}
+	}
+}
@@ -0,0 +1,41 @@
+use super::Client;
+use super::SerialPort;
+use crate::timing::{predict_transaction, Instruction};
+use crate::TransferError;
+use core::time::Duration;
+use std::time::Instant;
+
+/// The smallest response timeout padding set by calibration.
+const MIN_CALIBRATED_PADDING: Duration = Duration::from_millis(1);
+
+#[super::bisync]
+impl<Port, Buffer> Client<Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Measure the response latency of a motor and set its response timeout padding accordingly.
+	///
+	/// The motor is pinged `samples` times.
+	/// The latency of each ping is the measured round trip time minus the time needed to transfer the messages,
+	/// which includes the return delay of the motor and any delays in the serial port driver.
+	/// The padding for the motor is set to twice the largest measured latency, with a minimum of 1 millisecond,
+	/// and returned.
+	///
+	/// The pings use the current timeout of the motor, so make sure it is long enough before calibrating.
+	/// If any ping fails, the error is returned and the padding is not changed.
+	pub async fn calibrate_response_timeout_padding(&mut self, motor_id: u8, samples: usize) -> Result<Duration, TransferError<Port::Error>> {
+		let transfer_time = predict_transaction(&self.bus_timing(), Instruction::Ping, 1, 0).min;
+		let mut max_latency = Duration::ZERO;
+		for _ in 0..samples {
+			let start = Instant::now();
+			self.ping(motor_id).await?;
+			let latency = start.elapsed().saturating_sub(transfer_time);
+			max_latency = max_latency.max(latency);
+		}
+		let padding = (max_latency * 2).max(MIN_CALIBRATED_PADDING);
+		debug!("calibrated response timeout padding for motor {}: {:?}", motor_id, padding);
+		self.set_motor_response_timeout_padding(motor_id, Some(padding));
+		Ok(padding)
+	}
+}
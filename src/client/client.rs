@@ -5,14 +5,10 @@ use crate::TransferError;
 use crate::WriteError;
 use crate::bus::StatusPacket;
 use crate::bus::instruction_id;
+use crate::timing::DEFAULT_RESPONSE_TIMEOUT_PADDING;
 use super::Bus;
 use super::SerialPort;
 
-/// The default additional time added to the automatically calculated read timeout of a status response.
-///
-/// The official SDK adds a flat 34 milliseconds, so we mimic that.
-const DEFAULT_RESPONSE_TIMEOUT_PADDING: Duration = Duration::from_millis(34);
-
 /// Client for the Dynamixel Protocol 2 communication.
 ///
 /// Used to interact with devices on the bus.
@@ -34,6 +30,22 @@ where
 
 	/// Additional time added to the automatically calculated read timeout of a status response.
 	response_timeout_padding: Duration,
+
+	/// Per-motor overrides of the response timeout padding.
+	#[cfg(feature = "alloc")]
+	motor_timeout_padding: alloc::collections::BTreeMap<u8, Duration>,
+
+	/// The number of status responses that may still arrive for the last instruction.
+	///
+	/// Any responses that have not been read when the next instruction is sent are discarded first.
+	/// This happens when a multi-response operation like a sync read is dropped early.
+	pending_responses: usize,
+
+	/// The deadline for all pending responses to arrive.
+	pending_deadline: Option<Port::Instant>,
+
+	/// The motors that set the alert bit in a status response since it was last taken with [`Self::take_alert`].
+	alerts: crate::client::AlertSet,
 }
 
 impl<Port, Buffer> core::fmt::Debug for Client<Port, Buffer>
@@ -62,6 +74,11 @@ impl Client<serial2::SerialPort, Vec<u8>> {
 		Ok(Self {
 			bus,
 			response_timeout_padding: DEFAULT_RESPONSE_TIMEOUT_PADDING,
+			#[cfg(feature = "alloc")]
+			motor_timeout_padding: alloc::collections::BTreeMap::new(),
+			pending_responses: 0,
+			pending_deadline: None,
+			alerts: crate::client::AlertSet::new(),
 		})
 	}
 }
@@ -86,6 +103,11 @@ where
 		Ok(Self {
 			bus,
 			response_timeout_padding: DEFAULT_RESPONSE_TIMEOUT_PADDING,
+			#[cfg(feature = "alloc")]
+			motor_timeout_padding: alloc::collections::BTreeMap::new(),
+			pending_responses: 0,
+			pending_deadline: None,
+			alerts: crate::client::AlertSet::new(),
 		})
 	}
 }
@@ -103,6 +125,11 @@ impl Client<serial2_tokio::SerialPort, Vec<u8>> {
 		Ok(Self {
 			bus,
 			response_timeout_padding: DEFAULT_RESPONSE_TIMEOUT_PADDING,
+			#[cfg(feature = "alloc")]
+			motor_timeout_padding: alloc::collections::BTreeMap::new(),
+			pending_responses: 0,
+			pending_deadline: None,
+			alerts: crate::client::AlertSet::new(),
 		})
 	}
 }
@@ -127,6 +154,11 @@ where
 		Ok(Self {
 			bus,
 			response_timeout_padding: DEFAULT_RESPONSE_TIMEOUT_PADDING,
+			#[cfg(feature = "alloc")]
+			motor_timeout_padding: alloc::collections::BTreeMap::new(),
+			pending_responses: 0,
+			pending_deadline: None,
+			alerts: crate::client::AlertSet::new(),
 		})
 	}
 }
@@ -149,6 +181,11 @@ where
 		Ok(Self {
 			bus,
 			response_timeout_padding: DEFAULT_RESPONSE_TIMEOUT_PADDING,
+			#[cfg(feature = "alloc")]
+			motor_timeout_padding: alloc::collections::BTreeMap::new(),
+			pending_responses: 0,
+			pending_deadline: None,
+			alerts: crate::client::AlertSet::new(),
 		})
 	}
 }
@@ -168,6 +205,11 @@ where
 		Ok(Self {
 			bus,
 			response_timeout_padding: DEFAULT_RESPONSE_TIMEOUT_PADDING,
+			#[cfg(feature = "alloc")]
+			motor_timeout_padding: alloc::collections::BTreeMap::new(),
+			pending_responses: 0,
+			pending_deadline: None,
+			alerts: crate::client::AlertSet::new(),
 		})
 	}
 
@@ -216,6 +258,60 @@ where
 		self.response_timeout_padding = padding;
 	}
 
+	/// Get the response timeout padding used for a specific motor.
+	///
+	/// This is the padding set with [`Self::set_motor_response_timeout_padding`] for the motor,
+	/// or the global [`Self::response_timeout_padding`] if none was set.
+	pub fn motor_response_timeout_padding(&self, motor_id: u8) -> Duration {
+		#[cfg(feature = "alloc")]
+		if let Some(padding) = self.motor_timeout_padding.get(&motor_id) {
+			return *padding;
+		}
+		#[cfg(not(feature = "alloc"))]
+		let _ = motor_id;
+		self.response_timeout_padding
+	}
+
+	/// Set the response timeout padding for a specific motor, overriding the global padding.
+	///
+	/// Use this for motors with a long return delay time, or for slow custom devices.
+	/// The padding is used for all status responses of the motor, including the replies to sync and bulk reads.
+	/// Pass `None` to use the global [`Self::response_timeout_padding`] for the motor again.
+	#[cfg(feature = "alloc")]
+	pub fn set_motor_response_timeout_padding(&mut self, motor_id: u8, padding: Option<Duration>) {
+		match padding {
+			Some(padding) => self.motor_timeout_padding.insert(motor_id, padding),
+			None => self.motor_timeout_padding.remove(&motor_id),
+		};
+	}
+
+	/// Get the timing parameters of the bus, for use with [`timing::predict_transaction()`](crate::timing::predict_transaction).
+	///
+	/// The return delay of the motors is not known to the client, so the default return delay is used.
+	/// The timing only includes the global [response timeout padding](Self::response_timeout_padding):
+	/// if a motor has its own padding (see [`Self::set_motor_response_timeout_padding()`]),
+	/// the `max` time of a prediction underestimates how long the client may wait for its replies.
+	pub fn bus_timing(&self) -> crate::timing::BusTiming {
+		crate::timing::BusTiming {
+			baud_rate: self.bus.baud_rate,
+			return_delay: crate::timing::DEFAULT_RETURN_DELAY,
+			response_timeout_padding: self.response_timeout_padding,
+		}
+	}
+
+	/// Get the set of motors that set the alert bit in a status response.
+	///
+	/// The client records the alert bit of every status response it reads, including error responses.
+	/// A motor stays in the set until it is removed with [`Self::take_alert()`].
+	pub fn alerts(&self) -> &crate::client::AlertSet {
+		&self.alerts
+	}
+
+	/// Check if a motor set the alert bit in a status response, and remove it from the set of alerts.
+	pub fn take_alert(&mut self, motor_id: u8) -> bool {
+		self.alerts.remove(motor_id)
+	}
+
 	/// Write a raw instruction to a stream, and read a single raw response.
 	///
 	/// This function also checks that the packet ID of the status response matches the one from the instruction.
@@ -231,12 +327,32 @@ where
 		expected_response_parameters: u16,
 		encode_parameters: F,
 	) -> Result<StatusPacket<'_>, TransferError<Port::Error>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		self.transfer_single_timeout(packet_id, instruction_id, parameter_count, expected_response_parameters, None, encode_parameters)
+			.await
+	}
+
+	/// Write a raw instruction and read a single raw response, with an optional timeout override for the response.
+	///
+	/// If `timeout` is `None`, the timeout is computed from the expected number of response parameters and the padding for the motor.
+	pub(crate) async fn transfer_single_timeout<F>(
+		&mut self,
+		packet_id: u8,
+		instruction_id: u8,
+		parameter_count: usize,
+		expected_response_parameters: u16,
+		timeout: Option<Duration>,
+		encode_parameters: F,
+	) -> Result<StatusPacket<'_>, TransferError<Port::Error>>
 	where
 		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
 	{
 		self.write_instruction(packet_id, instruction_id, parameter_count, encode_parameters)
 			.await?;
-		let response = self.read_status_response(expected_response_parameters).await?;
+		let timeout = timeout.unwrap_or_else(|| self.motor_response_timeout(packet_id, expected_response_parameters));
+		let response = self.read_status_response_timeout(timeout).await?;
 		crate::error::InvalidPacketId::check(response.packet_id(), packet_id).map_err(crate::ReadError::from)?;
 		Ok(response)
 	}
@@ -252,15 +368,68 @@ where
 	where
 		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
 	{
+		self.discard_pending_responses().await;
 		self.bus
 			.write_instruction(packet_id, instruction_id, parameter_count, encode_parameters)
 			.await
 	}
 
 	/// Read a raw status response from the bus with the given deadline.
+	///
+	/// A status response that reports an error is returned as [`ReadError::MotorError`].
+	/// Use [`Self::read_status_packet_timeout`] to receive it as a status packet instead.
 	pub async fn read_status_response_timeout<'a>(&'a mut self, timeout: Duration) -> Result<StatusPacket<'a>, ReadError<Port::Error>> {
+		let status = self.read_status_packet_timeout(timeout).await?;
+		crate::MotorError::check(status.error())?;
+		Ok(status)
+	}
+
+	/// Read a raw status response with an automatically calculated timeout.
+	///
+	/// The read timeout is determined by the expected number of response parameters and the baud rate of the bus.
+	/// The sender of the response is not known in advance, so only the global [response timeout padding](Self::response_timeout_padding) is used,
+	/// not the padding of a specific motor.
+	pub async fn read_status_response<'a>(&'a mut self, expected_parameters: u16) -> Result<StatusPacket<'a>, ReadError<Port::Error>> {
+		let timeout = self.response_timeout(expected_parameters);
+		self.read_status_response_timeout(timeout).await
+	}
+
+	/// Read a raw status packet from the bus with the given deadline, without checking the error field.
+	///
+	/// Unlike [`Self::read_status_response_timeout`], a status response that reports an error is returned as is.
+	/// This is useful to forward status packets to another bus.
+	pub async fn read_status_packet_timeout<'a>(&'a mut self, timeout: Duration) -> Result<StatusPacket<'a>, ReadError<Port::Error>> {
+		self.read_status_packet_inner(timeout, false).await
+	}
+
+	/// Read a raw status packet in reply to a broadcast instruction with an unknown number of responses.
+	///
+	/// Like [`Self::read_status_packet_timeout`], but a timeout means no more responses will arrive,
+	/// so the pending responses are cleared.
+	pub(crate) async fn read_last_status_packet_timeout<'a>(
+		&'a mut self,
+		timeout: Duration,
+	) -> Result<StatusPacket<'a>, ReadError<Port::Error>> {
+		self.read_status_packet_inner(timeout, true).await
+	}
+
+	async fn read_status_packet_inner<'a>(
+		&'a mut self,
+		timeout: Duration,
+		clear_on_timeout: bool,
+	) -> Result<StatusPacket<'a>, ReadError<Port::Error>> {
 		let deadline = self.serial_port().make_deadline(timeout);
-		let packet = self.bus.read_packet_deadline(deadline).await?;
+		let packet = self.bus.read_packet_deadline(deadline).await;
+		match &packet {
+			Err(ReadError::Io(e)) if clear_on_timeout && Port::is_timeout_error(e) => {
+				self.pending_responses = 0;
+				self.pending_deadline = None;
+			},
+			Err(ReadError::Io(_)) => (),
+			// Anything but an I/O error means a (possibly invalid) response was consumed.
+			_ => self.pending_responses = self.pending_responses.saturating_sub(1),
+		}
+		let packet = packet?;
 		let status = match packet.as_status() {
 			Some(status) => status,
 			None => {
@@ -272,23 +441,115 @@ where
 			},
 		};
 
-		crate::MotorError::check(status.error())?;
+		if status.alert() {
+			self.alerts.insert(status.packet_id());
+		}
 		Ok(status)
 	}
 
-	/// Read a raw status response with an automatically calculated timeout.
+	/// Read a raw status packet with an automatically calculated timeout, without checking the error field.
 	///
-	/// The read timeout is determined by the expected number of response parameters and the baud rate of the bus.
-	pub async fn read_status_response<'a>(&'a mut self, expected_parameters: u16) -> Result<StatusPacket<'a>, ReadError<Port::Error>> {
+	/// See [`Self::read_status_packet_timeout`] and [`Self::read_status_response`].
+	pub async fn read_status_packet<'a>(&'a mut self, expected_parameters: u16) -> Result<StatusPacket<'a>, ReadError<Port::Error>> {
+		let timeout = self.response_timeout(expected_parameters);
+		self.read_status_packet_timeout(timeout).await
+	}
+
+	/// Get the read timeout for a status response with the given number of parameters.
+	pub(crate) fn response_timeout(&self, expected_parameters: u16) -> Duration {
+		self.response_transfer_time(expected_parameters) + self.response_timeout_padding
+	}
+
+	/// Get the read timeout for a status response from a specific motor with the given number of parameters.
+	///
+	/// This uses the response timeout padding for the motor.
+	pub(crate) fn motor_response_timeout(&self, motor_id: u8, expected_parameters: u16) -> Duration {
+		self.response_transfer_time(expected_parameters) + self.motor_response_timeout_padding(motor_id)
+	}
+
+	/// Get the time needed to transfer a status response with the given number of parameters.
+	fn response_transfer_time(&self, expected_parameters: u16) -> Duration {
 		let message_size = crate::bus::StatusPacket::message_len(expected_parameters as usize) as u32;
-		let timeout = crate::bus::message_transfer_time(message_size, self.bus.baud_rate) + self.response_timeout_padding;
-		self.read_status_response_timeout(timeout).await
+		crate::bus::message_transfer_time(message_size, self.bus.baud_rate)
+	}
+
+	/// Get the maximum number of bytes that can be read with a single read instruction.
+	///
+	/// This accounts for the worst case byte stuffing of the status packet in the read buffer.
+	#[cfg(feature = "alloc")]
+	pub(crate) fn max_read_size(&self) -> u16 {
+		// The body (instruction ID, error and parameters) grows by at most one byte for every three bytes when stuffed.
+		let available = self.bus.read_buffer.as_ref().len().saturating_sub(crate::bus::StatusPacket::message_len(0) - 2);
+		let parameters = (available / 4 * 3).saturating_sub(2);
+		parameters.min(u16::MAX.into()) as u16
+	}
+
+	/// Register the number of status responses that the last instruction will trigger.
+	///
+	/// Each response read with [`Self::read_status_response_timeout`] decrements the count.
+	/// If not all responses have been read before the next instruction is sent,
+	/// the remaining responses are discarded until they have all arrived or the timeout expires.
+	pub(crate) fn set_pending_responses(&mut self, count: usize, timeout: Duration) {
+		self.pending_responses = count;
+		self.pending_deadline = Some(self.bus.serial_port.make_deadline(timeout));
+	}
+
+	/// Forget the status responses that are still expected for the last instruction.
+	///
+	/// Used by the multi-response operations once they have read or timed out on every response,
+	/// so the next instruction does not wait for responses that will never arrive.
+	pub(crate) fn clear_pending_responses(&mut self) {
+		self.pending_responses = 0;
+		self.pending_deadline = None;
+	}
+
+	/// Check if status responses are still expected for the last instruction.
+	pub(crate) fn has_pending_responses(&self) -> bool {
+		self.pending_responses > 0
+	}
+
+	/// Discard any status responses that are still expected for a previous instruction.
+	///
+	/// The synchronous multi-response operations drain the bus when they are dropped,
+	/// but the asynchronous ones can not, since a [`Drop`] implementation can not `.await`.
+	///
+	/// The alert bit of the discarded responses is still recorded, see [`Self::alerts()`].
+	async fn discard_pending_responses(&mut self) {
+		let Some(deadline) = self.pending_deadline.take() else {
+			return;
+		};
+		if self.pending_responses > 0 {
+			debug!("discarding up to {} pending status responses", self.pending_responses);
+		}
+		while self.pending_responses > 0 {
+			match self.bus.read_packet_deadline(deadline).await {
+				// A timeout or another I/O error: no more responses will be read before the deadline.
+				Err(ReadError::Io(_)) => break,
+				Ok(packet) => {
+					self.pending_responses -= 1;
+					if let Some(status) = packet.as_status().filter(|status| status.alert()) {
+						self.alerts.insert(status.packet_id());
+					}
+				},
+				Err(_) => self.pending_responses -= 1,
+			}
+		}
+		self.pending_responses = 0;
 	}
 
 	/// Read an empty response from the bus if the motor ID is not the broadcast ID.
 	///
 	/// If the motor ID is the broadcast ID, return a fake response from the broadcast ID.
 	pub(crate) async fn read_response_if_not_broadcast(&mut self, motor_id: u8) -> Result<crate::Response<()>, ReadError<Port::Error>> {
+		self.read_response_if_not_broadcast_timeout(motor_id, None).await
+	}
+
+	/// Read an empty response from the bus if the motor ID is not the broadcast ID, with an optional timeout override.
+	pub(crate) async fn read_response_if_not_broadcast_timeout(
+		&mut self,
+		motor_id: u8,
+		timeout: Option<Duration>,
+	) -> Result<crate::Response<()>, ReadError<Port::Error>> {
 		if motor_id == crate::bus::packet_id::BROADCAST {
 			Ok(crate::Response {
 				motor_id: crate::bus::packet_id::BROADCAST,
@@ -296,7 +557,43 @@ where
 				data: (),
 			})
 		} else {
-			Ok(self.read_status_response(0).await?.try_into()?)
+			let timeout = timeout.unwrap_or_else(|| self.motor_response_timeout(motor_id, 0));
+			Ok(self.read_status_response_timeout(timeout).await?.try_into()?)
+		}
+	}
+
+	/// Probe for a Protocol 1.0 motor by reading its model number and firmware version.
+	///
+	/// This bypasses the read and write buffers of the bus, since Protocol 1.0 uses a different packet format.
+	/// Returns `Ok(None)` if no valid reply is received before the timeout.
+	pub(crate) async fn probe_protocol1(&mut self, motor_id: u8) -> Result<Option<crate::client::Ping>, TransferError<Port::Error>> {
+		use crate::client::protocol1;
+
+		self.discard_pending_responses().await;
+		let serial_port = &mut self.bus.serial_port;
+		serial_port.discard_input_buffer().map_err(WriteError::DiscardBuffer)?;
+		let instruction = protocol1::encode_ping(motor_id);
+		trace!("sending protocol 1.0 packet: {:02X?}", instruction);
+		serial_port.write_all(&instruction).await.map_err(WriteError::Write)?;
+
+		let message_size = protocol1::PING_REPLY_SIZE as u32;
+		let timeout = crate::bus::message_transfer_time(message_size, self.bus.baud_rate) + self.response_timeout_padding;
+		let deadline = serial_port.make_deadline(timeout);
+		let mut buffer = [0; 2 * protocol1::PING_REPLY_SIZE];
+		let mut read_len = 0;
+		while read_len < buffer.len() {
+			match serial_port.read(&mut buffer[read_len..], &deadline).await {
+				Ok(new_data) => read_len += new_data,
+				Err(e) if Port::is_timeout_error(&e) => return Ok(None),
+				Err(e) => return Err(ReadError::Io(e).into()),
+			}
+			match protocol1::parse_ping_reply(&buffer[..read_len], motor_id) {
+				protocol1::ParseResult::Incomplete => continue,
+				protocol1::ParseResult::Invalid => break,
+				protocol1::ParseResult::Reply(ping) => return Ok(Some(ping)),
+			}
 		}
+		debug!("ignoring invalid protocol 1.0 reply: {:02X?}", &buffer[..read_len]);
+		Ok(None)
 	}
 }
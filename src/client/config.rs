@@ -0,0 +1,119 @@
+use super::Client;
+use super::SerialPort;
+use crate::config::{BusConfig, ConfigDiff, ConfigError, MotorConfig, MotorDiff, RegisterChange};
+use crate::control_table::{self, Area};
+use crate::{ReadError, TransferError};
+
+#[super::bisync]
+impl<Port, Buffer> Client<Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Compare a configuration against the motors on the bus.
+	///
+	/// Every configured register is read from the motor and compared with the configured value.
+	/// Motors that do not reply to a ping are reported as missing.
+	pub async fn diff_config(&mut self, config: &BusConfig) -> Result<ConfigDiff, ConfigError<Port::Error>> {
+		let mut diff = ConfigDiff::default();
+		for motor in &config.motors {
+			match self.diff_motor_config(motor).await? {
+				Some(motor_diff) => diff.motors.push(motor_diff),
+				None => diff.missing_motors.push(motor.id),
+			}
+		}
+		Ok(diff)
+	}
+
+	/// Apply a configuration to the motors on the bus.
+	///
+	/// Only registers that differ from the configuration are written.
+	/// If EEPROM registers need to be changed, torque is disabled first and restored afterwards.
+	/// RAM registers are written after the EEPROM registers, with the torque enable register written last.
+	///
+	/// Motors that do not reply to a ping are skipped and reported as missing.
+	/// The returned diff contains all changes that were applied.
+	///
+	/// If an error occurs, the configuration may be partially applied:
+	/// motors and registers before the failing write keep their new value.
+	/// Call [`Self::diff_config()`] to find out which changes are still missing.
+	/// The torque of the failing motor is restored regardless, if it was disabled to write EEPROM registers.
+	pub async fn apply_config(&mut self, config: &BusConfig) -> Result<ConfigDiff, ConfigError<Port::Error>> {
+		let diff = self.diff_config(config).await?;
+		for motor in &diff.motors {
+			self.apply_motor_diff(motor).await?;
+		}
+		Ok(diff)
+	}
+
+	/// Compare the configuration of a single motor, or return `None` if the motor does not reply.
+	async fn diff_motor_config(&mut self, config: &MotorConfig) -> Result<Option<MotorDiff>, ConfigError<Port::Error>> {
+		let model = match self.ping(config.id).await {
+			Ok(response) => response.data.model,
+			Err(TransferError::ReadError(ReadError::Io(e))) if Port::is_timeout_error(&e) => return Ok(None),
+			Err(e) => return Err(e.into()),
+		};
+		let control_table = control_table::control_table_or_default(model);
+
+		let mut changes = Vec::new();
+		for resolved in config.resolve(model, control_table)? {
+			let register = resolved.register;
+			let current = self
+				.read_bytes::<Vec<u8>>(config.id, register.address, register.size)
+				.await?;
+			// The read checked that the reply has the size of the register.
+			let current = register.decode_value(&current.data, resolved.value < 0).unwrap_or_default();
+			if current != resolved.value {
+				changes.push(RegisterChange {
+					register,
+					current,
+					expected: resolved.value,
+				});
+			}
+		}
+
+		Ok(Some(MotorDiff {
+			motor_id: config.id,
+			model,
+			changes,
+		}))
+	}
+
+	/// Write the configured values of all changed registers of a motor.
+	async fn apply_motor_diff(&mut self, diff: &MotorDiff) -> Result<(), ConfigError<Port::Error>> {
+		let control_table = control_table::control_table_or_default(diff.model);
+		let torque_enable = control_table.torque_enable;
+		let (eeprom, ram): (Vec<_>, Vec<_>) = diff.changes.iter().partition(|change| change.register.area == Area::Eeprom);
+
+		if !eeprom.is_empty() {
+			let torque = self.disable_torque_for_eeprom(diff.motor_id, torque_enable.address).await?;
+			let result = self.write_register_changes(diff.motor_id, &eeprom).await;
+			self.restore_torque(diff.motor_id, torque_enable.address, torque, result).await?;
+		}
+
+		let (torque_changes, ram): (Vec<_>, Vec<_>) = ram.into_iter().partition(|change| change.register == torque_enable);
+		self.write_register_changes(diff.motor_id, &ram).await?;
+		self.write_register_changes(diff.motor_id, &torque_changes).await
+	}
+
+	/// Write the configured values of a list of registers, stopping at the first error.
+	async fn write_register_changes(&mut self, motor_id: u8, changes: &[&RegisterChange]) -> Result<(), ConfigError<Port::Error>> {
+		for change in changes {
+			self.write_register_change(motor_id, change).await?;
+		}
+		Ok(())
+	}
+
+	/// Write the configured value of a register.
+	async fn write_register_change(&mut self, motor_id: u8, change: &RegisterChange) -> Result<(), ConfigError<Port::Error>> {
+		let size = usize::from(change.register.size);
+		// The value was validated when the configuration was resolved.
+		let data = change.register.encode_value(change.expected).unwrap_or_default();
+		debug!(
+			"writing {} to register {:?} of motor {}",
+			change.expected, change.register.name, motor_id
+		);
+		self.write_bytes(motor_id, change.register.address, &data[..size]).await?;
+		Ok(())
+	}
+}
@@ -0,0 +1,106 @@
+use super::Client;
+use super::SerialPort;
+use crate::client::BaudRate;
+use crate::control_table::{self, ControlTable};
+use crate::{ConfigureError, ReadError, TransferError};
+
+/// The highest motor ID that can be assigned to a motor.
+///
+/// ID 253 is reserved and ID 254 is the broadcast ID.
+const MAX_MOTOR_ID: u8 = 252;
+
+#[super::bisync]
+impl<Port, Buffer> Client<Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Change the ID of a motor.
+	///
+	/// The ID register is in the EEPROM area, so the change is persistent.
+	/// Before writing the new ID, this checks that torque is disabled and that no other motor replies to the new ID.
+	/// Afterwards, the motor is pinged at the new ID to verify the change.
+	///
+	/// The control table layout is determined from the model number of the motor.
+	/// For unknown models, the X-series layout is used.
+	pub async fn change_id(&mut self, motor_id: u8, new_id: u8) -> Result<(), ConfigureError<Port::Error>> {
+		if new_id > MAX_MOTOR_ID {
+			return Err(ConfigureError::InvalidId { motor_id: new_id });
+		}
+		let (model, control_table) = self.prepare_configure(motor_id).await?;
+		if new_id == motor_id {
+			return Ok(());
+		}
+		if self.is_id_in_use(new_id).await? {
+			return Err(ConfigureError::IdInUse { motor_id: new_id });
+		}
+
+		self.write(motor_id, control_table.id.address, &new_id)
+			.await
+			.map_err(ConfigureError::TransferError)?;
+		self.verify_configure(new_id, model).await
+	}
+
+	/// Change the baud rate of a motor, and switch the client to the new baud rate.
+	///
+	/// The baud rate register is in the EEPROM area, so the change is persistent.
+	/// Before writing the new baud rate, this checks that torque is disabled and that the model supports the baud rate.
+	/// Afterwards, the client switches to the new baud rate and pings the motor to verify the change.
+	///
+	/// If the verification fails, the client switches back to the original baud rate.
+	/// Note that other motors on the bus are not affected and keep using the original baud rate.
+	///
+	/// The control table layout is determined from the model number of the motor.
+	/// For unknown models, the X-series layout is used.
+	pub async fn change_baud_rate(&mut self, motor_id: u8, baud_rate: BaudRate) -> Result<(), ConfigureError<Port::Error>> {
+		let (model, control_table) = self.prepare_configure(motor_id).await?;
+		let value = control_table
+			.encode_baud_rate(baud_rate)
+			.ok_or(ConfigureError::UnsupportedBaudRate { model, baud_rate })?;
+
+		self.write(motor_id, control_table.baud_rate.address, &value)
+			.await
+			.map_err(ConfigureError::TransferError)?;
+
+		let original_baud_rate = self.baud_rate();
+		self.set_baud_rate(baud_rate.bits_per_second())
+			.map_err(ConfigureError::SetBaudRate)?;
+		let verified = self.verify_configure(motor_id, model).await;
+		if verified.is_err() {
+			self.set_baud_rate(original_baud_rate).map_err(ConfigureError::SetBaudRate)?;
+		}
+		verified
+	}
+
+	/// Look up the control table of a motor and check that torque is disabled.
+	async fn prepare_configure(&mut self, motor_id: u8) -> Result<(u16, &'static ControlTable), ConfigureError<Port::Error>> {
+		let model = self.ping(motor_id).await?.data.model;
+		let control_table = control_table::control_table_or_default(model);
+		let torque_enable = self.read::<u8>(motor_id, control_table.torque_enable.address).await?;
+		if torque_enable.data != 0 {
+			return Err(ConfigureError::TorqueEnabled { motor_id });
+		}
+		Ok((model, control_table))
+	}
+
+	/// Check if any motor replies to a ping for the given ID.
+	async fn is_id_in_use(&mut self, motor_id: u8) -> Result<bool, ConfigureError<Port::Error>> {
+		match self.ping(motor_id).await {
+			Ok(_) => Ok(true),
+			Err(TransferError::ReadError(ReadError::Io(e))) if Port::is_timeout_error(&e) => Ok(false),
+			Err(e @ TransferError::WriteError(_)) | Err(e @ TransferError::ReadError(ReadError::Io(_))) => Err(e.into()),
+			// Something replied, even if the reply was not valid.
+			Err(_) => Ok(true),
+		}
+	}
+
+	/// Verify that a motor with the expected model replies at the given ID.
+	async fn verify_configure(&mut self, motor_id: u8, model: u16) -> Result<(), ConfigureError<Port::Error>> {
+		let response = self.ping(motor_id).await.map_err(ConfigureError::VerifyFailed)?;
+		if response.data.model != model {
+			// A different motor replied, so the ID is also used by another motor at the current baud rate.
+			return Err(ConfigureError::IdInUse { motor_id });
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,209 @@
+use super::{Client, MotorGroup};
+use super::SerialPort;
+use crate::bus::Data;
+use crate::client::{Cycle, CycleBudget, LoopStats};
+use crate::TransferError;
+use alloc::vec::Vec;
+use core::ops::ControlFlow;
+use core::time::Duration;
+use std::time::Instant;
+
+/// A fixed-rate read-compute-write loop for a [`MotorGroup`].
+///
+/// Each cycle reads the states of all motors, passes them to a user function to compute new goals,
+/// and writes the goals back to the motors.
+/// Cycles start at a fixed period.
+///
+/// If a cycle takes longer than the period, it is counted as an overrun and logged as a warning.
+/// The loop does not try to catch up: the cycles that should have started during the overrun are skipped.
+///
+/// When the loop is created, the expected bus time of one cycle is computed with [`MotorGroup::cycle_budget()`].
+/// A warning is logged if the period is too short for the number of motors at the baud rate of the client.
+#[derive(Debug)]
+pub struct ControlLoop<T, S = T> {
+	group: MotorGroup<T, S>,
+	goals: Vec<T>,
+	period: Duration,
+	budget: CycleBudget,
+	next_cycle: Option<(u64, Instant)>,
+	stats: LoopStats,
+}
+
+#[super::bisync]
+impl<T, S> ControlLoop<T, S>
+where
+	T: Data + Clone + Default,
+	S: Data,
+{
+	/// Create a new control loop for a group of motors.
+	///
+	/// The goals start with the default value of `T`,
+	/// so the compute function must set the goals of all motors in the first cycle.
+	///
+	/// # Panics
+	/// This function panics if the period is zero.
+	pub fn new<Port, Buffer>(client: &Client<Port, Buffer>, group: MotorGroup<T, S>, period: Duration) -> Self
+	where
+		Port: SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		assert!(!period.is_zero(), "ControlLoop::new: the period must not be zero");
+		let budget = group.cycle_budget(client);
+		if budget.transfer_time() > period {
+			warn!(
+				"control loop period of {:?} is impossible: transferring the messages for {} motors at {} baud takes {:?}",
+				period,
+				group.motor_ids().len(),
+				client.baud_rate(),
+				budget.transfer_time()
+			);
+		} else if budget.worst_case() > period {
+			warn!(
+				"control loop period of {:?} will be missed if a motor does not reply: the response timeout is {:?}",
+				period,
+				budget.worst_case()
+			);
+		}
+		let goals = (0..group.motor_ids().len()).map(|_| T::default()).collect();
+		Self {
+			group,
+			goals,
+			period,
+			budget,
+			next_cycle: None,
+			stats: LoopStats::default(),
+		}
+	}
+
+	/// Get the motor group of the loop.
+	pub fn group(&self) -> &MotorGroup<T, S> {
+		&self.group
+	}
+
+	/// Get the period of the loop.
+	pub fn period(&self) -> Duration {
+		self.period
+	}
+
+	/// Get the expected bus time of one cycle.
+	pub fn budget(&self) -> CycleBudget {
+		self.budget
+	}
+
+	/// Get the timing statistics of the loop.
+	pub fn stats(&self) -> LoopStats {
+		self.stats
+	}
+
+	/// Restart the schedule of the loop.
+	///
+	/// The next cycle starts immediately, and the following cycles are scheduled relative to it.
+	/// Use this after pausing the loop, so the pause is not counted as an overrun.
+	pub fn reset_schedule(&mut self) {
+		self.next_cycle = None;
+	}
+
+	/// Wait for the next cycle to start and execute it.
+	///
+	/// The `compute` function is called with the cycle information, the states read from the motors,
+	/// and the goals of the previous cycle, which it should update.
+	/// If it returns [`ControlFlow::Break`], the goals are not written and the value is returned.
+	///
+	/// If reading the states or writing the goals fails, the cycle is still recorded in the [statistics](Self::stats)
+	/// as a failed cycle before the error is returned.
+	pub async fn step<Port, Buffer, B, F>(&mut self, client: &mut Client<Port, Buffer>, mut compute: F) -> Result<ControlFlow<B>, TransferError<Port::Error>>
+	where
+		Port: SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+		F: FnMut(&Cycle, &[S], &mut [T]) -> ControlFlow<B>,
+	{
+		let (index, scheduled) = match self.next_cycle {
+			Some(next_cycle) => next_cycle,
+			None => (self.stats.cycles + self.stats.skipped_cycles, Instant::now()),
+		};
+		sleep_until(scheduled).await;
+		let start = Instant::now();
+		let cycle = Cycle {
+			index,
+			scheduled,
+			late: start - scheduled,
+		};
+
+		// Schedule the next cycle before doing any I/O, so errors do not disturb the schedule.
+		self.next_cycle = Some((index + 1, scheduled + self.period));
+		let states = match self.group.read_states(client).await {
+			Ok(states) => states,
+			Err(e) => return Err(self.fail_cycle(index, scheduled, start, e)),
+		};
+		if let ControlFlow::Break(value) = compute(&cycle, states, &mut self.goals) {
+			return Ok(ControlFlow::Break(value));
+		}
+		if let Err(e) = self.group.write_goals(client, &self.goals).await {
+			return Err(self.fail_cycle(index, scheduled, start, e.into()));
+		}
+		self.finish_cycle(index, scheduled, start);
+		Ok(ControlFlow::Continue(()))
+	}
+
+	/// Run cycles until the `compute` function returns [`ControlFlow::Break`] or a transfer fails.
+	///
+	/// See [`Self::step()`] for details.
+	/// After an error, the loop can be resumed by calling this function again.
+	pub async fn run<Port, Buffer, B, F>(&mut self, client: &mut Client<Port, Buffer>, mut compute: F) -> Result<B, TransferError<Port::Error>>
+	where
+		Port: SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+		F: FnMut(&Cycle, &[S], &mut [T]) -> ControlFlow<B>,
+	{
+		loop {
+			if let ControlFlow::Break(value) = self.step(client, &mut compute).await? {
+				return Ok(value);
+			}
+		}
+	}
+
+	/// Update the statistics after a transfer of a cycle failed, and pass on the error.
+	fn fail_cycle<E>(&mut self, index: u64, scheduled: Instant, start: Instant, error: E) -> E {
+		self.stats.failed_cycles += 1;
+		self.finish_cycle(index, scheduled, start);
+		error
+	}
+
+	/// Update the statistics and skip missed cycles after a cycle finished.
+	fn finish_cycle(&mut self, index: u64, scheduled: Instant, start: Instant) {
+		let end = Instant::now();
+		let cycle_time = end - start;
+		self.stats.cycles += 1;
+		self.stats.last_cycle_time = cycle_time;
+		self.stats.max_cycle_time = self.stats.max_cycle_time.max(cycle_time);
+
+		let next = scheduled + self.period;
+		if end > next {
+			let missed = ((end - next).as_nanos() / self.period.as_nanos()) as u64 + 1;
+			self.stats.overruns += 1;
+			self.stats.skipped_cycles += missed;
+			// Saturate instead of truncating the number of missed cycles, which only matters for absurdly short periods.
+			let skipped = self.period.saturating_mul(u32::try_from(missed).unwrap_or(u32::MAX));
+			self.next_cycle = Some((index + 1 + missed, next + skipped));
+			warn!(
+				"control loop cycle {} took {:?}, overrunning the period of {:?}: skipping {} cycles",
+				index, cycle_time, self.period, missed
+			);
+		}
+	}
+}
+
+/// Sleep until the given instant.
+#[super::only_sync]
+pub(super) fn sleep_until(deadline: Instant) {
+	let now = Instant::now();
+	if deadline > now {
+		std::thread::sleep(deadline - now);
+	}
+}
+
+/// Sleep until the given instant.
+#[super::only_async]
+pub(super) async fn sleep_until(deadline: Instant) {
+	tokio::time::sleep_until(deadline.into()).await;
+}
@@ -0,0 +1,97 @@
+use super::Client;
+use super::SerialPort;
+use crate::bus::packet_id;
+use crate::client::{DiscoveredMotor, ProtocolVersion};
+use crate::DiscoverError;
+
+#[super::bisync]
+impl<Port, Buffer> Client<Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Search for Protocol 2.0 motors at each of the given baud rates.
+	///
+	/// At each baud rate, a broadcast ping is sent and every motor that replies is added to the output collection.
+	/// The collection can be any type that implements [`Default`] and [`Extend`], such as a `Vec` if the `"alloc"` feature is enabled.
+	/// Use [`STANDARD_BAUD_RATES`](crate::client::STANDARD_BAUD_RATES) to try all baud rates supported by DYNAMIXEL motors.
+	///
+	/// The original baud rate of the client is restored afterwards, even if an error occurs.
+	///
+	/// Replies that can not be decoded are skipped.
+	/// This is to be expected when other motors on the bus are configured for a different baud rate.
+	pub async fn discover<C>(&mut self, baud_rates: &[u32]) -> Result<C, DiscoverError<Port::Error>>
+	where
+		C: Default + Extend<DiscoveredMotor>,
+	{
+		self.discover_with_restore(baud_rates, false).await
+	}
+
+	/// Search for Protocol 1.0 and Protocol 2.0 motors at each of the given baud rates.
+	///
+	/// This works like [`Self::discover`], but also probes every motor ID with a Protocol 1.0 read of the model number.
+	/// Protocol 1.0 motors do not reply to a broadcast ping, so this takes much longer:
+	/// each probe waits for the response timeout of the client (see [`Self::set_response_timeout_padding`]).
+	pub async fn discover_with_protocol1<C>(&mut self, baud_rates: &[u32]) -> Result<C, DiscoverError<Port::Error>>
+	where
+		C: Default + Extend<DiscoveredMotor>,
+	{
+		self.discover_with_restore(baud_rates, true).await
+	}
+
+	/// Search for motors, and restore the original baud rate afterwards.
+	async fn discover_with_restore<C>(&mut self, baud_rates: &[u32], probe_protocol1: bool) -> Result<C, DiscoverError<Port::Error>>
+	where
+		C: Default + Extend<DiscoveredMotor>,
+	{
+		let original_baud_rate = self.baud_rate();
+		let mut output = C::default();
+		let result = self.discover_into(&mut output, baud_rates, probe_protocol1).await;
+		let restored = self.set_baud_rate(original_baud_rate).map_err(DiscoverError::SetBaudRate);
+		result?;
+		restored?;
+		Ok(output)
+	}
+
+	/// Search for motors at each baud rate and add them to the output collection.
+	async fn discover_into<C>(&mut self, output: &mut C, baud_rates: &[u32], probe_protocol1: bool) -> Result<(), DiscoverError<Port::Error>>
+	where
+		C: Extend<DiscoveredMotor>,
+	{
+		for &baud_rate in baud_rates {
+			debug!("discovering motors at {} baud", baud_rate);
+			self.set_baud_rate(baud_rate).map_err(DiscoverError::SetBaudRate)?;
+
+			{
+				let mut scan = self.scan().await?;
+				while let Some(response) = scan.scan_next().await {
+					let Ok(response) = response else {
+						continue;
+					};
+					output.extend(core::iter::once(DiscoveredMotor {
+						baud_rate,
+						protocol: ProtocolVersion::V2,
+						motor_id: response.motor_id,
+						model: response.data.model,
+						firmware: response.data.firmware,
+					}));
+				}
+			}
+
+			if probe_protocol1 {
+				for motor_id in 0..packet_id::BROADCAST {
+					if let Some(ping) = self.probe_protocol1(motor_id).await? {
+						output.extend(core::iter::once(DiscoveredMotor {
+							baud_rate,
+							protocol: ProtocolVersion::V1,
+							motor_id,
+							model: ping.model,
+							firmware: ping.firmware,
+						}));
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,31 @@
+use super::Client;
+use super::SerialPort;
+use crate::control_table::{self, x_series};
+use crate::{HardwareErrorStatus, Response, TransferError};
+
+#[super::bisync]
+impl<Port, Buffer> Client<Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Read the "Hardware Error Status" register of a motor.
+	///
+	/// This is useful to find out why a motor sets the alert bit in its responses.
+	///
+	/// The motor is pinged first to look up the address of the register in the control table of its model,
+	/// since it differs between models (for example, the XL-320 uses address 50 instead of 70).
+	/// Unknown models are assumed to use the X-series layout.
+	pub async fn read_hardware_error(&mut self, motor_id: u8) -> Result<Response<HardwareErrorStatus>, TransferError<Port::Error>> {
+		let model = self.ping(motor_id).await?.data.model;
+		let register = control_table::control_table_or_default(model)
+			.register(x_series::HARDWARE_ERROR_STATUS.name)
+			.unwrap_or(&x_series::HARDWARE_ERROR_STATUS);
+		let response = self.read::<u8>(motor_id, register.address).await?;
+		Ok(Response {
+			motor_id: response.motor_id,
+			alert: response.alert,
+			data: HardwareErrorStatus::from_bits_retain(response.data),
+		})
+	}
+}
@@ -0,0 +1,239 @@
+use super::boot::DEFAULT_BOOT_TIMEOUT;
+use super::{Client, SerialPort};
+use crate::client::{HealthEvent, HealthThresholds, MotorHealth};
+use crate::control_table::{x_series as registers, ControlTableSnapshot};
+use crate::units::x_series;
+use crate::{HardwareErrorStatus, RestoreError, TransferError, WriteError};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::ops::Range;
+use core::time::Duration;
+use std::time::Instant;
+
+/// A service that periodically checks the health of a list of X-series motors.
+///
+/// Each check reads the Hardware Error Status, Present Input Voltage and Present Temperature registers of all motors,
+/// and looks at the alert bits recorded by the client (see [`Client::alerts()`]).
+/// Changes in the health of a motor are reported as [`HealthEvent`]s to a callback.
+///
+/// Motors with a hardware error can be recovered with [`Self::recover()`],
+/// which reboots the motor and restores the configuration saved with [`Self::save_configuration()`].
+#[derive(Debug)]
+pub struct HealthMonitor {
+	motor_ids: Vec<u8>,
+	thresholds: HealthThresholds,
+	interval: Duration,
+	boot_timeout: Duration,
+	health: Vec<Option<MotorHealth>>,
+	configurations: BTreeMap<u8, ControlTableSnapshot>,
+	last_check: Option<Instant>,
+}
+
+#[super::bisync]
+impl HealthMonitor {
+	/// Create a new health monitor that checks a list of motors every `interval`.
+	pub fn new(motor_ids: &[u8], thresholds: HealthThresholds, interval: Duration) -> Self {
+		Self {
+			motor_ids: motor_ids.to_vec(),
+			thresholds,
+			interval,
+			boot_timeout: DEFAULT_BOOT_TIMEOUT,
+			health: alloc::vec![None; motor_ids.len()],
+			configurations: BTreeMap::new(),
+			last_check: None,
+		}
+	}
+
+	/// Set how long [`Self::recover()`] waits for a motor to reply to a ping after a reboot.
+	///
+	/// The default is one second.
+	pub fn with_boot_timeout(mut self, boot_timeout: Duration) -> Self {
+		self.boot_timeout = boot_timeout;
+		self
+	}
+
+	/// Get the IDs of the monitored motors.
+	pub fn motor_ids(&self) -> &[u8] {
+		&self.motor_ids
+	}
+
+	/// Get the thresholds of the monitor.
+	pub fn thresholds(&self) -> HealthThresholds {
+		self.thresholds
+	}
+
+	/// Get the health of a motor from the last check.
+	///
+	/// Returns `None` if the motor is not monitored or has not been checked yet.
+	pub fn health(&self, motor_id: u8) -> Option<MotorHealth> {
+		let index = self.motor_ids.iter().position(|&id| id == motor_id)?;
+		self.health[index]
+	}
+
+	/// Check the health of all motors if the interval has passed since the last check.
+	///
+	/// Returns `true` if a check was performed.
+	/// Call this regularly, for example once per cycle of a control loop.
+	pub async fn poll<Port, Buffer, F>(&mut self, client: &mut Client<Port, Buffer>, on_event: F) -> Result<bool, WriteError<Port::Error>>
+	where
+		Port: SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+		F: FnMut(HealthEvent),
+	{
+		if self.last_check.is_some_and(|last_check| last_check.elapsed() < self.interval) {
+			return Ok(false);
+		}
+		self.check(client, on_event).await?;
+		Ok(true)
+	}
+
+	/// Check the health of all motors now.
+	///
+	/// Motors that fail to reply are reported as unreachable.
+	/// An error is only returned if an instruction could not be written to the bus.
+	pub async fn check<Port, Buffer, F>(&mut self, client: &mut Client<Port, Buffer>, mut on_event: F) -> Result<(), WriteError<Port::Error>>
+	where
+		Port: SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+		F: FnMut(HealthEvent),
+	{
+		self.last_check = Some(Instant::now());
+		for index in 0..self.motor_ids.len() {
+			let motor_id = self.motor_ids[index];
+			let previous = self.health[index];
+			let mut health = match Self::read_health(client, motor_id).await {
+				Ok(health) => health,
+				Err(TransferError::WriteError(e)) => return Err(e),
+				Err(TransferError::ReadError(_)) => {
+					debug!("motor {} did not reply to the health check", motor_id);
+					MotorHealth {
+						reachable: false,
+						..previous.unwrap_or_default()
+					}
+				},
+			};
+			// Take the alert after reading, so the alert bits of the replies to this check are included.
+			health.alert = client.take_alert(motor_id);
+			self.health[index] = Some(health);
+			self.report_changes(motor_id, previous, health, &mut on_event);
+		}
+		Ok(())
+	}
+
+	/// Save the configuration of a motor, to be restored by [`Self::recover()`].
+	///
+	/// The registers in `registers` are read from the motor.
+	/// Call this while the motor is healthy and fully configured,
+	/// for example with the address range of the RAM area of the control table.
+	pub async fn save_configuration<Port, Buffer>(
+		&mut self,
+		client: &mut Client<Port, Buffer>,
+		motor_id: u8,
+		registers: Range<u16>,
+	) -> Result<(), TransferError<Port::Error>>
+	where
+		Port: SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		let snapshot = client.dump_control_table(motor_id, registers).await?;
+		self.configurations.insert(motor_id, snapshot);
+		Ok(())
+	}
+
+	/// Reboot a motor to clear its hardware error, and restore its saved configuration.
+	///
+	/// After the reboot, the motor is pinged until it replies or the boot timeout expires.
+	/// Then the configuration saved with [`Self::save_configuration()`] is written back, if there is one.
+	///
+	/// Unlike [`Client::reboot_and_restore()`], this restores the configuration from before the hardware error,
+	/// so the torque is enabled again if it was enabled when the configuration was saved.
+	/// On success, a [`HealthEvent::Recovered`] event is reported and the health of the motor is reset until the next check.
+	pub async fn recover<Port, Buffer, F>(
+		&mut self,
+		client: &mut Client<Port, Buffer>,
+		motor_id: u8,
+		mut on_event: F,
+	) -> Result<(), RestoreError<Port::Error>>
+	where
+		Port: SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+		F: FnMut(HealthEvent),
+	{
+		client.reboot(motor_id).await?;
+		client.wait_for_boot(motor_id, self.boot_timeout).await?;
+		if let Some(snapshot) = self.configurations.get(&motor_id) {
+			client.restore_control_table_snapshot(motor_id, snapshot).await?;
+		}
+		// The reboot cleared the hardware error, so forget the alerts recorded before it.
+		client.take_alert(motor_id);
+		if let Some(index) = self.motor_ids.iter().position(|&id| id == motor_id) {
+			self.health[index] = None;
+		}
+		on_event(HealthEvent::Recovered { motor_id });
+		Ok(())
+	}
+
+	/// Read the hardware error status, temperature and input voltage of a motor.
+	async fn read_health<Port, Buffer>(client: &mut Client<Port, Buffer>, motor_id: u8) -> Result<MotorHealth, TransferError<Port::Error>>
+	where
+		Port: SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		// Read the register directly instead of with `read_hardware_error()`, which pings the motor to look up its model first.
+		let hardware_error = client.read::<u8>(motor_id, registers::HARDWARE_ERROR_STATUS.address).await?.data;
+		let hardware_error = HardwareErrorStatus::from_bits_retain(hardware_error);
+		let voltage = client.read_register(motor_id, x_series::PRESENT_INPUT_VOLTAGE).await?.data;
+		let temperature = client.read_register(motor_id, x_series::PRESENT_TEMPERATURE).await?.data;
+		Ok(MotorHealth {
+			reachable: true,
+			alert: false,
+			hardware_error,
+			temperature,
+			voltage,
+		})
+	}
+
+	/// Report the conditions that started between two checks of a motor.
+	///
+	/// A motor that was not reachable or not checked before is compared against a healthy motor.
+	fn report_changes<F>(&self, motor_id: u8, previous: Option<MotorHealth>, health: MotorHealth, on_event: &mut F)
+	where
+		F: FnMut(HealthEvent),
+	{
+		if !health.reachable {
+			if previous.is_none_or(|previous| previous.reachable) {
+				on_event(HealthEvent::Unreachable { motor_id });
+			}
+			return;
+		}
+
+		let previous = previous.filter(|previous| previous.reachable);
+		let started = |condition: &dyn Fn(&MotorHealth) -> bool| condition(&health) && !previous.as_ref().is_some_and(condition);
+		if started(&|health| health.alert) {
+			on_event(HealthEvent::Alert { motor_id });
+		}
+		let previous_error = previous.map(|previous| previous.hardware_error).unwrap_or_default();
+		if health.hardware_error != previous_error {
+			if health.hardware_error.is_empty() {
+				on_event(HealthEvent::HardwareErrorCleared { motor_id });
+			} else {
+				on_event(HealthEvent::HardwareError {
+					motor_id,
+					status: health.hardware_error,
+				});
+			}
+		}
+		if started(&|health| health.temperature > self.thresholds.max_temperature) {
+			on_event(HealthEvent::Overheating {
+				motor_id,
+				temperature: health.temperature,
+			});
+		}
+		if started(&|health| health.voltage < self.thresholds.min_voltage || health.voltage > self.thresholds.max_voltage) {
+			on_event(HealthEvent::VoltageOutOfRange {
+				motor_id,
+				voltage: health.voltage,
+			});
+		}
+	}
+}
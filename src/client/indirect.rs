@@ -0,0 +1,32 @@
+use super::Client;
+use super::SerialPort;
+use crate::bus::endian::write_u16_le;
+use crate::control_table::IndirectLayout;
+use crate::{Response, TransferError};
+
+#[super::bisync]
+impl<Port, Buffer> Client<Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Write the indirect addresses of an [`IndirectLayout`] to a motor.
+	///
+	/// Afterwards, the mapped registers can be read or written at [`IndirectLayout::address()`] with a single instruction,
+	/// for example with [`Self::sync_read_bytes()`] for multiple motors.
+	/// Use [`IndirectLayout::get()`] to decode the values from the read data.
+	///
+	/// Depending on the firmware, the indirect addresses can only be changed while the torque of the motor is disabled.
+	///
+	/// You may specify [`crate::bus::packet_id::BROADCAST`] as motor ID to configure all motors at once.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	pub async fn write_indirect_mapping(&mut self, motor_id: u8, layout: &IndirectLayout) -> Result<Response<()>, TransferError<Port::Error>> {
+		let mut data = [0; 40];
+		let mut size = 0;
+		for address in layout.indirect_addresses() {
+			write_u16_le(&mut data[size..], address);
+			size += 2;
+		}
+		self.write_bytes(motor_id, layout.block().addresses().address, &data[..size]).await
+	}
+}
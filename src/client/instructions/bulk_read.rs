@@ -52,6 +52,7 @@ where
 		reads: &'a [BulkReadData],
 	) -> Result<BulkReadBytes<'a, T, Port, Buffer>, WriteError<Port::Error>>
 	where
+		T: ?Sized,
 		[u8]: core::borrow::Borrow<T>,
 	{
 		write_bulk_read_instruction(self, reads).await?;
@@ -63,6 +64,36 @@ where
 			data: PhantomData,
 		})
 	}
+
+	/// Read arbitrary data ranges from multiple motors and collect all replies.
+	///
+	/// The result for each motor is inserted in the output collection, keyed by motor ID.
+	/// The collection can be a [`MotorMap`](crate::client::MotorMap), or a `BTreeMap` or `Vec` if the `"alloc"` feature is enabled.
+	///
+	/// Only errors writing the instruction are returned directly.
+	/// Errors reading the reply of a motor are stored in the output collection.
+	///
+	/// # Panics
+	/// The protocol forbids specifying the same motor ID multiple times.
+	/// This function panics if the same motor ID is used for more than one read.
+	///
+	/// This also panics if the output collection panics when extended,
+	/// for example when a [`MotorMap`](crate::client::MotorMap) has less capacity than the number of reads.
+	pub async fn bulk_read_all<T, C>(&mut self, reads: &[BulkReadData]) -> Result<C, WriteError<Port::Error>>
+	where
+		T: for<'b> From<&'b [u8]>,
+		C: Default + Extend<(u8, Result<Response<T>, ReadError<Port::Error>>)>,
+	{
+		let mut bulk_read = self.bulk_read_bytes::<T>(reads).await?;
+		let mut output = C::default();
+		// Read until the bulk read returns `None`, so it knows all responses have been handled.
+		let mut reads = reads.iter();
+		while let Some(result) = bulk_read.read_next().await {
+			let Some(read) = reads.next() else { break };
+			output.extend(core::iter::once((read.motor_id, result)));
+		}
+		Ok(output)
+	}
 }
 
 /// Write a bulk read instruction to a bus.
@@ -103,17 +134,21 @@ where
 				Ok(())
 			},
 		)
-		.await
+		.await?;
+	let timeout = reads.iter().map(|read| client.motor_response_timeout(read.motor_id, read.count)).sum();
+	client.set_pending_responses(reads.len(), timeout);
+	Ok(())
 }
 
 /// A bulk read operation that returns the unparsed bytes from each motor, one reply at a time.
 ///
-/// The replies must be fully consumed before the client is used again. The synchronous client is an
-/// [`Iterator`] and drains any unread replies on drop; the asynchronous client cannot (a [`Drop`] can't
-/// `.await`), so call [`read_next`](Self::read_next) until it returns [`None`] — dropping it early
-/// corrupts the next transaction.
+/// The synchronous client is an [`Iterator`] and drains any unread replies on drop. The asynchronous
+/// client cannot (a [`Drop`] can't `.await`), so call [`read_next`](Self::read_next) until it returns
+/// [`None`]. If it is dropped early anyway, for example in a cancelled `tokio::select!` branch, the
+/// client discards the remaining replies before sending the next instruction.
 pub struct BulkReadBytes<'a, T, Port, Buffer>
 where
+	T: ?Sized,
 	Port: SerialPort,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
@@ -125,6 +160,7 @@ where
 
 impl<T, Port, Buffer> core::fmt::Debug for BulkReadBytes<'_, T, Port, Buffer>
 where
+	T: ?Sized,
 	Port: SerialPort + core::fmt::Debug,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
@@ -144,6 +180,7 @@ where
 #[super::bisync]
 impl<T, Port, Buffer> BulkReadBytes<'_, T, Port, Buffer>
 where
+	T: ?Sized,
 	Port: SerialPort,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
@@ -153,9 +190,13 @@ where
 	}
 
 	fn pop_bulk_read_data(&mut self) -> Option<BulkReadData> {
-		let data = self.bulk_read_data.get(self.index)?;
+		let Some(&data) = self.bulk_read_data.get(self.index) else {
+			// Every response has been read or timed out, so there is nothing left to discard.
+			self.client.clear_pending_responses();
+			return None;
+		};
 		self.index += 1;
-		Some(*data)
+		Some(data)
 	}
 
 	/// Read the next motor reply, or [`None`] once every motor has replied.
@@ -180,7 +221,8 @@ where
 	where
 		T: for<'b> From<&'b [u8]>,
 	{
-		let response = self.client.read_status_response(count).await?;
+		let timeout = self.client.motor_response_timeout(motor_id, count);
+		let response = self.client.read_status_response_timeout(timeout).await?;
 		// TODO: Allow a response from a motor later in the list (meaning we missed an earlier motor response).
 		// We need to report a timeout or something for the missed motor though.
 		crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
@@ -192,7 +234,8 @@ where
 	where
 		[u8]: core::borrow::Borrow<T>,
 	{
-		let response = self.client.read_status_response(count).await?;
+		let timeout = self.client.motor_response_timeout(motor_id, count);
+		let response = self.client.read_status_response_timeout(timeout).await?;
 		// TODO: Allow a response from a motor later in the list (meaning we missed an earlier motor response).
 		// We need to report a timeout or something for the missed motor though.
 		crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
@@ -203,7 +246,7 @@ where
 
 // `Iterator` and `Drop` are synchronous-only: `Iterator::next` cannot `.await`, and `Drop` cannot
 // drain the bus asynchronously. The async client uses `read_next().await` and relies on the next
-// `write_instruction` discarding any unread responses.
+// `write_instruction` discarding any pending responses.
 #[super::only_sync]
 impl<T, Port, Buffer> Iterator for BulkReadBytes<'_, T, Port, Buffer>
 where
@@ -225,12 +268,14 @@ where
 #[super::only_sync]
 impl<T, Port, Buffer> Drop for BulkReadBytes<'_, T, Port, Buffer>
 where
+	T: ?Sized,
 	Port: SerialPort,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
 	fn drop(&mut self) {
-		for data in &self.bulk_read_data[self.index..] {
-			self.client.read_status_response(data.count).ok();
+		while let Some(data) = self.pop_bulk_read_data() {
+			let timeout = self.client.motor_response_timeout(data.motor_id, data.count);
+			self.client.read_status_response_timeout(timeout).ok();
 		}
 	}
 }
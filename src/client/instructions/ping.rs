@@ -1,8 +1,9 @@
 use super::Client;
 use super::SerialPort;
-use crate::bus::instruction_id;
+use crate::bus::{instruction_id, StatusPacket};
 use crate::client::Ping;
 use crate::{Response, TransferError};
+use core::time::Duration;
 
 #[super::bisync]
 impl<Port, Buffer> Client<Port, Buffer>
@@ -19,23 +20,71 @@ where
 		Ok(response.try_into()?)
 	}
 
+	/// Ping a specific motor by ID, with a custom timeout for the response.
+	///
+	/// The timeout replaces the automatically calculated timeout, including the response timeout padding.
+	pub async fn ping_timeout(&mut self, motor_id: u8, timeout: Duration) -> Result<Response<Ping>, TransferError<Port::Error>> {
+		let response = self
+			.transfer_single_timeout(motor_id, instruction_id::PING, 0, 3, Some(timeout), |_| Ok(()))
+			.await?;
+		Ok(response.try_into()?)
+	}
+
 	/// Scan the bus for motors with a broadcast ping.
 	///
 	/// See [`Scan`] for how to consume the per-motor replies.
 	pub async fn scan<'a>(&'a mut self) -> Result<Scan<'a, Port, Buffer>, crate::WriteError<Port::Error>> {
 		self.write_instruction(crate::bus::packet_id::BROADCAST, instruction_id::PING, 0, |_| Ok(()))
 			.await?;
+		// Every motor ID except the broadcast ID could reply.
+		self.set_pending_responses(MAX_SCAN_RESPONSES, scan_timeout(self.baud_rate()));
 		Ok(Scan { client: self })
 	}
+
+	/// Scan the bus for motors with a broadcast ping and collect all replies.
+	///
+	/// The reply of each motor is inserted in the output collection, keyed by motor ID.
+	/// The collection can be a [`MotorMap`](crate::client::MotorMap), or a `BTreeMap` or `Vec` if the `"alloc"` feature is enabled.
+	///
+	/// Replies that can not be decoded can not be attributed to a motor, so they are skipped.
+	///
+	/// # Panics
+	/// This panics if the output collection panics when extended,
+	/// for example when a [`MotorMap`](crate::client::MotorMap) does not have enough capacity for all replies.
+	pub async fn scan_all<C>(&mut self) -> Result<C, crate::WriteError<Port::Error>>
+	where
+		C: Default + Extend<(u8, Response<Ping>)>,
+	{
+		let mut scan = self.scan().await?;
+		let mut output = C::default();
+		while let Some(result) = scan.scan_next().await {
+			match result {
+				Ok(response) => output.extend(core::iter::once((response.motor_id, response))),
+				Err(_) => warn!("skipping invalid reply to broadcast ping"),
+			}
+		}
+		Ok(output)
+	}
+}
+
+/// The maximum number of motors that can reply to a broadcast ping.
+const MAX_SCAN_RESPONSES: usize = 253;
+
+/// The timeout for the next reply to a broadcast ping.
+///
+/// Motors delay their reply based on their ID, so we have to allow for the maximum number of replies.
+fn scan_timeout(baud_rate: u32) -> core::time::Duration {
+	let response_time = crate::bus::message_transfer_time(14, baud_rate);
+	response_time * MAX_SCAN_RESPONSES as u32 + core::time::Duration::from_millis(34)
 }
 
 /// A scan operation that yields a [`Response`] for each motor that replies to the broadcast ping.
 ///
-/// Scanning ends when no further reply arrives within the timeout. The replies must be fully consumed
-/// before the client is used again. The synchronous client is an [`Iterator`] and drains any unread
-/// replies on drop; the asynchronous client cannot (a [`Drop`] can't `.await`), so call
-/// [`scan_next`](Self::scan_next) until it returns [`None`] — dropping it early corrupts the next
-/// transaction.
+/// Scanning ends when no further reply arrives within the timeout. The synchronous client is an
+/// [`Iterator`] and drains any unread replies on drop. The asynchronous client cannot (a [`Drop`]
+/// can't `.await`), so call [`scan_next`](Self::scan_next) until it returns [`None`]. If it is
+/// dropped early anyway, the client discards the remaining replies before sending the next
+/// instruction.
 #[super::bisync]
 pub struct Scan<'a, Port, Buffer = crate::bus::DefaultBuffer>
 where
@@ -63,6 +112,10 @@ where
 {
 	/// Scan for the next motor reply, or [`None`] once no further reply arrives within the timeout.
 	pub async fn scan_next(&mut self) -> Option<Result<Response<Ping>, crate::ReadError<Port::Error>>> {
+		// Do not wait for another timeout once the scan has ended.
+		if !self.client.has_pending_responses() {
+			return None;
+		}
 		let response = self.next_response().await;
 		match response {
 			Ok(response) => Some(Ok(response)),
@@ -74,11 +127,29 @@ where
 		}
 	}
 
-	async fn next_response(&mut self) -> Result<Response<Ping>, crate::ReadError<Port::Error>> {
-		let response_time = crate::bus::message_transfer_time(14, self.client.baud_rate());
-		let timeout = response_time * 253 + core::time::Duration::from_millis(34);
+	/// Read the next reply as a raw status packet, or [`None`] once no further reply arrives within the timeout.
+	///
+	/// Unlike [`Self::scan_next`], the reply is not decoded, and a reply that reports an error is returned as is.
+	/// This is useful to forward the replies to another bus.
+	pub async fn scan_next_packet(&mut self) -> Option<Result<StatusPacket<'_>, crate::ReadError<Port::Error>>> {
+		if !self.client.has_pending_responses() {
+			return None;
+		}
+		let timeout = scan_timeout(self.client.baud_rate());
+		match self.client.read_last_status_packet_timeout(timeout).await {
+			Ok(status) => Some(Ok(status)),
+			Err(crate::ReadError::Io(e)) if Port::is_timeout_error(&e) => {
+				trace!("Ping response timed out.");
+				None
+			},
+			Err(e) => Some(Err(e)),
+		}
+	}
 
-		let response = self.client.read_status_response_timeout(timeout).await?;
+	async fn next_response(&mut self) -> Result<Response<Ping>, crate::ReadError<Port::Error>> {
+		let timeout = scan_timeout(self.client.baud_rate());
+		let response = self.client.read_last_status_packet_timeout(timeout).await?;
+		crate::MotorError::check(response.error())?;
 		Ok(response.try_into()?)
 	}
 }
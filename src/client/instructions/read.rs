@@ -4,6 +4,7 @@ use crate::bus::endian::write_u16_le;
 use crate::bus::instruction_id;
 use crate::bus::{Data, StatusPacket};
 use crate::{Response, TransferError};
+use core::time::Duration;
 
 #[super::bisync]
 impl<SerialPort, Buffer> Client<SerialPort, Buffer>
@@ -11,9 +12,15 @@ where
 	SerialPort: super::SerialPort,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
-	async fn read_raw(&mut self, motor_id: u8, address: u16, count: u16) -> Result<StatusPacket<'_>, TransferError<SerialPort::Error>> {
+	async fn read_raw(
+		&mut self,
+		motor_id: u8,
+		address: u16,
+		count: u16,
+		timeout: Option<Duration>,
+	) -> Result<StatusPacket<'_>, TransferError<SerialPort::Error>> {
 		let response = self
-			.transfer_single(motor_id, instruction_id::READ, 4, count, |buffer| {
+			.transfer_single_timeout(motor_id, instruction_id::READ, 4, count, timeout, |buffer| {
 				write_u16_le(&mut buffer[0..], address);
 				write_u16_le(&mut buffer[2..], count);
 				Ok(())
@@ -36,7 +43,7 @@ where
 	where
 		T: From<&'a [u8]>,
 	{
-		let status = self.read_raw(motor_id, address, count).await?;
+		let status = self.read_raw(motor_id, address, count, None).await?;
 		Ok(decode_status_packet_bytes(status)?)
 	}
 
@@ -50,7 +57,19 @@ where
 	where
 		T: Data,
 	{
-		let status = self.read_raw(motor_id, address, T::ENCODED_SIZE).await?;
+		let status = self.read_raw(motor_id, address, T::ENCODED_SIZE, None).await?;
+		Ok(decode_status_packet(status)?)
+	}
+
+	/// Read a value from a specific motor, with a custom timeout for the response.
+	///
+	/// The timeout replaces the automatically calculated timeout, including the response timeout padding.
+	/// Use this for a single slow transfer, or see [`Self::set_motor_response_timeout_padding`] to change the timeout for all transfers with a motor.
+	pub async fn read_timeout<T>(&mut self, motor_id: u8, address: u16, timeout: Duration) -> Result<Response<T>, TransferError<SerialPort::Error>>
+	where
+		T: Data,
+	{
+		let status = self.read_raw(motor_id, address, T::ENCODED_SIZE, Some(timeout)).await?;
 		Ok(decode_status_packet(status)?)
 	}
 }
@@ -1,6 +1,7 @@
 #![allow(unused_imports)]
 
 use core::marker::PhantomData;
+use core::time::Duration;
 
 use super::Client;
 use super::SerialPort;
@@ -35,6 +36,7 @@ where
 			Ok(())
 		})
 		.await?;
+		self.set_pending_responses(motor_ids.len(), self.sync_read_timeout_total(motor_ids, count, None));
 
 		Ok(SyncReadBytes {
 			client: self,
@@ -62,6 +64,7 @@ where
 			Ok(())
 		})
 		.await?;
+		self.set_pending_responses(motor_ids.len(), self.sync_read_timeout_total(motor_ids, count, None));
 
 		Ok(SyncReadBytes {
 			client: self,
@@ -79,6 +82,28 @@ where
 		&'a mut self,
 		motor_ids: &'a [u8],
 		address: u16,
+	) -> Result<SyncRead<'a, T, Port, Buffer>, WriteError<Port::Error>> {
+		self.sync_read_with_timeout(motor_ids, address, None).await
+	}
+
+	/// Read values from multiple motors in one command, with a custom timeout for each response.
+	///
+	/// The timeout replaces the automatically calculated timeout of each response, including the response timeout padding.
+	/// See [`SyncRead`] for how to consume the per-motor replies.
+	pub async fn sync_read_timeout<'a, T: Data>(
+		&'a mut self,
+		motor_ids: &'a [u8],
+		address: u16,
+		timeout: Duration,
+	) -> Result<SyncRead<'a, T, Port, Buffer>, WriteError<Port::Error>> {
+		self.sync_read_with_timeout(motor_ids, address, Some(timeout)).await
+	}
+
+	async fn sync_read_with_timeout<'a, T: Data>(
+		&'a mut self,
+		motor_ids: &'a [u8],
+		address: u16,
+		timeout: Option<Duration>,
 	) -> Result<SyncRead<'a, T, Port, Buffer>, WriteError<Port::Error>> {
 		let count = T::ENCODED_SIZE;
 		self.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_READ, 4 + motor_ids.len(), |buffer| {
@@ -88,22 +113,59 @@ where
 			Ok(())
 		})
 		.await?;
+		self.set_pending_responses(motor_ids.len(), self.sync_read_timeout_total(motor_ids, count, timeout));
 
 		Ok(SyncRead {
 			client: self,
 			motor_ids,
 			index: 0,
+			timeout,
 			data: PhantomData,
 		})
 	}
+
+	/// Get the total timeout for the responses to a sync read.
+	fn sync_read_timeout_total(&self, motor_ids: &[u8], count: u16, timeout: Option<Duration>) -> Duration {
+		motor_ids
+			.iter()
+			.map(|&motor_id| timeout.unwrap_or_else(|| self.motor_response_timeout(motor_id, count)))
+			.sum()
+	}
+
+	/// Read values from multiple motors in one command and collect all replies.
+	///
+	/// The result for each motor is inserted in the output collection, keyed by motor ID.
+	/// The collection can be a [`MotorMap`](crate::client::MotorMap), or a `BTreeMap` or `Vec` if the `"alloc"` feature is enabled.
+	///
+	/// Only errors writing the instruction are returned directly.
+	/// Errors reading the reply of a motor are stored in the output collection.
+	///
+	/// # Panics
+	/// This panics if the output collection panics when extended,
+	/// for example when a [`MotorMap`](crate::client::MotorMap) has less capacity than the number of motors.
+	pub async fn sync_read_all<T, C>(&mut self, motor_ids: &[u8], address: u16) -> Result<C, WriteError<Port::Error>>
+	where
+		T: Data,
+		C: Default + Extend<(u8, Result<Response<T>, ReadError<Port::Error>>)>,
+	{
+		let mut sync_read = self.sync_read::<T>(motor_ids, address).await?;
+		let mut output = C::default();
+		// Read until the sync read returns `None`, so it knows all responses have been handled.
+		let mut motor_ids = motor_ids.iter();
+		while let Some(result) = sync_read.read_next().await {
+			let Some(&motor_id) = motor_ids.next() else { break };
+			output.extend(core::iter::once((motor_id, result)));
+		}
+		Ok(output)
+	}
 }
 
 /// A sync read operation that returns the unparsed bytes from each motor, one reply at a time.
 ///
-/// The replies must be fully consumed before the client is used again. The synchronous client is an
-/// [`Iterator`] and drains any unread replies on drop; the asynchronous client cannot (a [`Drop`] can't
-/// `.await`), so call [`read_next`](Self::read_next) until it returns [`None`] — dropping it early
-/// corrupts the next transaction.
+/// The synchronous client is an [`Iterator`] and drains any unread replies on drop. The asynchronous
+/// client cannot (a [`Drop`] can't `.await`), so call [`read_next`](Self::read_next) until it returns
+/// [`None`]. If it is dropped early anyway, for example in a cancelled `tokio::select!` branch, the
+/// client discards the remaining replies before sending the next instruction.
 #[super::bisync]
 pub struct SyncReadBytes<'a, T, Port, Buffer = crate::bus::DefaultBuffer>
 where
@@ -120,10 +182,10 @@ where
 
 /// A sync read operation that returns the parsed value from each motor, one reply at a time.
 ///
-/// The replies must be fully consumed before the client is used again. The synchronous client is an
-/// [`Iterator`] and drains any unread replies on drop; the asynchronous client cannot (a [`Drop`] can't
-/// `.await`), so call [`read_next`](Self::read_next) until it returns [`None`] — dropping it early
-/// corrupts the next transaction.
+/// The synchronous client is an [`Iterator`] and drains any unread replies on drop. The asynchronous
+/// client cannot (a [`Drop`] can't `.await`), so call [`read_next`](Self::read_next) until it returns
+/// [`None`]. If it is dropped early anyway, for example in a cancelled `tokio::select!` branch, the
+/// client discards the remaining replies before sending the next instruction.
 #[super::bisync]
 pub struct SyncRead<'a, T, Port, Buffer = crate::bus::DefaultBuffer>
 where
@@ -134,6 +196,7 @@ where
 	client: &'a mut Client<Port, Buffer>,
 	motor_ids: &'a [u8],
 	index: usize,
+	timeout: Option<Duration>,
 	data: PhantomData<fn() -> T>,
 }
 
@@ -186,9 +249,13 @@ where
 	}
 
 	fn pop_motor_id(&mut self) -> Option<u8> {
-		let motor_id = self.motor_ids.get(self.index)?;
+		let Some(&motor_id) = self.motor_ids.get(self.index) else {
+			// Every response has been read or timed out, so there is nothing left to discard.
+			self.client.clear_pending_responses();
+			return None;
+		};
 		self.index += 1;
-		Some(*motor_id)
+		Some(motor_id)
 	}
 
 	/// Read the next motor reply, or [`None`] once every motor has replied.
@@ -213,7 +280,8 @@ where
 	where
 		T: From<&'a [u8]>,
 	{
-		let response = self.client.read_status_response(self.count).await?;
+		let timeout = self.client.motor_response_timeout(motor_id, self.count);
+		let response = self.client.read_status_response_timeout(timeout).await?;
 		// TODO: Allow a response from a motor later in the list (meaning we missed an earlier motor response).
 		// We need to report a timeout or something for the missed motor though.
 		crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
@@ -225,7 +293,8 @@ where
 	where
 		[u8]: core::borrow::Borrow<T>,
 	{
-		let response = self.client.read_status_response(self.count).await?;
+		let timeout = self.client.motor_response_timeout(motor_id, self.count);
+		let response = self.client.read_status_response_timeout(timeout).await?;
 		// TODO: Allow a response from a motor later in the list (meaning we missed an earlier motor response).
 		// We need to report a timeout or something for the missed motor though.
 		crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
@@ -247,9 +316,13 @@ where
 	}
 
 	fn pop_motor_id(&mut self) -> Option<u8> {
-		let motor_id = self.motor_ids.get(self.index)?;
+		let Some(&motor_id) = self.motor_ids.get(self.index) else {
+			// Every response has been read or timed out, so there is nothing left to discard.
+			self.client.clear_pending_responses();
+			return None;
+		};
 		self.index += 1;
-		Some(*motor_id)
+		Some(motor_id)
 	}
 
 	/// Read the next motor reply, or [`None`] once every motor has replied.
@@ -259,7 +332,10 @@ where
 	}
 
 	async fn next_response(&mut self, motor_id: u8) -> Result<Response<T>, ReadError<Port::Error>> {
-		let response = self.client.read_status_response(T::ENCODED_SIZE).await?;
+		let timeout = self
+			.timeout
+			.unwrap_or_else(|| self.client.motor_response_timeout(motor_id, T::ENCODED_SIZE));
+		let response = self.client.read_status_response_timeout(timeout).await?;
 		// TODO: Allow a response from a motor later in the list (meaning we missed an earlier motor response).
 		// We need to report a timeout or something for the missed motor though.
 		crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
@@ -270,7 +346,7 @@ where
 
 // `Iterator` and `Drop` are synchronous-only: `Iterator::next` cannot `.await`, and `Drop` cannot
 // drain the bus asynchronously. The async client uses `read_next().await` and relies on the next
-// `write_instruction` discarding any unread responses.
+// `write_instruction` discarding any pending responses.
 #[super::only_sync]
 impl<T, Port, Buffer> Iterator for SyncReadBytes<'_, T, Port, Buffer>
 where
@@ -297,8 +373,9 @@ where
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
 	fn drop(&mut self) {
-		while self.pop_motor_id().is_some() {
-			self.client.read_status_response(self.count).ok();
+		while let Some(motor_id) = self.pop_motor_id() {
+			let timeout = self.client.motor_response_timeout(motor_id, self.count);
+			self.client.read_status_response_timeout(timeout).ok();
 		}
 	}
 }
@@ -3,6 +3,7 @@ use crate::bus::endian::write_u16_le;
 use crate::bus::instruction_id;
 use crate::bus::Data;
 use crate::{Response, TransferError};
+use core::time::Duration;
 
 #[super::bisync]
 impl<SerialPort, Buffer> Client<SerialPort, Buffer>
@@ -10,18 +11,42 @@ where
 	SerialPort: super::SerialPort,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
-	/// Write value to a specific motor.
-	///
-	/// You may specify [`crate::bus::packet_id::BROADCAST`] as motor ID.
-	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
-	pub async fn write<T: Data>(&mut self, motor_id: u8, address: u16, data: &T) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+	async fn write_raw<T: Data>(
+		&mut self,
+		motor_id: u8,
+		address: u16,
+		data: &T,
+		timeout: Option<Duration>,
+	) -> Result<Response<()>, TransferError<SerialPort::Error>> {
 		self.write_instruction(motor_id, instruction_id::WRITE, 2 + T::ENCODED_SIZE as usize, |buffer| {
 			write_u16_le(&mut buffer[0..], address);
 			data.encode(&mut buffer[2..])?;
 			Ok(())
 		})
 		.await?;
-		Ok(self.read_response_if_not_broadcast(motor_id).await?)
+		Ok(self.read_response_if_not_broadcast_timeout(motor_id, timeout).await?)
+	}
+
+	/// Write value to a specific motor.
+	///
+	/// You may specify [`crate::bus::packet_id::BROADCAST`] as motor ID.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	pub async fn write<T: Data>(&mut self, motor_id: u8, address: u16, data: &T) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_raw(motor_id, address, data, None).await
+	}
+
+	/// Write value to a specific motor, with a custom timeout for the response.
+	///
+	/// The timeout replaces the automatically calculated timeout, including the response timeout padding.
+	/// Use this for a single slow transfer, or see [`Self::set_motor_response_timeout_padding`] to change the timeout for all transfers with a motor.
+	pub async fn write_timeout<T: Data>(
+		&mut self,
+		motor_id: u8,
+		address: u16,
+		data: &T,
+		timeout: Duration,
+	) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_raw(motor_id, address, data, Some(timeout)).await
 	}
 
 	/// Write an arbitrary amount of bytes to a specific motor.
@@ -8,9 +8,43 @@ pub(crate) mod asynch {
 	use crate::AsyncSerialPort as SerialPort;
 	use bisync::asynchronous::*;
 
+	#[cfg(feature = "serial2-tokio")]
+	mod boot;
+	#[cfg(feature = "std")]
+	mod calibrate;
 	mod client;
 	pub use client::Client;
+	#[cfg(feature = "serial2-tokio")]
+	mod control_loop;
+	#[cfg(feature = "serial2-tokio")]
+	pub use control_loop::ControlLoop;
+	mod motor;
+	pub use motor::Motor;
+	#[cfg(feature = "alloc")]
+	mod motor_group;
+	#[cfg(feature = "alloc")]
+	pub use motor_group::MotorGroup;
+	#[cfg(feature = "config")]
+	mod config;
+	mod configure;
+	mod discover;
+	mod hardware_error;
+	#[cfg(feature = "serial2-tokio")]
+	mod health;
+	#[cfg(feature = "serial2-tokio")]
+	pub use health::HealthMonitor;
+	mod indirect;
 	pub(crate) mod instructions;
+	#[cfg(feature = "alloc")]
+	mod snapshot;
+	mod torque;
+	#[cfg(feature = "serial2-tokio")]
+	mod trajectory;
+	mod units;
+	#[cfg(feature = "serial2-tokio")]
+	mod watchdog;
+	#[cfg(feature = "serial2-tokio")]
+	pub use watchdog::Watchdog;
 }
 
 #[path = "."]
@@ -19,18 +53,81 @@ pub(crate) mod sync {
 	use crate::SerialPort;
 	use bisync::synchronous::*;
 
+	#[cfg(feature = "std")]
+	mod boot;
+	#[cfg(feature = "std")]
+	mod calibrate;
 	mod client;
 	pub use client::Client;
+	#[cfg(feature = "std")]
+	mod control_loop;
+	#[cfg(feature = "std")]
+	pub use control_loop::ControlLoop;
+	mod motor;
+	pub use motor::Motor;
+	#[cfg(feature = "alloc")]
+	mod motor_group;
+	#[cfg(feature = "alloc")]
+	pub use motor_group::MotorGroup;
+	#[cfg(feature = "config")]
+	mod config;
+	mod configure;
+	mod discover;
+	mod hardware_error;
+	#[cfg(feature = "std")]
+	mod health;
+	#[cfg(feature = "std")]
+	pub use health::HealthMonitor;
+	mod indirect;
 	pub(crate) mod instructions;
+	#[cfg(feature = "alloc")]
+	mod snapshot;
+	mod torque;
+	#[cfg(feature = "std")]
+	mod trajectory;
+	mod units;
+	#[cfg(feature = "std")]
+	mod watchdog;
+	#[cfg(feature = "std")]
+	pub use watchdog::Watchdog;
 }
 
 pub use asynch::instructions::bulk_read::BulkReadBytes as AsyncBulkReadBytes;
 pub use asynch::instructions::ping::Scan as AsyncScan;
 pub use asynch::instructions::{sync_read::SyncRead as AsyncSyncRead, sync_read::SyncReadBytes as AsyncSyncReadBytes};
 pub use asynch::Client as AsyncClient;
+#[cfg(feature = "serial2-tokio")]
+pub use asynch::HealthMonitor as AsyncHealthMonitor;
+#[cfg(feature = "serial2-tokio")]
+pub use asynch::ControlLoop as AsyncControlLoop;
+pub use asynch::Motor as AsyncMotor;
+#[cfg(feature = "alloc")]
+pub use asynch::MotorGroup as AsyncMotorGroup;
+#[cfg(feature = "serial2-tokio")]
+pub use asynch::Watchdog as AsyncWatchdog;
 
 pub use sync::instructions::{bulk_read::BulkReadBytes, ping::Scan, sync_read::SyncRead, sync_read::SyncReadBytes};
-pub use sync::Client;
+pub use sync::{Client, Motor};
+#[cfg(feature = "std")]
+pub use sync::ControlLoop;
+#[cfg(feature = "std")]
+pub use sync::HealthMonitor;
+#[cfg(feature = "alloc")]
+pub use sync::MotorGroup;
+#[cfg(feature = "std")]
+pub use sync::Watchdog;
+
+mod motor_map;
+pub use motor_map::MotorMap;
+
+mod protocol1;
+
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "std")]
+pub use shared::{Priority, SharedClient, TransactionGuard};
+#[cfg(feature = "serial2-tokio")]
+pub use shared::{AsyncTransactionGuard, SharedAsyncClient};
 
 /// Sync data for a specific motor.
 ///
@@ -103,6 +200,412 @@ pub enum FactoryResetKind {
 	KeepIdAndBaudRate = 0x02,
 }
 
+/// The standard baud rates supported by DYNAMIXEL motors, from 9600 baud up to 4.5 Mbaud.
+///
+/// Use with [`Client::discover`] to search for motors with an unknown baud rate.
+pub const STANDARD_BAUD_RATES: [u32; 8] = [9_600, 57_600, 115_200, 1_000_000, 2_000_000, 3_000_000, 4_000_000, 4_500_000];
+
+/// A standard baud rate supported by DYNAMIXEL motors.
+///
+/// Not every model supports every baud rate.
+/// See [`ControlTable::baud_rates`](crate::control_table::ControlTable::baud_rates) for the baud rates supported by a model family.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum BaudRate {
+	/// 9600 baud.
+	B9600,
+
+	/// 57600 baud.
+	B57600,
+
+	/// 115200 baud.
+	B115200,
+
+	/// 1 Mbaud.
+	B1M,
+
+	/// 2 Mbaud.
+	B2M,
+
+	/// 3 Mbaud.
+	B3M,
+
+	/// 4 Mbaud.
+	B4M,
+
+	/// 4.5 Mbaud.
+	B4_5M,
+}
+
+impl BaudRate {
+	/// Get the baud rate in bits per second.
+	pub fn bits_per_second(self) -> u32 {
+		match self {
+			Self::B9600 => 9_600,
+			Self::B57600 => 57_600,
+			Self::B115200 => 115_200,
+			Self::B1M => 1_000_000,
+			Self::B2M => 2_000_000,
+			Self::B3M => 3_000_000,
+			Self::B4M => 4_000_000,
+			Self::B4_5M => 4_500_000,
+		}
+	}
+
+	/// Get the standard baud rate for a number of bits per second, if there is one.
+	pub fn from_bits_per_second(bits_per_second: u32) -> Option<Self> {
+		match bits_per_second {
+			9_600 => Some(Self::B9600),
+			57_600 => Some(Self::B57600),
+			115_200 => Some(Self::B115200),
+			1_000_000 => Some(Self::B1M),
+			2_000_000 => Some(Self::B2M),
+			3_000_000 => Some(Self::B3M),
+			4_000_000 => Some(Self::B4M),
+			4_500_000 => Some(Self::B4_5M),
+			_ => None,
+		}
+	}
+}
+
+/// The operating mode of an X-series motor, as stored in the "Operating Mode" register.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum OperatingMode {
+	/// Control the current (torque) of the motor.
+	Current = 0,
+
+	/// Control the velocity of the motor.
+	Velocity = 1,
+
+	/// Control the position of the motor within one revolution.
+	Position = 3,
+
+	/// Control the position of the motor over multiple revolutions.
+	ExtendedPosition = 4,
+
+	/// Control the position of the motor over multiple revolutions, with a limit on the current.
+	CurrentBasedPosition = 5,
+
+	/// Control the PWM duty cycle of the motor directly.
+	Pwm = 16,
+}
+
+impl OperatingMode {
+	/// Get the operating mode for a value of the "Operating Mode" register.
+	pub fn from_raw(raw: u8) -> Option<Self> {
+		match raw {
+			0 => Some(Self::Current),
+			1 => Some(Self::Velocity),
+			3 => Some(Self::Position),
+			4 => Some(Self::ExtendedPosition),
+			5 => Some(Self::CurrentBasedPosition),
+			16 => Some(Self::Pwm),
+			_ => None,
+		}
+	}
+
+	/// Get the value of the "Operating Mode" register for this operating mode.
+	pub fn raw(self) -> u8 {
+		self as u8
+	}
+
+	/// Check if the goal position is used in this operating mode.
+	pub fn uses_goal_position(self) -> bool {
+		matches!(self, Self::Position | Self::ExtendedPosition | Self::CurrentBasedPosition)
+	}
+
+	/// Check if the goal velocity is used in this operating mode.
+	pub fn uses_goal_velocity(self) -> bool {
+		self == Self::Velocity
+	}
+
+	/// Check if the goal current is used in this operating mode.
+	pub fn uses_goal_current(self) -> bool {
+		matches!(self, Self::Current | Self::CurrentBasedPosition)
+	}
+
+	/// Check if the goal PWM is used as set point in this operating mode.
+	///
+	/// In other operating modes, the goal PWM is only used as limit.
+	pub fn uses_goal_pwm(self) -> bool {
+		self == Self::Pwm
+	}
+}
+
+/// The present state of a motor, as read by [`Motor::present_state()`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PresentState {
+	/// The present PWM duty cycle, as fraction from -1.0 to 1.0.
+	pub pwm: f64,
+
+	/// The present current, or `None` if the model has no current sensing.
+	pub current: Option<crate::units::Current>,
+
+	/// The present velocity.
+	pub velocity: crate::units::AngularVelocity,
+
+	/// The present position.
+	pub position: crate::units::Angle,
+
+	/// The present input voltage.
+	pub input_voltage: crate::units::Voltage,
+
+	/// The present internal temperature.
+	pub temperature: crate::units::Temperature,
+}
+
+/// The expected bus time of writing goals to and reading states from a [`MotorGroup`].
+///
+/// Computed by [`MotorGroup::cycle_budget()`] from the baud rate and the response timeout padding of a client for each motor.
+/// Byte stuffing and delays in the serial port driver and the motors are not included.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CycleBudget {
+	/// The time to transmit the read instruction and to receive the replies of all motors.
+	pub read: core::time::Duration,
+
+	/// The time to transmit the write instruction.
+	pub write: core::time::Duration,
+
+	/// The sum of the response timeout padding of each motor, for all replies to the read instruction.
+	pub timeout_padding: core::time::Duration,
+}
+
+#[cfg(feature = "alloc")]
+impl CycleBudget {
+	/// Get the time needed to transmit all messages of a cycle.
+	///
+	/// If this is longer than the period of a control loop, the period can not be met.
+	pub fn transfer_time(&self) -> core::time::Duration {
+		self.read + self.write
+	}
+
+	/// Get the time a cycle can take before the read instruction times out.
+	pub fn worst_case(&self) -> core::time::Duration {
+		self.transfer_time() + self.timeout_padding
+	}
+}
+
+/// Information about the current cycle of a [`ControlLoop`].
+#[cfg(feature = "std")]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cycle {
+	/// The index of the cycle, counting from 0.
+	///
+	/// Cycles that were skipped because of an overrun are counted too.
+	pub index: u64,
+
+	/// The time the cycle was scheduled to start.
+	pub scheduled: std::time::Instant,
+
+	/// How late the cycle actually started.
+	pub late: core::time::Duration,
+}
+
+/// Timing statistics of a [`ControlLoop`].
+#[cfg(feature = "std")]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct LoopStats {
+	/// The number of cycles that have been executed, including failed cycles.
+	pub cycles: u64,
+
+	/// The number of cycles that failed because reading the states or writing the goals returned an error.
+	pub failed_cycles: u64,
+
+	/// The number of cycles that did not finish before the start of the next cycle.
+	pub overruns: u64,
+
+	/// The number of cycles that were skipped to recover from overruns.
+	pub skipped_cycles: u64,
+
+	/// The duration of the last cycle.
+	pub last_cycle_time: core::time::Duration,
+
+	/// The duration of the longest cycle.
+	pub max_cycle_time: core::time::Duration,
+}
+
+/// The result of a safe stop by a [`Watchdog`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SafeStopReport {
+	/// The motors that confirmed that their torque is disabled.
+	pub acknowledged: alloc::vec::Vec<u8>,
+
+	/// The motors that did not reply, or that still have their torque enabled.
+	pub unacknowledged: alloc::vec::Vec<u8>,
+}
+
+/// Thresholds used by a [`HealthMonitor`] to detect unhealthy motors.
+#[cfg(feature = "std")]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HealthThresholds {
+	/// The highest temperature that is considered healthy.
+	pub max_temperature: crate::units::Temperature,
+
+	/// The lowest input voltage that is considered healthy.
+	pub min_voltage: crate::units::Voltage,
+
+	/// The highest input voltage that is considered healthy.
+	pub max_voltage: crate::units::Voltage,
+}
+
+/// The health of a motor, as seen by the last check of a [`HealthMonitor`].
+#[cfg(feature = "std")]
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct MotorHealth {
+	/// The motor replied to all reads of the last check.
+	pub reachable: bool,
+
+	/// The motor set the alert bit in a status response since the previous check.
+	pub alert: bool,
+
+	/// The value of the Hardware Error Status register.
+	pub hardware_error: crate::HardwareErrorStatus,
+
+	/// The present temperature of the motor.
+	pub temperature: crate::units::Temperature,
+
+	/// The present input voltage of the motor.
+	pub voltage: crate::units::Voltage,
+}
+
+/// An event reported by a [`HealthMonitor`].
+///
+/// Events are only reported when a condition starts, not for every check while it persists.
+#[cfg(feature = "std")]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum HealthEvent {
+	/// The motor set the alert bit in a status response.
+	Alert {
+		/// The ID of the motor.
+		motor_id: u8,
+	},
+
+	/// The motor reported a new hardware error.
+	HardwareError {
+		/// The ID of the motor.
+		motor_id: u8,
+
+		/// The value of the Hardware Error Status register.
+		status: crate::HardwareErrorStatus,
+	},
+
+	/// The hardware error of the motor was cleared.
+	HardwareErrorCleared {
+		/// The ID of the motor.
+		motor_id: u8,
+	},
+
+	/// The temperature of the motor rose above the threshold.
+	Overheating {
+		/// The ID of the motor.
+		motor_id: u8,
+
+		/// The present temperature of the motor.
+		temperature: crate::units::Temperature,
+	},
+
+	/// The input voltage of the motor is outside the thresholds.
+	VoltageOutOfRange {
+		/// The ID of the motor.
+		motor_id: u8,
+
+		/// The present input voltage of the motor.
+		voltage: crate::units::Voltage,
+	},
+
+	/// The motor stopped replying.
+	Unreachable {
+		/// The ID of the motor.
+		motor_id: u8,
+	},
+
+	/// The motor was rebooted and its saved configuration was restored by [`HealthMonitor::recover()`].
+	Recovered {
+		/// The ID of the motor.
+		motor_id: u8,
+	},
+}
+
+/// A set of motor IDs that set the alert bit in a status response.
+///
+/// See [`Client::alerts()`] and [`Client::take_alert()`].
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct AlertSet {
+	bits: [u32; 8],
+}
+
+impl AlertSet {
+	/// Create an empty set.
+	pub const fn new() -> Self {
+		Self { bits: [0; 8] }
+	}
+
+	/// Check if a motor is in the set.
+	pub fn contains(&self, motor_id: u8) -> bool {
+		self.bits[usize::from(motor_id / 32)] & (1 << (motor_id % 32)) != 0
+	}
+
+	/// Add a motor to the set.
+	pub fn insert(&mut self, motor_id: u8) {
+		self.bits[usize::from(motor_id / 32)] |= 1 << (motor_id % 32);
+	}
+
+	/// Remove a motor from the set, and return if it was in the set.
+	pub fn remove(&mut self, motor_id: u8) -> bool {
+		let contained = self.contains(motor_id);
+		self.bits[usize::from(motor_id / 32)] &= !(1 << (motor_id % 32));
+		contained
+	}
+
+	/// Remove all motors from the set.
+	pub fn clear(&mut self) {
+		self.bits = [0; 8];
+	}
+
+	/// Check if the set is empty.
+	pub fn is_empty(&self) -> bool {
+		self.bits.iter().all(|&bits| bits == 0)
+	}
+
+	/// Iterate over the motor IDs in the set, in ascending order.
+	pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+		(0..=u8::MAX).filter(|&motor_id| self.contains(motor_id))
+	}
+}
+
+/// A version of the DYNAMIXEL communication protocol.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ProtocolVersion {
+	/// DYNAMIXEL Protocol 1.0.
+	V1,
+
+	/// DYNAMIXEL Protocol 2.0.
+	V2,
+}
+
+/// A motor found by [`Client::discover`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DiscoveredMotor {
+	/// The baud rate the motor replied at.
+	pub baud_rate: u32,
+
+	/// The protocol version the motor replied with.
+	pub protocol: ProtocolVersion,
+
+	/// The ID of the motor.
+	pub motor_id: u8,
+
+	/// The model of the motor.
+	///
+	/// Refer to the online manual to find the codes for each model.
+	pub model: u16,
+
+	/// The firmware version of the motor.
+	pub firmware: u8,
+}
+
 /// A response from a motor to a ping instruction.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Ping {
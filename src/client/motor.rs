@@ -0,0 +1,255 @@
+use super::Client;
+use super::SerialPort;
+use crate::client::{OperatingMode, PresentState};
+use crate::control_table::{self, x_series};
+use crate::units::{self, Angle, AngularVelocity, Current, ModelUnits, UnitRegister};
+use crate::{MotorCommandError, Response, TransferError};
+
+/// The size of one PWM unit as fraction of the full duty cycle: 0.113%.
+const PWM_UNIT: f64 = 0.00113;
+
+/// A handle to a single motor with the X-series control table layout.
+///
+/// The handle knows the model and operating mode of the motor,
+/// and refuses commands that do not match the operating mode.
+/// The operating mode is read once when the handle is created, and updated by [`Self::set_operating_mode()`].
+/// If the operating mode is changed by other means, create a new handle.
+///
+/// Create a handle with [`Client::motor()`].
+pub struct Motor<'c, Port, Buffer = crate::bus::DefaultBuffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	client: &'c mut Client<Port, Buffer>,
+	motor_id: u8,
+	model: u16,
+	units: ModelUnits,
+	operating_mode: OperatingMode,
+}
+
+impl<Port, Buffer> core::fmt::Debug for Motor<'_, Port, Buffer>
+where
+	Port: SerialPort + core::fmt::Debug,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("Motor")
+			.field("serial_port", self.client.serial_port())
+			.field("motor_id", &self.motor_id)
+			.field("model", &self.model)
+			.field("operating_mode", &self.operating_mode)
+			.finish()
+	}
+}
+
+#[super::bisync]
+impl<Port, Buffer> Client<Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Get a handle to a single motor.
+	///
+	/// This pings the motor to determine the model, and reads the operating mode.
+	/// Unknown models are assumed to use the X-series control table layout, without current sensing.
+	///
+	/// Returns an error if the motor uses a different control table layout, such as the XL-320.
+	pub async fn motor(&mut self, motor_id: u8) -> Result<Motor<'_, Port, Buffer>, MotorCommandError<Port::Error>> {
+		let model = self.ping(motor_id).await?.data.model;
+		let units = match control_table::model(model) {
+			Some(info) if !core::ptr::eq(info.control_table, &x_series::CONTROL_TABLE) => {
+				return Err(MotorCommandError::UnsupportedModel { motor_id, model });
+			},
+			Some(info) => info.units,
+			None => ModelUnits::x_series(None),
+		};
+		let operating_mode = self.read::<u8>(motor_id, x_series::OPERATING_MODE.address).await?.data;
+		let operating_mode = OperatingMode::from_raw(operating_mode).ok_or(MotorCommandError::UnknownOperatingMode {
+			motor_id,
+			value: operating_mode,
+		})?;
+		Ok(Motor {
+			client: self,
+			motor_id,
+			model,
+			units,
+			operating_mode,
+		})
+	}
+}
+
+#[super::bisync]
+impl<Port, Buffer> Motor<'_, Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Get the ID of the motor.
+	pub fn motor_id(&self) -> u8 {
+		self.motor_id
+	}
+
+	/// Get the model number of the motor.
+	pub fn model(&self) -> u16 {
+		self.model
+	}
+
+	/// Get the size of the raw units of the motor.
+	pub fn units(&self) -> &ModelUnits {
+		&self.units
+	}
+
+	/// Get the operating mode of the motor.
+	pub fn operating_mode(&self) -> OperatingMode {
+		self.operating_mode
+	}
+
+	/// Get the client used to communicate with the motor.
+	pub fn client(&mut self) -> &mut Client<Port, Buffer> {
+		self.client
+	}
+
+	/// Change the operating mode of the motor.
+	///
+	/// The operating mode is stored in EEPROM, which can only be written while torque is disabled.
+	/// If torque is enabled, it is disabled before changing the operating mode and enabled again afterwards,
+	/// also when writing the operating mode fails.
+	///
+	/// Nothing is written if the motor is already in the requested operating mode.
+	pub async fn set_operating_mode(&mut self, operating_mode: OperatingMode) -> Result<(), MotorCommandError<Port::Error>> {
+		if operating_mode == self.operating_mode {
+			return Ok(());
+		}
+		let torque_enable = x_series::TORQUE_ENABLE.address;
+		let torque = self.client.disable_torque_for_eeprom(self.motor_id, torque_enable).await?;
+		let result = self
+			.client
+			.write(self.motor_id, x_series::OPERATING_MODE.address, &operating_mode.raw())
+			.await;
+		if result.is_ok() {
+			self.operating_mode = operating_mode;
+		}
+		// Leave the torque as we found it, even if the operating mode could not be changed.
+		self.client.restore_torque(self.motor_id, torque_enable, torque, result).await?;
+		Ok(())
+	}
+
+	/// Enable the torque of the motor.
+	pub async fn enable_torque(&mut self) -> Result<Response<()>, TransferError<Port::Error>> {
+		self.client.write(self.motor_id, x_series::TORQUE_ENABLE.address, &1u8).await
+	}
+
+	/// Disable the torque of the motor.
+	pub async fn disable_torque(&mut self) -> Result<Response<()>, TransferError<Port::Error>> {
+		self.client.write(self.motor_id, x_series::TORQUE_ENABLE.address, &0u8).await
+	}
+
+	/// Set the goal position of the motor.
+	///
+	/// The motor must be in position, extended position or current-based position mode.
+	pub async fn set_goal_position(&mut self, position: Angle) -> Result<Response<()>, MotorCommandError<Port::Error>> {
+		self.check_operating_mode(self.operating_mode.uses_goal_position(), "set the goal position")?;
+		Ok(self.client.write_register(self.motor_id, units::x_series::GOAL_POSITION, position).await?)
+	}
+
+	/// Set the goal velocity of the motor.
+	///
+	/// The motor must be in velocity mode.
+	pub async fn set_goal_velocity(&mut self, velocity: AngularVelocity) -> Result<Response<()>, MotorCommandError<Port::Error>> {
+		self.check_operating_mode(self.operating_mode.uses_goal_velocity(), "set the goal velocity")?;
+		Ok(self.client.write_register(self.motor_id, units::x_series::GOAL_VELOCITY, velocity).await?)
+	}
+
+	/// Set the goal current of the motor.
+	///
+	/// The motor must be in current or current-based position mode,
+	/// and the model must support current sensing.
+	pub async fn set_goal_current(&mut self, current: Current) -> Result<Response<()>, MotorCommandError<Port::Error>> {
+		const COMMAND: &str = "set the goal current";
+		let register = self.current_register(units::x_series::goal_current, COMMAND)?;
+		self.check_operating_mode(self.operating_mode.uses_goal_current(), COMMAND)?;
+		Ok(self.client.write_register(self.motor_id, register, current).await?)
+	}
+
+	/// Set the goal PWM duty cycle of the motor, as fraction from -1.0 to 1.0.
+	///
+	/// The motor must be in PWM mode.
+	/// The value is rounded to the resolution of the motor, which is 0.113%.
+	pub async fn set_goal_pwm(&mut self, pwm: f64) -> Result<Response<()>, MotorCommandError<Port::Error>> {
+		self.check_operating_mode(self.operating_mode.uses_goal_pwm(), "set the goal PWM")?;
+		let raw = <i16 as units::RawValue>::from_f64(pwm / PWM_UNIT);
+		Ok(self.client.write(self.motor_id, x_series::GOAL_PWM.address, &raw).await?)
+	}
+
+	/// Read the present PWM, current, velocity, position, input voltage and temperature of the motor.
+	///
+	/// All values are read with a single read instruction.
+	pub async fn present_state(&mut self) -> Result<Response<PresentState>, TransferError<Port::Error>> {
+		const START: u16 = x_series::PRESENT_PWM.address;
+		const COUNT: usize = (x_series::PRESENT_TEMPERATURE.address + 1 - START) as usize;
+		let response = self.client.read::<[u8; COUNT]>(self.motor_id, START).await?;
+		let data = &response.data;
+		let field = |register: control_table::Register| usize::from(register.address - START);
+		let i16_at = |register| {
+			let i = field(register);
+			i16::from_le_bytes([data[i], data[i + 1]])
+		};
+		let u16_at = |register| {
+			let i = field(register);
+			u16::from_le_bytes([data[i], data[i + 1]])
+		};
+		let i32_at = |register| {
+			let i = field(register);
+			i32::from_le_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]])
+		};
+
+		let current = units::x_series::present_current(&self.units).map(|register| register.to_quantity(i16_at(x_series::PRESENT_CURRENT)));
+		Ok(Response {
+			motor_id: response.motor_id,
+			alert: response.alert,
+			data: PresentState {
+				pwm: f64::from(i16_at(x_series::PRESENT_PWM)) * PWM_UNIT,
+				current,
+				velocity: units::x_series::PRESENT_VELOCITY.to_quantity(i32_at(x_series::PRESENT_VELOCITY)),
+				position: units::x_series::PRESENT_POSITION.to_quantity(i32_at(x_series::PRESENT_POSITION)),
+				input_voltage: units::x_series::PRESENT_INPUT_VOLTAGE.to_quantity(u16_at(x_series::PRESENT_INPUT_VOLTAGE)),
+				temperature: units::x_series::PRESENT_TEMPERATURE.to_quantity(data[field(x_series::PRESENT_TEMPERATURE)]),
+			},
+		})
+	}
+
+	/// Reboot the motor.
+	///
+	/// After a reboot, torque is disabled and all RAM registers are reset to their default values.
+	/// The operating mode is stored in EEPROM and is not affected.
+	pub async fn reboot(&mut self) -> Result<Response<()>, TransferError<Port::Error>> {
+		self.client.reboot(self.motor_id).await
+	}
+
+	/// Return an error if a command does not match the operating mode of the motor.
+	fn check_operating_mode(&self, matches: bool, command: &'static str) -> Result<(), MotorCommandError<Port::Error>> {
+		if matches {
+			Ok(())
+		} else {
+			Err(MotorCommandError::WrongOperatingMode {
+				motor_id: self.motor_id,
+				operating_mode: self.operating_mode,
+				command,
+			})
+		}
+	}
+
+	/// Get a current register for the model of the motor, or an error if the model has no current sensing.
+	fn current_register<T>(
+		&self,
+		register: fn(&ModelUnits) -> Option<UnitRegister<T, Current>>,
+		command: &'static str,
+	) -> Result<UnitRegister<T, Current>, MotorCommandError<Port::Error>> {
+		register(&self.units).ok_or(MotorCommandError::UnsupportedCommand {
+			motor_id: self.motor_id,
+			model: self.model,
+			command,
+		})
+	}
+}
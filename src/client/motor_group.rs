@@ -0,0 +1,206 @@
+use super::Client;
+use super::SerialPort;
+use crate::bus::Data;
+use crate::client::{BulkReadData, BulkWriteData, CycleBudget, SyncWriteData};
+use crate::control_table::Register;
+use crate::timing::{predict_transaction, Instruction};
+use crate::{TransferError, WriteError};
+use alloc::vec::Vec;
+
+/// A group of motors that are commanded together, such as the joints of an arm or a leg.
+///
+/// Each motor in the group has a goal register of type `T` and a state register of type `S`.
+/// For example, the goal position and present position registers with `i32` values.
+/// The registers may be at different addresses for each motor, to support groups with different models.
+///
+/// If all motors use the same address, the group uses sync write and sync read instructions.
+/// Otherwise, it falls back to bulk write and bulk read instructions.
+///
+/// The group keeps its own buffers, so that writing goals and reading states does not allocate once all motors are added.
+/// The group does not borrow the client, so that multiple groups can share the same client.
+#[derive(Debug)]
+pub struct MotorGroup<T, S = T> {
+	/// The IDs of the motors in the group.
+	motor_ids: Vec<u8>,
+
+	/// The address of the goal register of each motor.
+	goal_addresses: Vec<u16>,
+
+	/// The bulk write parameters, used if the goal registers are at different addresses.
+	bulk_writes: Vec<BulkWriteData<Vec<u8>>>,
+
+	/// The bulk read parameters, also used for sync reads if all state registers are at the same address.
+	state_reads: Vec<BulkReadData>,
+
+	/// The last read states of all motors.
+	states: Vec<S>,
+
+	_goal: core::marker::PhantomData<fn(T)>,
+}
+
+impl<T: Data, S: Data> Default for MotorGroup<T, S> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[super::bisync]
+impl<T: Data, S: Data> MotorGroup<T, S> {
+	/// Create a new empty group.
+	pub fn new() -> Self {
+		Self::with_capacity(0)
+	}
+
+	/// Create a new empty group with room for `capacity` motors.
+	pub fn with_capacity(capacity: usize) -> Self {
+		Self {
+			motor_ids: Vec::with_capacity(capacity),
+			goal_addresses: Vec::with_capacity(capacity),
+			bulk_writes: Vec::with_capacity(capacity),
+			state_reads: Vec::with_capacity(capacity),
+			states: Vec::with_capacity(capacity),
+			_goal: core::marker::PhantomData,
+		}
+	}
+
+	/// Create a group of motors that all use the same goal and state registers.
+	///
+	/// # Panics
+	/// See [`Self::add_motor()`].
+	pub fn from_registers(motor_ids: &[u8], goal: &Register, state: &Register) -> Self {
+		let mut group = Self::with_capacity(motor_ids.len());
+		for &motor_id in motor_ids {
+			group.add_motor(motor_id, goal, state);
+		}
+		group
+	}
+
+	/// Add a motor to the group.
+	///
+	/// # Panics
+	/// This function panics if the motor is already in the group,
+	/// or if the size of the registers does not match the size of `T` and `S`.
+	pub fn add_motor(&mut self, motor_id: u8, goal: &Register, state: &Register) {
+		assert!(
+			!self.motor_ids.contains(&motor_id),
+			"MotorGroup::add_motor: motor ID {} is already in the group",
+			motor_id
+		);
+		assert_eq!(goal.size, T::ENCODED_SIZE, "MotorGroup::add_motor: size of {:?} does not match the goal type", goal.name);
+		assert_eq!(state.size, S::ENCODED_SIZE, "MotorGroup::add_motor: size of {:?} does not match the state type", state.name);
+		self.motor_ids.push(motor_id);
+		self.goal_addresses.push(goal.address);
+		self.bulk_writes.push(BulkWriteData {
+			motor_id,
+			address: goal.address,
+			data: alloc::vec![0; usize::from(T::ENCODED_SIZE)],
+		});
+		self.state_reads.push(BulkReadData {
+			motor_id,
+			address: state.address,
+			count: S::ENCODED_SIZE,
+		});
+		self.states.reserve(self.motor_ids.len() - self.states.len());
+	}
+
+	/// Get the IDs of the motors in the group, in the order they were added.
+	pub fn motor_ids(&self) -> &[u8] {
+		&self.motor_ids
+	}
+
+	/// Check if the goals are written with a sync write instruction.
+	///
+	/// This is true if the goal registers of all motors are at the same address.
+	pub fn uses_sync_write(&self) -> bool {
+		self.goal_addresses.windows(2).all(|pair| pair[0] == pair[1])
+	}
+
+	/// Check if the states are read with a sync read instruction.
+	///
+	/// This is true if the state registers of all motors are at the same address.
+	pub fn uses_sync_read(&self) -> bool {
+		self.state_reads.windows(2).all(|pair| pair[0].address == pair[1].address)
+	}
+
+	/// Compute the expected bus time of writing goals to and reading states from all motors in the group.
+	///
+	/// The budget is based on the baud rate and the response timeout padding of the client for each motor,
+	/// as set with [`Client::set_motor_response_timeout_padding()`].
+	pub fn cycle_budget<Port, Buffer>(&self, client: &Client<Port, Buffer>) -> CycleBudget
+	where
+		Port: SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		let timing = client.bus_timing();
+		let motor_count = self.motor_ids.len();
+		let write = if self.uses_sync_write() { Instruction::SyncWrite } else { Instruction::BulkWrite };
+		let read = if self.uses_sync_read() { Instruction::SyncRead } else { Instruction::BulkRead };
+		CycleBudget {
+			read: predict_transaction(&timing, read, motor_count, S::ENCODED_SIZE).min,
+			write: predict_transaction(&timing, write, motor_count, T::ENCODED_SIZE).min,
+			timeout_padding: self.motor_ids.iter().map(|&id| client.motor_response_timeout_padding(id)).sum(),
+		}
+	}
+
+	/// Write the goal of each motor, in the order the motors were added.
+	///
+	/// # Panics
+	/// This function panics if the number of goals does not match the number of motors in the group.
+	pub async fn write_goals<Port, Buffer>(&mut self, client: &mut Client<Port, Buffer>, goals: &[T]) -> Result<(), WriteError<Port::Error>>
+	where
+		T: Clone,
+		Port: SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		assert_eq!(
+			goals.len(),
+			self.motor_ids.len(),
+			"MotorGroup::write_goals: got {} goals for {} motors",
+			goals.len(),
+			self.motor_ids.len()
+		);
+		if self.motor_ids.is_empty() {
+			return Ok(());
+		}
+
+		if self.uses_sync_write() {
+			let data = self.motor_ids.iter().zip(goals).map(|(&motor_id, goal)| SyncWriteData {
+				motor_id,
+				data: goal.clone(),
+			});
+			client.sync_write(self.goal_addresses[0], data).await
+		} else {
+			for (write, goal) in self.bulk_writes.iter_mut().zip(goals) {
+				goal.encode(&mut write.data)?;
+			}
+			client.bulk_write(&self.bulk_writes).await
+		}
+	}
+
+	/// Read the state of each motor, in the order the motors were added.
+	///
+	/// If any motor fails to reply, the error is returned.
+	pub async fn read_states<Port, Buffer>(&mut self, client: &mut Client<Port, Buffer>) -> Result<&[S], TransferError<Port::Error>>
+	where
+		Port: SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		self.states.clear();
+		if self.motor_ids.is_empty() {
+			return Ok(&self.states);
+		}
+
+		if self.uses_sync_read() {
+			let mut reads = client.sync_read::<S>(&self.motor_ids, self.state_reads[0].address).await?;
+			while let Some(response) = reads.read_next().await {
+				self.states.push(response?.data);
+			}
+		} else {
+			let mut reads = client.bulk_read_bytes_borrow::<[u8]>(&self.state_reads).await?;
+			while let Some(response) = reads.read_next_borrow().await {
+				self.states.push(S::decode(response?.data)?);
+			}
+		}
+		Ok(&self.states)
+	}
+}
@@ -0,0 +1,144 @@
+/// A fixed-capacity map from motor IDs to values, for use without an allocator.
+///
+/// This can be used as output collection for [`Client::sync_read_all`](crate::Client::sync_read_all),
+/// [`Client::bulk_read_all`](crate::Client::bulk_read_all) and [`Client::scan_all`](crate::Client::scan_all).
+/// If the `"alloc"` feature is enabled, you can also use a `BTreeMap` or `Vec` instead.
+///
+/// Entries are kept in insertion order.
+#[derive(Clone, Eq, PartialEq)]
+pub struct MotorMap<V, const N: usize> {
+	entries: [Option<(u8, V)>; N],
+	len: usize,
+}
+
+impl<V, const N: usize> MotorMap<V, N> {
+	/// Create a new empty map.
+	pub const fn new() -> Self {
+		Self {
+			entries: [const { None }; N],
+			len: 0,
+		}
+	}
+
+	/// Get the number of entries in the map.
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	/// Check if the map is empty.
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Get the maximum number of entries in the map.
+	pub fn capacity(&self) -> usize {
+		N
+	}
+
+	/// Get a reference to the value for a motor.
+	pub fn get(&self, motor_id: u8) -> Option<&V> {
+		self.iter().find(|(id, _)| *id == motor_id).map(|(_, value)| value)
+	}
+
+	/// Get a mutable reference to the value for a motor.
+	pub fn get_mut(&mut self, motor_id: u8) -> Option<&mut V> {
+		self.entries[..self.len]
+			.iter_mut()
+			.flatten()
+			.find(|(id, _)| *id == motor_id)
+			.map(|(_, value)| value)
+	}
+
+	/// Check if the map contains a value for a motor.
+	pub fn contains_key(&self, motor_id: u8) -> bool {
+		self.get(motor_id).is_some()
+	}
+
+	/// Insert a value for a motor.
+	///
+	/// If the map already contained a value for the motor, it is replaced and the old value is returned.
+	///
+	/// If the map is full, the value is returned as error.
+	pub fn insert(&mut self, motor_id: u8, value: V) -> Result<Option<V>, V> {
+		if let Some(old) = self.get_mut(motor_id) {
+			return Ok(Some(core::mem::replace(old, value)));
+		}
+		let Some(slot) = self.entries.get_mut(self.len) else {
+			return Err(value);
+		};
+		*slot = Some((motor_id, value));
+		self.len += 1;
+		Ok(None)
+	}
+
+	/// Iterate over the motor IDs and values in the map.
+	pub fn iter(&self) -> impl Iterator<Item = (u8, &V)> + '_ {
+		self.entries[..self.len].iter().flatten().map(|(id, value)| (*id, value))
+	}
+}
+
+impl<V, const N: usize> Default for MotorMap<V, N> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<V, const N: usize> core::fmt::Debug for MotorMap<V, N>
+where
+	V: core::fmt::Debug,
+{
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_map().entries(self.iter()).finish()
+	}
+}
+
+/// Extend the map with new entries.
+///
+/// # Panics
+/// This panics if the map does not have enough capacity for all entries.
+impl<V, const N: usize> Extend<(u8, V)> for MotorMap<V, N> {
+	fn extend<I: IntoIterator<Item = (u8, V)>>(&mut self, iter: I) {
+		for (motor_id, value) in iter {
+			if self.insert(motor_id, value).is_err() {
+				panic!("MotorMap: capacity of {} entries exceeded", N);
+			}
+		}
+	}
+}
+
+impl<V, const N: usize> IntoIterator for MotorMap<V, N> {
+	type Item = (u8, V);
+	type IntoIter = core::iter::Flatten<core::array::IntoIter<Option<(u8, V)>, N>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.entries.into_iter().flatten()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn insert_and_get() {
+		let mut map = MotorMap::<u32, 2>::new();
+		assert!(map.is_empty());
+		assert!(map.insert(3, 30) == Ok(None));
+		assert!(map.insert(1, 10) == Ok(None));
+		assert!(map.insert(3, 31) == Ok(Some(30)));
+		assert!(map.insert(2, 20) == Err(20));
+		assert!(map.len() == 2);
+		assert!(map.get(3) == Some(&31));
+		assert!(map.get(1) == Some(&10));
+		assert!(map.get(2) == None);
+		assert!(map.into_iter().collect::<Vec<_>>() == [(3, 31), (1, 10)]);
+	}
+
+	#[test]
+	#[should_panic]
+	fn extend_panics_when_full() {
+		let mut map = MotorMap::<u32, 1>::new();
+		map.extend([(1, 10), (2, 20)]);
+	}
+}
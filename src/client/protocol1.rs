@@ -0,0 +1,83 @@
+//! Minimal support for DYNAMIXEL Protocol 1.0 packets, used to probe for motors that do not speak Protocol 2.0.
+
+use crate::client::Ping;
+
+/// The instruction ID of a Protocol 1.0 read instruction.
+const READ: u8 = 0x02;
+
+/// The size of an encoded Protocol 1.0 status packet with the model and firmware.
+pub(crate) const PING_REPLY_SIZE: usize = 9;
+
+/// Compute the checksum of a Protocol 1.0 packet, excluding the two header bytes.
+fn checksum(data: &[u8]) -> u8 {
+	!data.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+}
+
+/// Encode a Protocol 1.0 instruction that reads the model number and firmware version of a motor.
+///
+/// Protocol 1.0 has no ping instruction that reports the model,
+/// but all Protocol 1.0 motors store the model at address 0 and the firmware version at address 2.
+pub(crate) fn encode_ping(motor_id: u8) -> [u8; 8] {
+	let mut packet = [0xFF, 0xFF, motor_id, 4, READ, 0, 3, 0];
+	packet[7] = checksum(&packet[2..7]);
+	packet
+}
+
+/// The result of parsing a (partial) Protocol 1.0 status packet.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum ParseResult {
+	/// More data is needed to parse the reply.
+	Incomplete,
+
+	/// The data does not contain a valid reply from the motor.
+	Invalid,
+
+	/// A valid reply from the motor.
+	Reply(Ping),
+}
+
+/// Parse the reply to an instruction encoded with [`encode_ping`].
+///
+/// Leading garbage before the packet header is ignored.
+pub(crate) fn parse_ping_reply(data: &[u8], motor_id: u8) -> ParseResult {
+	let Some(start) = data.windows(2).position(|header| header == [0xFF, 0xFF]) else {
+		return ParseResult::Incomplete;
+	};
+	let Some(packet) = data[start..].get(..PING_REPLY_SIZE) else {
+		return ParseResult::Incomplete;
+	};
+	let [_, _, id, length, _error, model_low, model_high, firmware, check] = *packet else {
+		unreachable!()
+	};
+	if id != motor_id || length != 5 || check != checksum(&packet[2..8]) {
+		return ParseResult::Invalid;
+	}
+	ParseResult::Reply(Ping {
+		model: u16::from_le_bytes([model_low, model_high]),
+		firmware,
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn test_encode_ping() {
+		assert!(encode_ping(1) == [0xFF, 0xFF, 0x01, 0x04, 0x02, 0x00, 0x03, 0xF5]);
+	}
+
+	#[test]
+	fn test_parse_ping_reply() {
+		// An AX-12A (model 12) with firmware 24.
+		let reply = [0x00, 0xFF, 0xFF, 0x01, 0x05, 0x00, 0x0C, 0x00, 0x18, 0xD5];
+		assert!(parse_ping_reply(&reply, 1) == ParseResult::Reply(Ping { model: 12, firmware: 24 }));
+		assert!(parse_ping_reply(&reply[..9], 1) == ParseResult::Incomplete);
+		assert!(parse_ping_reply(&reply, 2) == ParseResult::Invalid);
+
+		let mut corrupt = reply;
+		corrupt[8] = 0x19;
+		assert!(parse_ping_reply(&corrupt, 1) == ParseResult::Invalid);
+	}
+}
@@ -0,0 +1,611 @@
+//! Shared handles to a [`Client`] or [`AsyncClient`](crate::AsyncClient) for use from multiple threads or tasks.
+
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+
+use super::Client;
+
+/// The priority of a shared client handle.
+///
+/// When the bus is released, waiting handles with a higher priority get access before handles with a lower priority.
+/// A running transaction is never interrupted: priorities only determine who goes next.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub enum Priority {
+	/// For background work that can wait, like diagnostics and logging.
+	Low,
+
+	/// The default priority.
+	#[default]
+	Normal,
+
+	/// For time critical work, like a control loop.
+	High,
+}
+
+impl Priority {
+	/// The number of distinct priorities.
+	const COUNT: usize = 3;
+
+	fn index(self) -> usize {
+		self as usize
+	}
+}
+
+/// The state of a priority gate: whether it is taken, and how many handles are waiting at each priority.
+#[derive(Debug, Default)]
+struct GateState {
+	locked: bool,
+	waiting: [usize; Priority::COUNT],
+}
+
+impl GateState {
+	/// Try to take the gate for a waiter with the given priority.
+	///
+	/// The waiter must already be registered in `waiting`.
+	/// On success, the waiter is removed from the waiting list.
+	fn try_take(&mut self, priority: Priority) -> bool {
+		let higher_waiting = self.waiting[priority.index() + 1..].iter().any(|&count| count > 0);
+		if self.locked || higher_waiting {
+			return false;
+		}
+		self.locked = true;
+		self.waiting[priority.index()] -= 1;
+		true
+	}
+}
+
+/// A lock that hands out access in order of priority.
+#[derive(Debug, Default)]
+struct Gate {
+	state: Mutex<GateState>,
+	released: Condvar,
+}
+
+impl Gate {
+	fn state(&self) -> MutexGuard<'_, GateState> {
+		self.state.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+
+	fn acquire(&self, priority: Priority) {
+		let mut state = self.state();
+		state.waiting[priority.index()] += 1;
+		while !state.try_take(priority) {
+			state = self.released.wait(state).unwrap_or_else(PoisonError::into_inner);
+		}
+	}
+
+	fn release(&self) {
+		self.state().locked = false;
+		self.released.notify_all();
+	}
+}
+
+/// The data shared by all handles to the same client.
+#[derive(Debug)]
+struct Shared<C> {
+	gate: Gate,
+	client: Mutex<C>,
+}
+
+/// A thread-safe handle to a [`Client`] that can be cloned and shared between threads.
+///
+/// Use [`Self::lock()`] to get exclusive access to the client for one or more transactions.
+/// While a [`TransactionGuard`] exists, no other handle can send instructions on the bus.
+/// Since a [`SyncRead`](crate::client::SyncRead), [`BulkReadBytes`](crate::client::BulkReadBytes) or [`Scan`](crate::client::Scan)
+/// borrows the client from the guard, all replies are drained before another handle gets access to the bus.
+///
+/// Each handle has a [`Priority`]:
+/// when the bus is released, handles with a higher priority get access first.
+/// Use [`Self::with_priority()`] to create a handle with a different priority for the same client.
+///
+/// # Example
+/// ```no_run
+/// # #[cfg(feature = "serial2")]
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use dynamixel2::client::{Priority, SharedClient};
+///
+/// let client = dynamixel2::Client::open("/dev/ttyUSB0", 57600)?;
+/// let control = SharedClient::new(client).with_priority(Priority::High);
+/// let diagnostics = control.with_priority(Priority::Low);
+///
+/// std::thread::spawn(move || loop {
+///   let temperature = diagnostics.lock().read::<u8>(1, 146);
+///   println!("{temperature:?}");
+/// });
+///
+/// let mut client = control.lock();
+/// for response in client.sync_read::<u32>(&[1, 2], 132)? {
+///   println!("{:?}", response?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct SharedClient<Port, Buffer = crate::bus::DefaultBuffer>
+where
+	Port: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	shared: Arc<Shared<Client<Port, Buffer>>>,
+	priority: Priority,
+}
+
+impl<Port, Buffer> SharedClient<Port, Buffer>
+where
+	Port: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Create a shared handle to a client with [`Priority::Normal`].
+	pub fn new(client: Client<Port, Buffer>) -> Self {
+		Self {
+			shared: Arc::new(Shared {
+				gate: Gate::default(),
+				client: Mutex::new(client),
+			}),
+			priority: Priority::Normal,
+		}
+	}
+
+	/// Get the priority of this handle.
+	pub fn priority(&self) -> Priority {
+		self.priority
+	}
+
+	/// Create a new handle to the same client with a different priority.
+	pub fn with_priority(&self, priority: Priority) -> Self {
+		Self {
+			shared: self.shared.clone(),
+			priority,
+		}
+	}
+
+	/// Get exclusive access to the client, blocking until the bus is available.
+	///
+	/// When multiple handles are waiting, the handle with the highest priority gets access first.
+	pub fn lock(&self) -> TransactionGuard<'_, Port, Buffer> {
+		self.shared.gate.acquire(self.priority);
+		// The gate guarantees exclusive access, so the mutex is never contended.
+		// If a previous user panicked, the next instruction discards any pending replies, so we can safely continue.
+		let client = self.shared.client.lock().unwrap_or_else(PoisonError::into_inner);
+		TransactionGuard {
+			client,
+			gate: &self.shared.gate,
+		}
+	}
+}
+
+impl<Port, Buffer> Clone for SharedClient<Port, Buffer>
+where
+	Port: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn clone(&self) -> Self {
+		self.with_priority(self.priority)
+	}
+}
+
+impl<Port, Buffer> core::fmt::Debug for SharedClient<Port, Buffer>
+where
+	Port: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("SharedClient")
+			.field("priority", &self.priority)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Exclusive access to the client of a [`SharedClient`].
+///
+/// The guard dereferences to the [`Client`].
+/// The bus is released for other handles when the guard is dropped.
+pub struct TransactionGuard<'a, Port, Buffer = crate::bus::DefaultBuffer>
+where
+	Port: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	client: MutexGuard<'a, Client<Port, Buffer>>,
+	gate: &'a Gate,
+}
+
+impl<Port, Buffer> core::ops::Deref for TransactionGuard<'_, Port, Buffer>
+where
+	Port: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	type Target = Client<Port, Buffer>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.client
+	}
+}
+
+impl<Port, Buffer> core::ops::DerefMut for TransactionGuard<'_, Port, Buffer>
+where
+	Port: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.client
+	}
+}
+
+impl<Port, Buffer> Drop for TransactionGuard<'_, Port, Buffer>
+where
+	Port: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn drop(&mut self) {
+		self.gate.release();
+	}
+}
+
+impl<Port, Buffer> core::fmt::Debug for TransactionGuard<'_, Port, Buffer>
+where
+	Port: crate::SerialPort + core::fmt::Debug,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_tuple("TransactionGuard").field(&*self.client).finish()
+	}
+}
+
+#[cfg(feature = "serial2-tokio")]
+pub use self::asynch::{AsyncTransactionGuard, SharedAsyncClient};
+
+#[cfg(feature = "serial2-tokio")]
+mod asynch {
+	use std::sync::{Arc, MutexGuard, PoisonError};
+
+	use super::{GateState, Priority};
+	use crate::AsyncClient;
+
+	/// A lock that hands out access in order of priority to asynchronous tasks.
+	#[derive(Debug, Default)]
+	struct AsyncGate {
+		state: std::sync::Mutex<GateState>,
+		released: tokio::sync::Notify,
+	}
+
+	impl AsyncGate {
+		fn state(&self) -> MutexGuard<'_, GateState> {
+			self.state.lock().unwrap_or_else(PoisonError::into_inner)
+		}
+
+		async fn acquire(&self, priority: Priority) {
+			let mut waiter = Waiter::register(self, priority);
+			loop {
+				// Register for the notification before checking the state, so we can not miss a release.
+				let mut released = core::pin::pin!(self.released.notified());
+				released.as_mut().enable();
+				if self.state().try_take(priority) {
+					waiter.registered = false;
+					return;
+				}
+				released.await;
+			}
+		}
+
+		fn release(&self) {
+			self.state().locked = false;
+			self.released.notify_waiters();
+		}
+	}
+
+	/// A registered waiter of an [`AsyncGate`].
+	///
+	/// If the future waiting for the gate is dropped, this removes the waiter from the waiting list again.
+	struct Waiter<'a> {
+		gate: &'a AsyncGate,
+		priority: Priority,
+		registered: bool,
+	}
+
+	impl<'a> Waiter<'a> {
+		fn register(gate: &'a AsyncGate, priority: Priority) -> Self {
+			gate.state().waiting[priority.index()] += 1;
+			Self {
+				gate,
+				priority,
+				registered: true,
+			}
+		}
+	}
+
+	impl Drop for Waiter<'_> {
+		fn drop(&mut self) {
+			if self.registered {
+				self.gate.state().waiting[self.priority.index()] -= 1;
+				// Lower priority waiters may have been waiting for us.
+				self.gate.released.notify_waiters();
+			}
+		}
+	}
+
+	/// The data shared by all handles to the same asynchronous client.
+	#[derive(Debug)]
+	struct Shared<C> {
+		gate: AsyncGate,
+		client: tokio::sync::Mutex<C>,
+	}
+
+	impl<C> Shared<C> {
+		fn new(client: C) -> Self {
+			Self {
+				gate: AsyncGate::default(),
+				client: tokio::sync::Mutex::new(client),
+			}
+		}
+
+		async fn lock(&self, priority: Priority) -> SharedGuard<'_, C> {
+			self.gate.acquire(priority).await;
+			// Create the guard right away, so the gate is always released again.
+			let gate = GateGuard { gate: &self.gate };
+			// The gate guarantees exclusive access and guards release the mutex before the gate,
+			// so the mutex is never contended.
+			// Do not await here: a future dropped after taking the gate would never release it.
+			let client = self.client.try_lock().expect("client mutex is locked while holding the gate");
+			SharedGuard { client, _gate: gate }
+		}
+	}
+
+	/// Releases an [`AsyncGate`] when dropped.
+	#[derive(Debug)]
+	struct GateGuard<'a> {
+		gate: &'a AsyncGate,
+	}
+
+	impl Drop for GateGuard<'_> {
+		fn drop(&mut self) {
+			self.gate.release();
+		}
+	}
+
+	/// Exclusive access to the data of a [`Shared`].
+	///
+	/// Fields are dropped in declaration order, so the mutex is unlocked before the gate is released.
+	#[derive(Debug)]
+	struct SharedGuard<'a, C> {
+		client: tokio::sync::MutexGuard<'a, C>,
+		_gate: GateGuard<'a>,
+	}
+
+	/// A handle to an [`AsyncClient`] that can be cloned and shared between tasks.
+	///
+	/// This is the asynchronous version of [`SharedClient`](super::SharedClient).
+	/// Use [`Self::lock()`] to get exclusive access to the client for one or more transactions.
+	/// While an [`AsyncTransactionGuard`] exists, no other handle can send instructions on the bus.
+	///
+	/// Each handle has a [`Priority`]:
+	/// when the bus is released, handles with a higher priority get access first.
+	pub struct SharedAsyncClient<Port, Buffer = crate::bus::DefaultBuffer>
+	where
+		Port: crate::AsyncSerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		shared: Arc<Shared<AsyncClient<Port, Buffer>>>,
+		priority: Priority,
+	}
+
+	impl<Port, Buffer> SharedAsyncClient<Port, Buffer>
+	where
+		Port: crate::AsyncSerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		/// Create a shared handle to an asynchronous client with [`Priority::Normal`].
+		pub fn new(client: AsyncClient<Port, Buffer>) -> Self {
+			Self {
+				shared: Arc::new(Shared::new(client)),
+				priority: Priority::Normal,
+			}
+		}
+
+		/// Get the priority of this handle.
+		pub fn priority(&self) -> Priority {
+			self.priority
+		}
+
+		/// Create a new handle to the same client with a different priority.
+		pub fn with_priority(&self, priority: Priority) -> Self {
+			Self {
+				shared: self.shared.clone(),
+				priority,
+			}
+		}
+
+		/// Get exclusive access to the client, waiting until the bus is available.
+		///
+		/// When multiple handles are waiting, the handle with the highest priority gets access first.
+		///
+		/// This function is cancel safe: if the returned future is dropped before it completes,
+		/// the handle is removed from the waiting list.
+		pub async fn lock(&self) -> AsyncTransactionGuard<'_, Port, Buffer> {
+			AsyncTransactionGuard {
+				inner: self.shared.lock(self.priority).await,
+			}
+		}
+	}
+
+	impl<Port, Buffer> Clone for SharedAsyncClient<Port, Buffer>
+	where
+		Port: crate::AsyncSerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		fn clone(&self) -> Self {
+			self.with_priority(self.priority)
+		}
+	}
+
+	impl<Port, Buffer> core::fmt::Debug for SharedAsyncClient<Port, Buffer>
+	where
+		Port: crate::AsyncSerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+			f.debug_struct("SharedAsyncClient")
+				.field("priority", &self.priority)
+				.finish_non_exhaustive()
+		}
+	}
+
+	/// Exclusive access to the client of a [`SharedAsyncClient`].
+	///
+	/// The guard dereferences to the [`AsyncClient`].
+	/// The bus is released for other handles when the guard is dropped.
+	pub struct AsyncTransactionGuard<'a, Port, Buffer = crate::bus::DefaultBuffer>
+	where
+		Port: crate::AsyncSerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		inner: SharedGuard<'a, AsyncClient<Port, Buffer>>,
+	}
+
+	impl<Port, Buffer> core::ops::Deref for AsyncTransactionGuard<'_, Port, Buffer>
+	where
+		Port: crate::AsyncSerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		type Target = AsyncClient<Port, Buffer>;
+
+		fn deref(&self) -> &Self::Target {
+			&self.inner.client
+		}
+	}
+
+	impl<Port, Buffer> core::ops::DerefMut for AsyncTransactionGuard<'_, Port, Buffer>
+	where
+		Port: crate::AsyncSerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		fn deref_mut(&mut self) -> &mut Self::Target {
+			&mut self.inner.client
+		}
+	}
+
+	impl<Port, Buffer> core::fmt::Debug for AsyncTransactionGuard<'_, Port, Buffer>
+	where
+		Port: crate::AsyncSerialPort + core::fmt::Debug,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+			f.debug_tuple("AsyncTransactionGuard").field(&*self.inner.client).finish()
+		}
+	}
+
+	#[cfg(test)]
+	mod test {
+		use super::*;
+		use assert2::assert;
+		use core::future::Future;
+		use core::pin::Pin;
+		use core::task::Poll;
+		use std::time::Duration;
+
+		#[tokio::test]
+		async fn cancelled_waiter_does_not_block_others() {
+			let gate = AsyncGate::default();
+			gate.acquire(Priority::Normal).await;
+
+			// A high priority waiter that gives up must not keep blocking lower priority waiters.
+			let timed_out = tokio::time::timeout(Duration::from_millis(10), gate.acquire(Priority::High)).await;
+			assert!(timed_out.is_err());
+			assert!(gate.state().waiting == [0, 0, 0]);
+
+			gate.release();
+			let acquired = tokio::time::timeout(Duration::from_secs(1), gate.acquire(Priority::Low)).await;
+			assert!(acquired.is_ok());
+		}
+
+		/// Poll a future once with the context of the current task.
+		async fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+			std::future::poll_fn(|context| Poll::Ready(Pin::new(&mut *future).poll(context))).await
+		}
+
+		#[tokio::test]
+		async fn cancelled_lock_releases_the_gate() {
+			let shared = Shared::new(0u8);
+			let guard = shared.lock(Priority::Normal).await;
+
+			// Register a waiter, hand the gate over and then cancel the waiter at every point it can be cancelled.
+			let mut waiting = Box::pin(shared.lock(Priority::High));
+			assert!(poll_once(&mut waiting).await.is_pending());
+			drop(guard);
+			if let Poll::Ready(guard) = poll_once(&mut waiting).await {
+				assert!(*guard.client == 0);
+			}
+			drop(waiting);
+
+			assert!(!shared.gate.state().locked);
+			assert!(shared.gate.state().waiting == [0, 0, 0]);
+			let acquired = tokio::time::timeout(Duration::from_secs(1), shared.lock(Priority::Low)).await;
+			assert!(acquired.is_ok());
+		}
+
+		#[tokio::test]
+		async fn guard_unlocks_client_before_gate() {
+			let shared = Arc::new(Shared::new(0u8));
+			let holder = {
+				let shared = shared.clone();
+				tokio::spawn(async move {
+					for _ in 0..100 {
+						*shared.lock(Priority::Normal).await.client += 1;
+						tokio::task::yield_now().await;
+					}
+				})
+			};
+			for _ in 0..100 {
+				// This would panic on `try_lock()` if the gate was released while the mutex is still locked.
+				drop(shared.lock(Priority::High).await);
+				tokio::task::yield_now().await;
+			}
+			holder.await.unwrap();
+			assert!(*shared.lock(Priority::Normal).await.client == 100);
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+	use std::time::Duration;
+
+	#[test]
+	fn gate_prefers_higher_priority() {
+		let gate = Arc::new(Gate::default());
+		let order = Arc::new(Mutex::new(Vec::new()));
+		gate.acquire(Priority::Normal);
+
+		let mut threads = Vec::new();
+		for priority in [Priority::Low, Priority::High, Priority::Normal] {
+			let thread_gate = gate.clone();
+			let order = order.clone();
+			threads.push(std::thread::spawn(move || {
+				thread_gate.acquire(priority);
+				order.lock().unwrap().push(priority);
+				thread_gate.release();
+			}));
+			// Make sure all threads are waiting in a known order.
+			while gate.state().waiting.iter().sum::<usize>() < threads.len() {
+				std::thread::sleep(Duration::from_millis(1));
+			}
+		}
+
+		gate.release();
+		for thread in threads {
+			thread.join().unwrap();
+		}
+		assert!(*order.lock().unwrap() == [Priority::High, Priority::Normal, Priority::Low]);
+	}
+
+	#[test]
+	fn gate_can_be_reused() {
+		let gate = Gate::default();
+		gate.acquire(Priority::Low);
+		gate.release();
+		gate.acquire(Priority::High);
+		gate.release();
+		let state = gate.state();
+		assert!(!state.locked);
+		assert!(state.waiting == [0, 0, 0]);
+	}
+}
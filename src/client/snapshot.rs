@@ -0,0 +1,127 @@
+use super::Client;
+use super::SerialPort;
+use crate::control_table::{Area, ControlTableSnapshot, Register};
+use crate::{RestoreError, TransferError};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+#[super::bisync]
+impl<Port, Buffer> Client<Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Read a range of the control table of a motor.
+	///
+	/// The range is read with as few read instructions as possible,
+	/// where each read is small enough for the status packet to fit in the read buffer of the client.
+	///
+	/// The returned snapshot also contains the model number and firmware version of the motor.
+	pub async fn dump_control_table(
+		&mut self,
+		motor_id: u8,
+		range: Range<u16>,
+	) -> Result<ControlTableSnapshot, TransferError<Port::Error>> {
+		let ping = self.ping(motor_id).await?.data;
+		let chunk_size = self.max_read_size().max(1);
+
+		let mut data = Vec::with_capacity(usize::from(range.end.saturating_sub(range.start)));
+		let mut address = range.start;
+		while address < range.end {
+			let count = chunk_size.min(range.end - address);
+			trace!("reading {} bytes at address {} of motor {}", count, address, motor_id);
+			let response = self.read_bytes::<Vec<u8>>(motor_id, address, count).await?;
+			data.extend_from_slice(&response.data);
+			address += count;
+		}
+
+		Ok(ControlTableSnapshot {
+			motor_id,
+			model: ping.model,
+			firmware: ping.firmware,
+			address: range.start,
+			data,
+		})
+	}
+
+	/// Write a control table snapshot back to a motor.
+	///
+	/// Only the registers from [`ControlTableSnapshot::restorable_registers()`] are written.
+	/// In particular, the ID and baud rate of the motor are never changed.
+	///
+	/// The motor must be the same model as the motor the snapshot was taken from.
+	/// If EEPROM registers are restored, torque is disabled first and restored afterwards, also if restoring them fails.
+	/// RAM registers are written after the EEPROM registers, with the torque enable register written last.
+	pub async fn restore_control_table_snapshot(
+		&mut self,
+		motor_id: u8,
+		snapshot: &ControlTableSnapshot,
+	) -> Result<(), RestoreError<Port::Error>> {
+		self.restore_control_table_registers(motor_id, snapshot, None).await
+	}
+
+	/// Write the registers of a control table snapshot that differ from the current values of a motor.
+	///
+	/// The `current` snapshot holds the current values of the motor, for example as read with [`Self::dump_control_table()`].
+	/// Only the restorable registers that have a different value in `current` are written,
+	/// so torque is only disabled if an EEPROM register actually changes.
+	///
+	/// See [`Self::restore_control_table_snapshot()`] for the order in which registers are written.
+	pub async fn restore_control_table_changes(
+		&mut self,
+		motor_id: u8,
+		snapshot: &ControlTableSnapshot,
+		current: &ControlTableSnapshot,
+	) -> Result<(), RestoreError<Port::Error>> {
+		self.restore_control_table_registers(motor_id, snapshot, Some(current)).await
+	}
+
+	/// Write the restorable registers of a snapshot, skipping registers that already have the same value in `current`.
+	async fn restore_control_table_registers(
+		&mut self,
+		motor_id: u8,
+		snapshot: &ControlTableSnapshot,
+		current: Option<&ControlTableSnapshot>,
+	) -> Result<(), RestoreError<Port::Error>> {
+		let model = self.ping(motor_id).await?.data.model;
+		if model != snapshot.model {
+			return Err(RestoreError::ModelMismatch {
+				motor_id,
+				expected: snapshot.model,
+				actual: model,
+			});
+		}
+
+		let torque_enable = snapshot.control_table().torque_enable;
+		let (eeprom, ram): (Vec<_>, Vec<_>) = snapshot
+			.restorable_registers()
+			.filter(|(register, value)| current.is_none_or(|current| current.get(register) != Some(*value)))
+			.partition(|(register, _)| register.area == Area::Eeprom);
+
+		if !eeprom.is_empty() {
+			let torque = self.disable_torque_for_eeprom(motor_id, torque_enable.address).await?;
+			let result = self.restore_registers(motor_id, &eeprom).await;
+			self.restore_torque(motor_id, torque_enable.address, torque, result).await?;
+		}
+
+		let (torque_changes, ram): (Vec<_>, Vec<_>) = ram.into_iter().partition(|(register, _)| **register == torque_enable);
+		self.restore_registers(motor_id, &ram).await?;
+		self.restore_registers(motor_id, &torque_changes).await?;
+		Ok(())
+	}
+
+	/// Write the values of a list of registers from a snapshot, stopping at the first error.
+	async fn restore_registers(&mut self, motor_id: u8, registers: &[(&Register, &[u8])]) -> Result<(), TransferError<Port::Error>> {
+		for (register, value) in registers {
+			self.restore_register(motor_id, register, value).await?;
+		}
+		Ok(())
+	}
+
+	/// Write the value of a single register from a snapshot.
+	async fn restore_register(&mut self, motor_id: u8, register: &Register, value: &[u8]) -> Result<(), TransferError<Port::Error>> {
+		debug!("restoring register {:?} of motor {}", register.name, motor_id);
+		self.write_bytes(motor_id, register.address, value).await?;
+		Ok(())
+	}
+}
@@ -0,0 +1,40 @@
+use super::Client;
+use super::SerialPort;
+use crate::TransferError;
+
+#[super::bisync]
+impl<Port, Buffer> Client<Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Disable the torque of a motor so that its EEPROM registers can be written.
+	///
+	/// Returns the original value of the torque enable register.
+	/// Pass it to [`Self::restore_torque()`] when done, also if writing the EEPROM registers failed.
+	pub(crate) async fn disable_torque_for_eeprom(&mut self, motor_id: u8, torque_enable: u16) -> Result<u8, TransferError<Port::Error>> {
+		let torque = self.read::<u8>(motor_id, torque_enable).await?.data;
+		if torque != 0 {
+			debug!("disabling torque of motor {} to write EEPROM registers", motor_id);
+			self.write(motor_id, torque_enable, &0u8).await?;
+		}
+		Ok(torque)
+	}
+
+	/// Restore the torque of a motor after writing EEPROM registers, and pass on the result of the writes.
+	///
+	/// The torque is restored even if `result` is an error, in which case that error is returned.
+	/// Otherwise, an error restoring the torque is returned.
+	pub(crate) async fn restore_torque<T, E>(&mut self, motor_id: u8, torque_enable: u16, torque: u8, result: Result<T, E>) -> Result<T, E>
+	where
+		E: From<TransferError<Port::Error>>,
+	{
+		if torque == 0 {
+			return result;
+		}
+		let restored = self.write(motor_id, torque_enable, &torque).await;
+		let value = result?;
+		restored?;
+		Ok(value)
+	}
+}
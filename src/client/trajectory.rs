@@ -0,0 +1,103 @@
+use super::control_loop::sleep_until;
+use super::{Client, SerialPort};
+use crate::client::SyncWriteData;
+use crate::trajectory::{StreamOptions, StreamOutcome, Trajectory};
+use crate::units::x_series;
+use crate::units::Angle;
+use crate::WriteError;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+use std::time::Instant;
+
+#[super::bisync]
+impl<Port, Buffer> Client<Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Stream the set-points of a trajectory to a list of X-series motors.
+	///
+	/// A set-point for all motors is written to the Goal Position register with a sync write instruction every `options.period`,
+	/// until the end position of the trajectory has been written.
+	/// The motors are given in the same order as the positions of the trajectory.
+	///
+	/// If [`StreamOptions::profile_registers`] is set, the peak velocity and acceleration of each motor
+	/// are written to the Profile Velocity and Profile Acceleration registers first.
+	///
+	/// The `abort` flag is checked before each set-point.
+	/// If it is set, streaming stops and the motors keep the last written set-point as goal.
+	/// The flag can be set from another thread or from a signal handler.
+	///
+	/// # Panics
+	/// This function panics if the number of motors does not match the trajectory, or if the period is zero.
+	pub async fn stream_trajectory(
+		&mut self,
+		motor_ids: &[u8],
+		trajectory: &Trajectory,
+		options: &StreamOptions,
+		abort: &AtomicBool,
+	) -> Result<StreamOutcome, WriteError<Port::Error>> {
+		assert_eq!(
+			motor_ids.len(),
+			trajectory.motor_count(),
+			"Client::stream_trajectory: got {} motors for a trajectory of {} motors",
+			motor_ids.len(),
+			trajectory.motor_count()
+		);
+		assert!(!options.period.is_zero(), "Client::stream_trajectory: the period must not be zero");
+
+		if options.profile_registers {
+			let data: Vec<_> = motor_ids
+				.iter()
+				.enumerate()
+				.map(|(i, &motor_id)| SyncWriteData {
+					motor_id,
+					// A profile value of 0 means an infinite velocity or acceleration, so round small values up.
+					data: x_series::PROFILE_ACCELERATION.to_raw(trajectory.peak_acceleration(i)).max(1),
+				})
+				.collect();
+			self.sync_write(x_series::PROFILE_ACCELERATION.register.address, &data).await?;
+			let data: Vec<_> = motor_ids
+				.iter()
+				.enumerate()
+				.map(|(i, &motor_id)| SyncWriteData {
+					motor_id,
+					data: x_series::PROFILE_VELOCITY.to_raw(trajectory.peak_velocity(i)).max(1),
+				})
+				.collect();
+			self.sync_write(x_series::PROFILE_VELOCITY.register.address, &data).await?;
+		}
+
+		let duration = trajectory.duration();
+		let mut positions = alloc::vec![Angle::default(); motor_ids.len()];
+		let mut data: Vec<_> = motor_ids.iter().map(|&motor_id| SyncWriteData { motor_id, data: 0i32 }).collect();
+		let mut last: Option<Duration> = None;
+		let start = Instant::now();
+		let mut scheduled = start;
+		loop {
+			sleep_until(scheduled).await;
+			if abort.load(Ordering::Relaxed) {
+				return Ok(StreamOutcome::Aborted { at: last });
+			}
+
+			let time = (Instant::now() - start).min(duration);
+			trajectory.sample(time, &mut positions);
+			for (entry, &position) in data.iter_mut().zip(&positions) {
+				entry.data = x_series::GOAL_POSITION.to_raw(position);
+			}
+			self.sync_write(x_series::GOAL_POSITION.register.address, &data).await?;
+			last = Some(time);
+			if time >= duration {
+				return Ok(StreamOutcome::Completed);
+			}
+
+			// Skip set-points that should have been written already, instead of trying to catch up.
+			scheduled += options.period;
+			let now = Instant::now();
+			while scheduled < now {
+				scheduled += options.period;
+			}
+		}
+	}
+}
@@ -0,0 +1,53 @@
+use super::Client;
+use super::SerialPort;
+use crate::units::{Quantity, RawValue, UnitRegister};
+use crate::{Response, TransferError};
+
+#[super::bisync]
+impl<Port, Buffer> Client<Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Read a register from a specific motor and convert the value to a physical quantity.
+	///
+	/// See the [`units`](crate::units) module for the available registers.
+	///
+	/// This function will not work correctly if the motor ID is set to [`packet_id::BROADCAST`][crate::bus::packet_id::BROADCAST].
+	pub async fn read_register<T, Q>(
+		&mut self,
+		motor_id: u8,
+		register: UnitRegister<T, Q>,
+	) -> Result<Response<Q>, TransferError<Port::Error>>
+	where
+		T: RawValue,
+		Q: Quantity,
+	{
+		let response = self.read::<T>(motor_id, register.register.address).await?;
+		Ok(Response {
+			motor_id: response.motor_id,
+			alert: response.alert,
+			data: register.to_quantity(response.data),
+		})
+	}
+
+	/// Convert a physical quantity to the raw value of a register and write it to a specific motor.
+	///
+	/// The value is rounded to the nearest raw value.
+	/// See the [`units`](crate::units) module for the available registers.
+	///
+	/// You may specify [`crate::bus::packet_id::BROADCAST`] as motor ID.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	pub async fn write_register<T, Q>(
+		&mut self,
+		motor_id: u8,
+		register: UnitRegister<T, Q>,
+		value: Q,
+	) -> Result<Response<()>, TransferError<Port::Error>>
+	where
+		T: RawValue,
+		Q: Quantity,
+	{
+		self.write(motor_id, register.register.address, &register.to_raw(value)).await
+	}
+}
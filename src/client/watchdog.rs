@@ -0,0 +1,217 @@
+use super::{Client, ControlLoop, SerialPort};
+use crate::bus::Data;
+use crate::client::{Cycle, SafeStopReport, SyncWriteData};
+use crate::control_table::x_series;
+use crate::{TransferError, WatchdogError, WriteError};
+use alloc::vec::Vec;
+use core::ops::ControlFlow;
+use core::time::Duration;
+
+/// The unit of the Bus Watchdog register.
+const WATCHDOG_UNIT: Duration = Duration::from_millis(20);
+
+/// A supervisor that uses the Bus Watchdog of X-series motors to stop them when the controller stops communicating.
+///
+/// When the watchdog is armed, the motors stop moving if they do not receive an instruction within the watchdog timeout.
+/// Any instruction for a motor refreshes its watchdog,
+/// so a [`ControlLoop`] that runs through [`Self::step()`] keeps the watchdog fed as long as it writes goals every cycle.
+/// Use [`Self::feed()`] to refresh the watchdog when there is no other traffic.
+///
+/// When a transfer of the control loop fails, the supervisor performs a safe stop:
+/// it disables the torque of all supervised motors with a sync write to the broadcast ID,
+/// and reads back the Torque Enable register of each motor to find out which motors acknowledged it.
+/// The synchronous supervisor also performs a safe stop when it is dropped without calling [`Self::disarm()`].
+/// The asynchronous supervisor can not do that from its destructor, so it only logs a warning:
+/// call [`Self::safe_stop()`] or [`Self::disarm()`] before dropping it.
+///
+/// Note that a triggered watchdog sets the Bus Watchdog register to -1, and the motor rejects new goal values until the register is cleared.
+/// Arming the watchdog again clears it.
+pub struct Watchdog<'c, Port, Buffer = crate::bus::DefaultBuffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	client: &'c mut Client<Port, Buffer>,
+	motor_ids: Vec<u8>,
+	timeout: u8,
+	armed: bool,
+}
+
+impl<Port, Buffer> core::fmt::Debug for Watchdog<'_, Port, Buffer>
+where
+	Port: SerialPort + core::fmt::Debug,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("Watchdog")
+			.field("serial_port", self.client.serial_port())
+			.field("motor_ids", &self.motor_ids)
+			.field("timeout", &self.timeout())
+			.field("armed", &self.armed)
+			.finish()
+	}
+}
+
+#[super::bisync]
+impl<'c, Port, Buffer> Watchdog<'c, Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Arm the Bus Watchdog of a set of motors.
+	///
+	/// The timeout is rounded up to a multiple of 20 milliseconds.
+	/// The Bus Watchdog register of each motor is cleared and then set to the timeout,
+	/// with a separate write for each motor, so that missing motors are reported as an error.
+	///
+	/// Returns an error without writing anything if the timeout is zero or longer than 2.54 seconds.
+	pub async fn arm(client: &'c mut Client<Port, Buffer>, motor_ids: &[u8], timeout: Duration) -> Result<Self, WatchdogError<Port::Error>> {
+		let raw_timeout = timeout.as_nanos().div_ceil(WATCHDOG_UNIT.as_nanos());
+		let raw_timeout = match u8::try_from(raw_timeout) {
+			Ok(raw_timeout @ 1..=127) => raw_timeout,
+			_ => return Err(WatchdogError::InvalidTimeout { timeout }),
+		};
+
+		for &motor_id in motor_ids {
+			client.write(motor_id, x_series::BUS_WATCHDOG.address, &0u8).await?;
+			client.write(motor_id, x_series::BUS_WATCHDOG.address, &raw_timeout).await?;
+		}
+		Ok(Self {
+			client,
+			motor_ids: motor_ids.to_vec(),
+			timeout: raw_timeout,
+			armed: true,
+		})
+	}
+
+	/// Get the client used by the supervisor.
+	///
+	/// Transfers made directly with the client are not supervised,
+	/// but they do refresh the watchdog of the motors they address.
+	pub fn client(&mut self) -> &mut Client<Port, Buffer> {
+		self.client
+	}
+
+	/// Get the IDs of the supervised motors.
+	pub fn motor_ids(&self) -> &[u8] {
+		&self.motor_ids
+	}
+
+	/// Get the watchdog timeout, rounded up to a multiple of 20 milliseconds.
+	pub fn timeout(&self) -> Duration {
+		WATCHDOG_UNIT * self.timeout.into()
+	}
+
+	/// Check if the supervisor is still armed.
+	///
+	/// The supervisor is disarmed after a safe stop or a call to [`Self::disarm()`].
+	pub fn is_armed(&self) -> bool {
+		self.armed
+	}
+
+	/// Refresh the watchdog of all supervised motors.
+	///
+	/// This writes the watchdog timeout again with a single sync write.
+	pub async fn feed(&mut self) -> Result<(), WriteError<Port::Error>> {
+		let data: Vec<_> = self
+			.motor_ids
+			.iter()
+			.map(|&motor_id| SyncWriteData {
+				motor_id,
+				data: self.timeout,
+			})
+			.collect();
+		self.client.sync_write(x_series::BUS_WATCHDOG.address, &data).await
+	}
+
+	/// Run one cycle of a control loop, and perform a safe stop if a transfer fails.
+	///
+	/// See [`ControlLoop::step()`] for details.
+	/// The error of the control loop is returned after the safe stop, and the result of the safe stop is logged.
+	pub async fn step<T, S, B, F>(&mut self, control_loop: &mut ControlLoop<T, S>, compute: F) -> Result<ControlFlow<B>, TransferError<Port::Error>>
+	where
+		T: Data + Clone + Default,
+		S: Data,
+		F: FnMut(&Cycle, &[S], &mut [T]) -> ControlFlow<B>,
+	{
+		let result = control_loop.step(self.client, compute).await;
+		if result.is_err() {
+			warn!("control loop transfer failed, disabling torque of supervised motors");
+			self.safe_stop_logged().await;
+		}
+		result
+	}
+
+	/// Disable the torque of all supervised motors and report which motors acknowledged it.
+	///
+	/// The torque is disabled with a single sync write to the broadcast ID.
+	/// Afterwards, the Torque Enable register of each motor is read back.
+	/// Motors that do not reply or that still have their torque enabled are reported as unacknowledged.
+	///
+	/// The supervisor is disarmed afterwards, even if the sync write fails.
+	pub async fn safe_stop(&mut self) -> Result<SafeStopReport, WriteError<Port::Error>> {
+		self.armed = false;
+		let data: Vec<_> = self.motor_ids.iter().map(|&motor_id| SyncWriteData { motor_id, data: 0u8 }).collect();
+		self.client.sync_write(x_series::TORQUE_ENABLE.address, &data).await?;
+
+		let mut report = SafeStopReport::default();
+		for &motor_id in &self.motor_ids {
+			match self.client.read::<u8>(motor_id, x_series::TORQUE_ENABLE.address).await {
+				Ok(response) if response.data == 0 => report.acknowledged.push(motor_id),
+				_ => report.unacknowledged.push(motor_id),
+			}
+		}
+		Ok(report)
+	}
+
+	/// Disarm the Bus Watchdog of all supervised motors without disabling the torque.
+	///
+	/// Use this to hand the motors back to unsupervised control.
+	pub async fn disarm(mut self) -> Result<(), WriteError<Port::Error>> {
+		self.armed = false;
+		let data: Vec<_> = self.motor_ids.iter().map(|&motor_id| SyncWriteData { motor_id, data: 0u8 }).collect();
+		self.client.sync_write(x_series::BUS_WATCHDOG.address, &data).await
+	}
+
+	/// Perform a safe stop and log the result.
+	async fn safe_stop_logged(&mut self) {
+		match self.safe_stop().await {
+			Ok(report) if report.unacknowledged.is_empty() => debug!("disabled torque of motors {:?}", report.acknowledged),
+			Ok(report) => warn!(
+				"disabled torque of motors {:?}, but motors {:?} did not acknowledge",
+				report.acknowledged, report.unacknowledged
+			),
+			Err(_) => warn!("failed to send torque disable instruction to motors {:?}", self.motor_ids),
+		}
+	}
+}
+
+#[super::only_sync]
+impl<Port, Buffer> Drop for Watchdog<'_, Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn drop(&mut self) {
+		if self.armed {
+			warn!("watchdog supervisor dropped while armed, disabling torque of supervised motors");
+			self.safe_stop_logged();
+		}
+	}
+}
+
+#[super::only_async]
+impl<Port, Buffer> Drop for Watchdog<'_, Port, Buffer>
+where
+	Port: SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn drop(&mut self) {
+		if self.armed {
+			warn!(
+				"watchdog supervisor dropped while armed, motors {:?} keep their torque until their Bus Watchdog expires",
+				self.motor_ids
+			);
+		}
+	}
+}
@@ -0,0 +1,391 @@
+//! Declarative configuration of the motors on a bus.
+//!
+//! A [`BusConfig`] describes the motors that are expected on a bus, and the values of their registers.
+//! It can be loaded from a TOML or YAML file, compared against the live bus with [`Client::diff_config`](crate::Client::diff_config),
+//! and applied with [`Client::apply_config`](crate::Client::apply_config).
+//!
+//! Registers are identified by their name in the control table of the motor model (see [`crate::control_table`]),
+//! and values are given as raw register values.
+//!
+//! ```toml
+//! [[motors]]
+//! id = 1
+//! name = "left_hip"
+//! model = 1020
+//!
+//! [motors.registers]
+//! "Operating Mode" = 3
+//! "Return Delay Time" = 0
+//! "Position P Gain" = 800
+//! "Homing Offset" = -1024
+//! ```
+//!
+//! The same configuration in YAML:
+//!
+//! ```yaml
+//! motors:
+//!   - id: 1
+//!     name: left_hip
+//!     model: 1020
+//!     registers:
+//!       Operating Mode: 3
+//!       Return Delay Time: 0
+//!       Position P Gain: 800
+//!       Homing Offset: -1024
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::control_table::{ControlTable, Register};
+use crate::TransferError;
+
+/// The expected configuration of all motors on a bus.
+#[derive(Debug, Clone, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BusConfig {
+	/// The expected motors.
+	#[serde(default)]
+	pub motors: Vec<MotorConfig>,
+}
+
+/// The expected configuration of a single motor.
+#[derive(Debug, Clone, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MotorConfig {
+	/// The ID of the motor.
+	pub id: u8,
+
+	/// An optional human readable name of the motor.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+
+	/// The expected model number of the motor.
+	///
+	/// If set, the configuration is only applied if the motor reports this model number.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub model: Option<u16>,
+
+	/// The expected raw register values, by register name.
+	#[serde(default)]
+	pub registers: BTreeMap<String, i64>,
+}
+
+impl BusConfig {
+	/// Parse a configuration from a TOML document.
+	pub fn from_toml(data: &str) -> Result<Self, LoadError> {
+		toml::from_str(data).map_err(LoadError::Toml)
+	}
+
+	/// Parse a configuration from a YAML document.
+	pub fn from_yaml(data: &str) -> Result<Self, LoadError> {
+		serde_yaml::from_str(data).map_err(LoadError::Yaml)
+	}
+
+	/// Load a configuration from a file.
+	///
+	/// The format is determined by the file extension: `.toml`, `.yaml` or `.yml`.
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+		let path = path.as_ref();
+		let parse = match path.extension().and_then(|extension| extension.to_str()) {
+			Some("toml") => Self::from_toml,
+			Some("yaml" | "yml") => Self::from_yaml,
+			_ => return Err(LoadError::UnknownFormat(path.to_owned())),
+		};
+		let data = std::fs::read_to_string(path).map_err(LoadError::Io)?;
+		parse(&data)
+	}
+}
+
+/// The differences between a [`BusConfig`] and the live bus.
+///
+/// Returned by [`Client::diff_config`](crate::Client::diff_config), and by [`Client::apply_config`](crate::Client::apply_config) to report the changes that were applied.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ConfigDiff {
+	/// The motors that were found on the bus, with the registers that differ from the configuration.
+	pub motors: Vec<MotorDiff>,
+
+	/// The IDs of the configured motors that did not reply.
+	pub missing_motors: Vec<u8>,
+}
+
+impl ConfigDiff {
+	/// Check if the bus matches the configuration.
+	pub fn is_empty(&self) -> bool {
+		self.missing_motors.is_empty() && self.motors.iter().all(|motor| motor.changes.is_empty())
+	}
+}
+
+/// The differences between a [`MotorConfig`] and a motor on the bus.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MotorDiff {
+	/// The ID of the motor.
+	pub motor_id: u8,
+
+	/// The model number reported by the motor.
+	pub model: u16,
+
+	/// The registers that differ from the configuration.
+	pub changes: Vec<RegisterChange>,
+}
+
+/// A register that differs from the configuration.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RegisterChange {
+	/// The register.
+	pub register: Register,
+
+	/// The current value of the register.
+	///
+	/// The value is sign-extended if the configured value is negative.
+	pub current: i64,
+
+	/// The configured value of the register.
+	pub expected: i64,
+}
+
+/// An error that can occur while loading a configuration.
+#[derive(Debug)]
+pub enum LoadError {
+	/// Failed to read the file.
+	Io(std::io::Error),
+
+	/// The file extension does not indicate a supported format.
+	UnknownFormat(PathBuf),
+
+	/// Failed to parse the TOML document.
+	Toml(toml::de::Error),
+
+	/// Failed to parse the YAML document.
+	Yaml(serde_yaml::Error),
+}
+
+/// An error that can occur while comparing or applying a configuration.
+#[derive(Debug)]
+pub enum ConfigError<E> {
+	/// Communication with a motor failed.
+	TransferError(TransferError<E>),
+
+	/// The configuration does not match the motor.
+	InvalidConfig(InvalidConfig),
+}
+
+/// The configuration of a motor is not valid for the motor on the bus.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum InvalidConfig {
+	/// The motor reported a different model number than configured.
+	ModelMismatch {
+		/// The ID of the motor.
+		motor_id: u8,
+
+		/// The configured model number.
+		expected: u16,
+
+		/// The model number reported by the motor.
+		actual: u16,
+	},
+
+	/// The control table of the motor has no register with the configured name.
+	UnknownRegister {
+		/// The ID of the motor.
+		motor_id: u8,
+
+		/// The name of the register.
+		name: String,
+	},
+
+	/// The register can not be set with a configuration.
+	///
+	/// This is the case for read-only registers, registers larger than 4 bytes and the ID and baud rate registers.
+	/// Use [`Client::change_id`](crate::Client::change_id) and [`Client::change_baud_rate`](crate::Client::change_baud_rate) to change the ID or baud rate of a motor.
+	NotConfigurable {
+		/// The ID of the motor.
+		motor_id: u8,
+
+		/// The name of the register.
+		name: String,
+	},
+
+	/// The configured value does not fit in the register.
+	ValueOutOfRange {
+		/// The ID of the motor.
+		motor_id: u8,
+
+		/// The name of the register.
+		name: String,
+
+		/// The configured value.
+		value: i64,
+	},
+}
+
+/// A configured register value, resolved against the control table of a motor.
+pub(crate) struct ResolvedRegister {
+	pub(crate) register: Register,
+	pub(crate) value: i64,
+}
+
+impl MotorConfig {
+	/// Resolve the configured register names against the control table of the motor.
+	pub(crate) fn resolve(&self, model: u16, control_table: &ControlTable) -> Result<Vec<ResolvedRegister>, InvalidConfig> {
+		if let Some(expected) = self.model {
+			if expected != model {
+				return Err(InvalidConfig::ModelMismatch {
+					motor_id: self.id,
+					expected,
+					actual: model,
+				});
+			}
+		}
+
+		let mut resolved = Vec::with_capacity(self.registers.len());
+		for (name, &value) in &self.registers {
+			let register = control_table.register(name).ok_or_else(|| InvalidConfig::UnknownRegister {
+				motor_id: self.id,
+				name: name.clone(),
+			})?;
+			if !register.is_writable() || register.size > 4 || *register == control_table.id || *register == control_table.baud_rate {
+				return Err(InvalidConfig::NotConfigurable {
+					motor_id: self.id,
+					name: name.clone(),
+				});
+			}
+			if register.encode_value(value).is_none() {
+				return Err(InvalidConfig::ValueOutOfRange {
+					motor_id: self.id,
+					name: name.clone(),
+					value,
+				});
+			}
+			resolved.push(ResolvedRegister { register: *register, value });
+		}
+		resolved.sort_by_key(|resolved| resolved.register.address);
+		Ok(resolved)
+	}
+}
+
+impl<E> From<TransferError<E>> for ConfigError<E> {
+	fn from(other: TransferError<E>) -> Self {
+		Self::TransferError(other)
+	}
+}
+
+impl<E> From<InvalidConfig> for ConfigError<E> {
+	fn from(other: InvalidConfig) -> Self {
+		Self::InvalidConfig(other)
+	}
+}
+
+impl std::fmt::Display for LoadError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::Io(e) => write!(f, "failed to read configuration: {}", e),
+			Self::UnknownFormat(path) => write!(
+				f,
+				"unknown configuration format: {}: expected a .toml, .yaml or .yml file",
+				path.display()
+			),
+			Self::Toml(e) => write!(f, "invalid TOML configuration: {}", e),
+			Self::Yaml(e) => write!(f, "invalid YAML configuration: {}", e),
+		}
+	}
+}
+
+impl<E> std::fmt::Display for ConfigError<E>
+where
+	E: std::fmt::Display,
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::TransferError(e) => write!(f, "{}", e),
+			Self::InvalidConfig(e) => write!(f, "{}", e),
+		}
+	}
+}
+
+impl std::fmt::Display for InvalidConfig {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::ModelMismatch { motor_id, expected, actual } => write!(
+				f,
+				"motor {} has model number {}, but the configuration expects {}",
+				motor_id, actual, expected
+			),
+			Self::UnknownRegister { motor_id, name } => write!(f, "motor {} has no register named {:?}", motor_id, name),
+			Self::NotConfigurable { motor_id, name } => write!(f, "register {:?} of motor {} can not be configured", name, motor_id),
+			Self::ValueOutOfRange { motor_id, name, value } => write!(
+				f,
+				"value {} does not fit in register {:?} of motor {}",
+				value, name, motor_id
+			),
+		}
+	}
+}
+
+impl std::error::Error for LoadError {}
+impl<E: std::fmt::Debug + std::fmt::Display> std::error::Error for ConfigError<E> {}
+impl std::error::Error for InvalidConfig {}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::control_table::x_series;
+	use assert2::{assert, let_assert};
+
+	#[test]
+	fn parse_toml_and_yaml() {
+		let toml = r#"
+			[[motors]]
+			id = 1
+			name = "left_hip"
+
+			[motors.registers]
+			"Position P Gain" = 800
+			"Homing Offset" = -1024
+
+			[[motors]]
+			id = 2
+		"#;
+		let yaml = r#"
+motors:
+  - id: 1
+    name: left_hip
+    registers:
+      Position P Gain: 800
+      Homing Offset: -1024
+  - id: 2
+"#;
+		let_assert!(Ok(from_toml) = BusConfig::from_toml(toml));
+		let_assert!(Ok(from_yaml) = BusConfig::from_yaml(yaml));
+		assert!(from_toml == from_yaml);
+		assert!(from_toml.motors.len() == 2);
+		assert!(from_toml.motors[0].registers["Homing Offset"] == -1024);
+		assert!(from_toml.motors[1].registers.is_empty());
+
+		let_assert!(Err(LoadError::Toml(_)) = BusConfig::from_toml("[[motors]]\nid = 1\nspeed = 3\n"));
+	}
+
+	#[test]
+	fn resolve_registers() {
+		let mut motor = MotorConfig {
+			id: 1,
+			..Default::default()
+		};
+		motor.registers.insert("Position P Gain".into(), 800);
+		motor.registers.insert("Operating Mode".into(), 3);
+		let_assert!(Ok(resolved) = motor.resolve(1020, &x_series::CONTROL_TABLE));
+		assert!(resolved[0].register == x_series::OPERATING_MODE);
+		assert!(resolved[1].register == x_series::POSITION_P_GAIN);
+
+		motor.model = Some(1060);
+		let_assert!(Err(InvalidConfig::ModelMismatch { .. }) = motor.resolve(1020, &x_series::CONTROL_TABLE));
+
+		motor.model = None;
+		motor.registers.insert("ID".into(), 3);
+		let_assert!(Err(InvalidConfig::NotConfigurable { .. }) = motor.resolve(1020, &x_series::CONTROL_TABLE));
+
+		motor.registers.remove("ID");
+		motor.registers.insert("Operating Mode".into(), 300);
+		let_assert!(Err(InvalidConfig::ValueOutOfRange { .. }) = motor.resolve(1020, &x_series::CONTROL_TABLE));
+	}
+}
@@ -0,0 +1,315 @@
+//! Indirect address mappings for the X-series.
+//!
+//! The X-series has indirect address registers, which make the matching indirect data registers mirror arbitrary other registers.
+//! This can be used to pack scattered registers into one contiguous block,
+//! so that they can be read or written with a single (sync) read or write instruction.
+
+use super::{x_series, Register};
+use crate::bus::Data;
+use crate::IndirectMappingError;
+
+/// The maximum number of bytes in an indirect data block.
+const MAX_SIZE: usize = 20;
+
+/// A block of indirect addresses and the matching indirect data.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum IndirectBlock {
+	/// Indirect address 1 to 20, at address 168, with the data at address 224.
+	First,
+
+	/// Indirect address 21 to 28, at address 578, with the data at address 634.
+	Second,
+}
+
+impl IndirectBlock {
+	/// Get the register holding the indirect addresses of this block.
+	pub fn addresses(self) -> Register {
+		match self {
+			Self::First => x_series::INDIRECT_ADDRESSES_1,
+			Self::Second => x_series::INDIRECT_ADDRESSES_21,
+		}
+	}
+
+	/// Get the register holding the indirect data of this block.
+	pub fn data(self) -> Register {
+		match self {
+			Self::First => x_series::INDIRECT_DATA_1,
+			Self::Second => x_series::INDIRECT_DATA_21,
+		}
+	}
+
+	/// Get the number of bytes that can be mapped in this block.
+	pub fn size(self) -> u16 {
+		self.data().size
+	}
+}
+
+/// A builder for an [`IndirectLayout`].
+///
+/// Add the registers that should be packed together in the order they should appear in the indirect data block.
+/// Then use [`Client::write_indirect_mapping()`](crate::Client::write_indirect_mapping) to write the resulting layout to the motors.
+///
+/// # Example
+/// ```
+/// use dynamixel2::control_table::x_series;
+/// use dynamixel2::control_table::IndirectMapping;
+///
+/// let layout = IndirectMapping::new()
+///   .register(x_series::PRESENT_POSITION)
+///   .register(x_series::PRESENT_CURRENT)
+///   .register(x_series::PRESENT_TEMPERATURE)
+///   .register(x_series::HARDWARE_ERROR_STATUS)
+///   .build()
+///   .unwrap();
+/// assert_eq!(layout.address(), 224);
+/// assert_eq!(layout.size(), 8);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IndirectMapping {
+	block: IndirectBlock,
+	registers: [Option<Register>; MAX_SIZE],
+	count: usize,
+	size: usize,
+}
+
+impl Default for IndirectMapping {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl IndirectMapping {
+	/// Create a new mapping for the first block of indirect addresses.
+	pub fn new() -> Self {
+		Self::with_block(IndirectBlock::First)
+	}
+
+	/// Create a new mapping for a specific block of indirect addresses.
+	pub fn with_block(block: IndirectBlock) -> Self {
+		Self {
+			block,
+			registers: [None; MAX_SIZE],
+			count: 0,
+			size: 0,
+		}
+	}
+
+	/// Add a register to the end of the mapping.
+	pub fn register(mut self, register: Register) -> Self {
+		if let Some(slot) = self.registers.get_mut(self.count) {
+			*slot = Some(register);
+			self.count += 1;
+		}
+		self.size += usize::from(register.size);
+		self
+	}
+
+	/// Check the mapping and compute the layout of the indirect data block.
+	///
+	/// Returns an error if the registers do not fit in the block,
+	/// or if a register was added twice or overlaps with a register that was added before.
+	pub fn build(&self) -> Result<IndirectLayout, IndirectMappingError> {
+		if self.size > usize::from(self.block.size()) {
+			return Err(IndirectMappingError::TooLarge {
+				required_size: self.size,
+				block_size: self.block.size(),
+			});
+		}
+
+		let mut fields = [None; MAX_SIZE];
+		let mut offset = 0;
+		for (i, register) in self.registers[..self.count].iter().flatten().enumerate() {
+			let overlaps = |other: &Register| other.address < register.range().end && register.address < other.range().end;
+			if let Some(other) = self.registers[..i].iter().flatten().find(|other| overlaps(other)) {
+				if other == register {
+					return Err(IndirectMappingError::DuplicateRegister { name: register.name });
+				}
+				return Err(IndirectMappingError::OverlappingRegister {
+					name: register.name,
+					other: other.name,
+				});
+			}
+			fields[i] = Some(IndirectField {
+				register: *register,
+				offset,
+			});
+			offset += register.size;
+		}
+		Ok(IndirectLayout {
+			block: self.block,
+			fields,
+			count: self.count,
+			size: offset,
+		})
+	}
+}
+
+/// A register in an indirect data block.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct IndirectField {
+	/// The register that is mirrored.
+	pub register: Register,
+
+	/// The offset of the register in the indirect data block.
+	pub offset: u16,
+}
+
+impl IndirectField {
+	/// Get the raw bytes of the field from an indirect data block.
+	///
+	/// Returns `None` if the data is too short.
+	pub fn get<'a>(&self, data: &'a [u8]) -> Option<&'a [u8]> {
+		let start = usize::from(self.offset);
+		data.get(start..start + usize::from(self.register.size))
+	}
+}
+
+/// The layout of an indirect data block, created with an [`IndirectMapping`].
+#[derive(Debug, Clone)]
+pub struct IndirectLayout {
+	block: IndirectBlock,
+	fields: [Option<IndirectField>; MAX_SIZE],
+	count: usize,
+	size: u16,
+}
+
+impl IndirectLayout {
+	/// Get the block of indirect addresses used by the layout.
+	pub fn block(&self) -> IndirectBlock {
+		self.block
+	}
+
+	/// Get the address of the indirect data block.
+	///
+	/// Use this address to read or write the mapped registers.
+	pub fn address(&self) -> u16 {
+		self.block.data().address
+	}
+
+	/// Get the number of mapped bytes.
+	///
+	/// Use this as count to read all mapped registers.
+	pub fn size(&self) -> u16 {
+		self.size
+	}
+
+	/// Iterate over the mapped registers, in the order of the data block.
+	pub fn fields(&self) -> impl Iterator<Item = &IndirectField> {
+		self.fields[..self.count].iter().flatten()
+	}
+
+	/// Find a mapped register by name.
+	pub fn field(&self, name: &str) -> Option<&IndirectField> {
+		self.fields().find(|field| field.register.name == name)
+	}
+
+	/// Iterate over the indirect addresses to write, one for each mapped byte.
+	pub fn indirect_addresses(&self) -> impl Iterator<Item = u16> + '_ {
+		self.fields().flat_map(|field| field.register.range())
+	}
+
+	/// Decode the value of a mapped register from an indirect data block.
+	///
+	/// Returns `None` if the register is not mapped or if the size of `T` does not match the register,
+	/// and an error if the data is too short.
+	pub fn get<T: Data>(&self, data: &[u8], register: &Register) -> Option<Result<T, crate::InvalidMessage>> {
+		let field = self.fields().find(|field| field.register == *register)?;
+		if field.register.size != T::ENCODED_SIZE {
+			return None;
+		}
+		let end = usize::from(field.offset + field.register.size);
+		if let Err(e) = crate::InvalidParameterCount::check_min(data.len(), end) {
+			return Some(Err(e.into()));
+		}
+		Some(T::decode(&data[usize::from(field.offset)..end]))
+	}
+
+	/// Split an indirect data block into the raw bytes of each mapped register.
+	///
+	/// Fields that are not fully contained in `data` are skipped.
+	pub fn decode<'a>(&'a self, data: &'a [u8]) -> impl Iterator<Item = (&'a Register, &'a [u8])> {
+		self.fields().filter_map(move |field| Some((&field.register, field.get(data)?)))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::control_table::{Access, Area};
+	use assert2::{assert, let_assert};
+
+	fn layout() -> IndirectLayout {
+		let_assert!(
+			Ok(layout) = IndirectMapping::new()
+				.register(x_series::PRESENT_POSITION)
+				.register(x_series::PRESENT_CURRENT)
+				.register(x_series::PRESENT_TEMPERATURE)
+				.register(x_series::HARDWARE_ERROR_STATUS)
+				.build()
+		);
+		layout
+	}
+
+	#[test]
+	fn build_layout() {
+		let layout = layout();
+		assert!(layout.address() == 224);
+		assert!(layout.size() == 8);
+		assert!(layout.field("Present Current").map(|field| field.offset) == Some(4));
+		assert!(layout.indirect_addresses().eq([132, 133, 134, 135, 126, 127, 146, 70]));
+	}
+
+	#[test]
+	fn decode_layout() {
+		let layout = layout();
+		let data = [0x00, 0x08, 0x00, 0x00, 0xF6, 0xFF, 40, 0x04];
+		assert!(let Some(Ok(2048)) = layout.get::<i32>(&data, &x_series::PRESENT_POSITION));
+		assert!(let Some(Ok(-10)) = layout.get::<i16>(&data, &x_series::PRESENT_CURRENT));
+		assert!(let Some(Ok(40)) = layout.get::<u8>(&data, &x_series::PRESENT_TEMPERATURE));
+		assert!(let None = layout.get::<u16>(&data, &x_series::PRESENT_TEMPERATURE));
+		assert!(let None = layout.get::<u8>(&data, &x_series::LED));
+		assert!(let Some(Err(_)) = layout.get::<u8>(&data[..4], &x_series::PRESENT_TEMPERATURE));
+
+		let names = layout.decode(&data).map(|(register, data)| (register.name, data.len()));
+		assert!(names.eq([("Present Position", 4), ("Present Current", 2), ("Present Temperature", 1), ("Hardware Error Status", 1)]));
+	}
+
+	#[test]
+	fn invalid_mapping() {
+		let mapping = IndirectMapping::with_block(IndirectBlock::Second)
+			.register(x_series::PRESENT_POSITION)
+			.register(x_series::PRESENT_VELOCITY)
+			.register(x_series::PRESENT_TEMPERATURE);
+		let_assert!(Err(IndirectMappingError::TooLarge { required_size: 9, block_size: 8 }) = mapping.build());
+
+		let mapping = IndirectMapping::new()
+			.register(x_series::PRESENT_POSITION)
+			.register(x_series::PRESENT_POSITION);
+		let_assert!(Err(IndirectMappingError::DuplicateRegister { name: "Present Position" }) = mapping.build());
+
+		// The low bytes of Present Position, which overlap with the full register.
+		let low_position = Register::new("Present Position Low", 132, 2, Access::Read, Area::Ram);
+		let mapping = IndirectMapping::new()
+			.register(x_series::PRESENT_POSITION)
+			.register(x_series::PRESENT_CURRENT)
+			.register(low_position);
+		let_assert!(
+			Err(IndirectMappingError::OverlappingRegister {
+				name: "Present Position Low",
+				other: "Present Position",
+			}) = mapping.build()
+		);
+
+		// Adjacent registers do not overlap.
+		let mapping = IndirectMapping::new()
+			.register(x_series::PRESENT_VELOCITY)
+			.register(x_series::PRESENT_POSITION);
+		let_assert!(Ok(_) = mapping.build());
+
+		let mut mapping = IndirectMapping::new();
+		for _ in 0..25 {
+			mapping = mapping.register(x_series::LED);
+		}
+		let_assert!(Err(IndirectMappingError::TooLarge { required_size: 25, .. }) = mapping.build());
+	}
+}
@@ -0,0 +1,309 @@
+//! Control table layouts of known DYNAMIXEL models.
+//!
+//! Each DYNAMIXEL motor exposes its settings and state as registers in a control table.
+//! The layout of the control table depends on the model of the motor.
+//! This module contains the layouts of the supported model families,
+//! and a lookup from the model number reported by a ping to the layout.
+//!
+//! If a model is not known, the X-series layout is a reasonable guess for most Protocol 2.0 motors.
+
+use crate::client::BaudRate;
+use crate::units::ModelUnits;
+
+pub mod x_series;
+pub mod xl320;
+
+mod indirect;
+pub use indirect::{IndirectBlock, IndirectField, IndirectLayout, IndirectMapping};
+
+#[cfg(feature = "alloc")]
+mod snapshot;
+#[cfg(feature = "alloc")]
+pub use snapshot::ControlTableSnapshot;
+
+/// The access rights of a register.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Access {
+	/// The register can only be read.
+	Read,
+
+	/// The register can be read and written.
+	ReadWrite,
+}
+
+/// The memory area of a register.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Area {
+	/// Non-volatile memory, retained when the motor is powered off.
+	///
+	/// Registers in the EEPROM area can only be written while torque is disabled.
+	Eeprom,
+
+	/// Volatile memory, reset to the default values when the motor is powered on.
+	Ram,
+}
+
+/// A register in the control table of a motor.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Register {
+	/// The name of the register, as used in the online manual.
+	pub name: &'static str,
+
+	/// The address of the register.
+	pub address: u16,
+
+	/// The size of the register in bytes.
+	pub size: u16,
+
+	/// The access rights of the register.
+	pub access: Access,
+
+	/// The memory area of the register.
+	pub area: Area,
+}
+
+impl Register {
+	/// Create a new register description.
+	pub const fn new(name: &'static str, address: u16, size: u16, access: Access, area: Area) -> Self {
+		Self {
+			name,
+			address,
+			size,
+			access,
+			area,
+		}
+	}
+
+	/// Check if the register can be written.
+	pub fn is_writable(&self) -> bool {
+		self.access == Access::ReadWrite
+	}
+
+	/// Get the address range of the register.
+	pub fn range(&self) -> core::ops::Range<u16> {
+		self.address..self.address + self.size
+	}
+
+	/// Encode a value for the register as little endian bytes.
+	///
+	/// Negative values are encoded in two's complement.
+	/// Only the first [`Self::size`] bytes of the result are used.
+	/// Returns `None` if the register is larger than 4 bytes, or if the value does not fit in the register.
+	pub fn encode_value(&self, value: i64) -> Option<[u8; 4]> {
+		if !(1..=4).contains(&self.size) {
+			return None;
+		}
+		let bits = 8 * u32::from(self.size);
+		let min = -(1i64 << (bits - 1));
+		let max = (1i64 << bits) - 1;
+		if value < min || value > max {
+			return None;
+		}
+		Some((value as u32).to_le_bytes())
+	}
+
+	/// Decode the little endian value of the register, optionally sign-extending it.
+	///
+	/// Returns `None` if the register is larger than 4 bytes, or if the length of `data` does not match the size of the register.
+	pub fn decode_value(&self, data: &[u8], signed: bool) -> Option<i64> {
+		if !(1..=4).contains(&self.size) || data.len() != usize::from(self.size) {
+			return None;
+		}
+		let mut bytes = [0; 4];
+		bytes[..data.len()].copy_from_slice(data);
+		let value = u32::from_le_bytes(bytes);
+		let bits = 8 * u32::from(self.size);
+		if signed {
+			let shift = 64 - bits;
+			Some((i64::from(value) << shift) >> shift)
+		} else {
+			Some(i64::from(value))
+		}
+	}
+}
+
+/// The control table layout of a model family.
+#[derive(Debug)]
+pub struct ControlTable {
+	/// The name of the model family.
+	pub name: &'static str,
+
+	/// All registers in the control table, sorted by address.
+	pub registers: &'static [Register],
+
+	/// The register holding the firmware version.
+	pub firmware_version: Register,
+
+	/// The register holding the motor ID.
+	pub id: Register,
+
+	/// The register holding the baud rate.
+	pub baud_rate: Register,
+
+	/// The register to enable or disable torque.
+	pub torque_enable: Register,
+
+	/// The supported baud rates, indexed by the value of the baud rate register.
+	pub baud_rates: &'static [BaudRate],
+}
+
+impl ControlTable {
+	/// Find a register by name.
+	pub fn register(&self, name: &str) -> Option<&'static Register> {
+		self.registers.iter().find(|register| register.name == name)
+	}
+
+	/// Find the register that contains the given address.
+	pub fn register_at(&self, address: u16) -> Option<&'static Register> {
+		self.registers.iter().find(|register| register.range().contains(&address))
+	}
+
+	/// Get the address range covering all registers in the control table.
+	pub fn address_range(&self) -> core::ops::Range<u16> {
+		let start = self.registers.first().map_or(0, |register| register.address);
+		let end = self.registers.last().map_or(0, |register| register.range().end);
+		start..end
+	}
+
+	/// Get the value of the baud rate register for a baud rate.
+	///
+	/// Returns `None` if the baud rate is not supported by the model family.
+	pub fn encode_baud_rate(&self, baud_rate: BaudRate) -> Option<u8> {
+		let index = self.baud_rates.iter().position(|supported| *supported == baud_rate)?;
+		Some(index as u8)
+	}
+
+	/// Get the baud rate for a value of the baud rate register.
+	pub fn decode_baud_rate(&self, value: u8) -> Option<BaudRate> {
+		self.baud_rates.get(usize::from(value)).copied()
+	}
+}
+
+/// A known motor model.
+#[derive(Debug)]
+pub struct Model {
+	/// The model number, as reported by a ping.
+	pub number: u16,
+
+	/// The name of the model.
+	pub name: &'static str,
+
+	/// The control table layout of the model.
+	pub control_table: &'static ControlTable,
+
+	/// The size of the raw units of the model.
+	pub units: ModelUnits,
+}
+
+impl Model {
+	const fn new(number: u16, name: &'static str, control_table: &'static ControlTable, units: ModelUnits) -> Self {
+		Self {
+			number,
+			name,
+			control_table,
+			units,
+		}
+	}
+}
+
+/// All known models.
+pub static MODELS: &[Model] = &[
+	Model::new(30, "MX-28(2.0)", &x_series::CONTROL_TABLE, ModelUnits::x_series(None)),
+	Model::new(311, "MX-64(2.0)", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(3.36))),
+	Model::new(321, "MX-106(2.0)", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(3.36))),
+	Model::new(350, "XL-320", &xl320::CONTROL_TABLE, ModelUnits::XL320),
+	Model::new(1000, "XH430-W350", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(1.34))),
+	Model::new(1001, "XD430-T350", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(1.34))),
+	Model::new(1010, "XH430-W210", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(1.34))),
+	Model::new(1011, "XD430-T210", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(1.34))),
+	Model::new(1020, "XM430-W350", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(2.69))),
+	Model::new(1030, "XM430-W210", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(2.69))),
+	Model::new(1040, "XH430-V350", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(1.34))),
+	Model::new(1050, "XH430-V210", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(1.34))),
+	Model::new(1060, "XL430-W250", &x_series::CONTROL_TABLE, ModelUnits::x_series(None)),
+	Model::new(1070, "XC430-W150", &x_series::CONTROL_TABLE, ModelUnits::x_series(None)),
+	Model::new(1080, "XC430-W240", &x_series::CONTROL_TABLE, ModelUnits::x_series(None)),
+	Model::new(1090, "2XL430-W250", &x_series::CONTROL_TABLE, ModelUnits::x_series(None)),
+	Model::new(1100, "XH540-W270", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(2.69))),
+	Model::new(1101, "XD540-T270", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(2.69))),
+	Model::new(1110, "XH540-W150", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(2.69))),
+	Model::new(1111, "XD540-T150", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(2.69))),
+	Model::new(1120, "XM540-W270", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(2.69))),
+	Model::new(1130, "XM540-W150", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(2.69))),
+	Model::new(1140, "XH540-V270", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(2.69))),
+	Model::new(1150, "XH540-V150", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(2.69))),
+	Model::new(1160, "2XC430-W250", &x_series::CONTROL_TABLE, ModelUnits::x_series(None)),
+	Model::new(1170, "XW540-T260", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(2.69))),
+	Model::new(1180, "XW540-T140", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(2.69))),
+	Model::new(1190, "XL330-M077", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(1.0))),
+	Model::new(1200, "XL330-M288", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(1.0))),
+	Model::new(1210, "XC330-T181", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(1.0))),
+	Model::new(1220, "XC330-T288", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(1.0))),
+	Model::new(1230, "XC330-M181", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(1.0))),
+	Model::new(1240, "XC330-M288", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(1.0))),
+	Model::new(1270, "XW430-T333", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(1.34))),
+	Model::new(1280, "XW430-T200", &x_series::CONTROL_TABLE, ModelUnits::x_series(Some(1.34))),
+];
+
+/// Look up a model by the model number reported by a ping.
+pub fn model(number: u16) -> Option<&'static Model> {
+	MODELS.iter().find(|model| model.number == number)
+}
+
+/// Get the control table layout for a model number.
+///
+/// Falls back to the X-series layout if the model is not known.
+pub fn control_table_or_default(number: u16) -> &'static ControlTable {
+	model(number).map_or(&x_series::CONTROL_TABLE, |model| model.control_table)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+
+	fn check_sorted_and_disjoint(table: &ControlTable) {
+		for pair in table.registers.windows(2) {
+			assert!(pair[0].range().end <= pair[1].address, "{} overlaps {}", pair[0].name, pair[1].name);
+		}
+		for register in [&table.firmware_version, &table.id, &table.baud_rate, &table.torque_enable] {
+			assert!(table.register_at(register.address) == Some(register));
+		}
+	}
+
+	#[test]
+	fn control_tables_are_sorted_and_disjoint() {
+		check_sorted_and_disjoint(&x_series::CONTROL_TABLE);
+		check_sorted_and_disjoint(&xl320::CONTROL_TABLE);
+	}
+
+	#[test]
+	fn lookup_model() {
+		let_assert!(Some(model) = model(1020));
+		assert!(model.name == "XM430-W350");
+		assert!(model.control_table.id.address == 7);
+		assert!(model.units == crate::units::ModelUnits::x_series(Some(2.69)));
+		assert!(control_table_or_default(350).id.address == 3);
+		assert!(control_table_or_default(0xFFFF).id.address == 7);
+		assert!(x_series::CONTROL_TABLE.address_range() == (0..642));
+	}
+
+	#[test]
+	fn encode_and_decode_values() {
+		assert!(x_series::HOMING_OFFSET.encode_value(-1) == Some([0xFF; 4]));
+		assert!(x_series::TORQUE_ENABLE.encode_value(255) == Some([0xFF, 0, 0, 0]));
+		assert!(x_series::TORQUE_ENABLE.encode_value(256) == None);
+		assert!(x_series::TORQUE_ENABLE.encode_value(-129) == None);
+		assert!(x_series::POSITION_P_GAIN.decode_value(&[0xFF, 0xFF], true) == Some(-1));
+		assert!(x_series::POSITION_P_GAIN.decode_value(&[0xFF, 0xFF], false) == Some(0xFFFF));
+		assert!(x_series::HOMING_OFFSET.decode_value(&[0x00, 0xFC, 0xFF, 0xFF], true) == Some(-1024));
+		assert!(x_series::HOMING_OFFSET.decode_value(&[0xFF, 0xFF], true) == None);
+	}
+
+	#[test]
+	fn baud_rate_encoding() {
+		assert!(x_series::CONTROL_TABLE.encode_baud_rate(BaudRate::B1M) == Some(3));
+		assert!(x_series::CONTROL_TABLE.decode_baud_rate(7) == Some(BaudRate::B4_5M));
+		assert!(xl320::CONTROL_TABLE.encode_baud_rate(BaudRate::B2M) == None);
+	}
+}
@@ -0,0 +1,108 @@
+use super::{ControlTable, Register};
+use alloc::vec::Vec;
+
+/// A copy of (a part of) the control table of a motor.
+///
+/// Created by [`Client::dump_control_table`](crate::Client::dump_control_table),
+/// and written back to a motor with [`Client::restore_control_table_snapshot`](crate::Client::restore_control_table_snapshot).
+///
+/// If the `"serde"` feature is enabled, the snapshot can be serialized to store it in a file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControlTableSnapshot {
+	/// The ID of the motor the snapshot was taken from.
+	pub motor_id: u8,
+
+	/// The model number of the motor.
+	pub model: u16,
+
+	/// The firmware version of the motor.
+	pub firmware: u8,
+
+	/// The address of the first byte in the snapshot.
+	pub address: u16,
+
+	/// The raw contents of the control table, starting at `address`.
+	pub data: Vec<u8>,
+}
+
+impl ControlTableSnapshot {
+	/// Get the address range covered by the snapshot.
+	pub fn range(&self) -> core::ops::Range<u16> {
+		let len = u16::try_from(self.data.len()).unwrap_or(u16::MAX);
+		self.address..self.address.saturating_add(len)
+	}
+
+	/// Get the control table layout of the motor the snapshot was taken from.
+	///
+	/// Falls back to the X-series layout if the model is not known.
+	pub fn control_table(&self) -> &'static ControlTable {
+		super::control_table_or_default(self.model)
+	}
+
+	/// Get the raw bytes of a register, if the register is fully contained in the snapshot.
+	pub fn get(&self, register: &Register) -> Option<&[u8]> {
+		let start = usize::from(register.address.checked_sub(self.address)?);
+		self.data.get(start..start + usize::from(register.size))
+	}
+
+	/// Iterate over the registers that are restored by [`Client::restore_control_table_snapshot`](crate::Client::restore_control_table_snapshot).
+	///
+	/// These are all writable registers that are fully contained in the snapshot,
+	/// except for the ID and baud rate, which would make the motor unreachable.
+	/// Indirect data registers are skipped too, since they only mirror other registers.
+	/// The Bus Watchdog is skipped as well: restoring it would re-arm the watchdog,
+	/// and writing back a tripped watchdog is rejected by the motor.
+	pub fn restorable_registers(&self) -> impl Iterator<Item = (&'static Register, &[u8])> + '_ {
+		let control_table = self.control_table();
+		control_table
+			.registers
+			.iter()
+			.filter(|register| register.is_writable())
+			.filter(|register| **register != control_table.id && **register != control_table.baud_rate)
+			.filter(|register| !register.name.starts_with("Indirect Data") && register.name != "Bus Watchdog")
+			.filter_map(|register| Some((register, self.get(register)?)))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::control_table::x_series;
+	use assert2::assert;
+
+	fn snapshot(address: u16, len: usize) -> ControlTableSnapshot {
+		ControlTableSnapshot {
+			motor_id: 1,
+			model: 1020,
+			firmware: 46,
+			address,
+			data: (0..len).map(|i| i as u8).collect(),
+		}
+	}
+
+	#[test]
+	fn get_register() {
+		let snapshot = snapshot(4, 100);
+		assert!(snapshot.range() == (4..104));
+		assert!(snapshot.get(&x_series::ID) == Some(&[3][..]));
+		assert!(snapshot.get(&x_series::HOMING_OFFSET) == Some(&[16, 17, 18, 19][..]));
+		assert!(snapshot.get(&x_series::MODEL_NUMBER) == None);
+		assert!(snapshot.get(&x_series::GOAL_VELOCITY) == None);
+	}
+
+	#[test]
+	fn restorable_registers() {
+		let snapshot = snapshot(0, 256);
+		let names: Vec<_> = snapshot.restorable_registers().map(|(register, _)| register.name).collect();
+		assert!(names.contains(&"Homing Offset"));
+		assert!(names.contains(&"Torque Enable"));
+		assert!(names.contains(&"Indirect Address 1-20"));
+		assert!(!names.contains(&"ID"));
+		assert!(!names.contains(&"Baud Rate"));
+		assert!(!names.contains(&"Present Position"));
+		assert!(!names.contains(&"Indirect Data 1-20"));
+		assert!(!names.contains(&"Bus Watchdog"));
+		assert!(!names.contains(&"Indirect Address 21-28"));
+	}
+}
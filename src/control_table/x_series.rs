@@ -0,0 +1,246 @@
+//! The control table of the X-series, also used by the MX-series with Protocol 2.0 firmware.
+//!
+//! Not every X-series model has every register, but the registers that exist are at the same address.
+
+use super::{Access, Area, ControlTable, Register};
+use crate::client::BaudRate;
+
+/// The Model Number register.
+pub const MODEL_NUMBER: Register = Register::new("Model Number", 0, 2, Access::Read, Area::Eeprom);
+
+/// The Model Information register.
+pub const MODEL_INFORMATION: Register = Register::new("Model Information", 2, 4, Access::Read, Area::Eeprom);
+
+/// The Firmware Version register.
+pub const FIRMWARE_VERSION: Register = Register::new("Firmware Version", 6, 1, Access::Read, Area::Eeprom);
+
+/// The ID register.
+pub const ID: Register = Register::new("ID", 7, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Baud Rate register.
+pub const BAUD_RATE: Register = Register::new("Baud Rate", 8, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Return Delay Time register.
+pub const RETURN_DELAY_TIME: Register = Register::new("Return Delay Time", 9, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Drive Mode register.
+pub const DRIVE_MODE: Register = Register::new("Drive Mode", 10, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Operating Mode register.
+pub const OPERATING_MODE: Register = Register::new("Operating Mode", 11, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Secondary(Shadow) ID register.
+pub const SECONDARY_ID: Register = Register::new("Secondary(Shadow) ID", 12, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Protocol Type register.
+pub const PROTOCOL_TYPE: Register = Register::new("Protocol Type", 13, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Homing Offset register.
+pub const HOMING_OFFSET: Register = Register::new("Homing Offset", 20, 4, Access::ReadWrite, Area::Eeprom);
+
+/// The Moving Threshold register.
+pub const MOVING_THRESHOLD: Register = Register::new("Moving Threshold", 24, 4, Access::ReadWrite, Area::Eeprom);
+
+/// The Temperature Limit register.
+pub const TEMPERATURE_LIMIT: Register = Register::new("Temperature Limit", 31, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Max Voltage Limit register.
+pub const MAX_VOLTAGE_LIMIT: Register = Register::new("Max Voltage Limit", 32, 2, Access::ReadWrite, Area::Eeprom);
+
+/// The Min Voltage Limit register.
+pub const MIN_VOLTAGE_LIMIT: Register = Register::new("Min Voltage Limit", 34, 2, Access::ReadWrite, Area::Eeprom);
+
+/// The PWM Limit register.
+pub const PWM_LIMIT: Register = Register::new("PWM Limit", 36, 2, Access::ReadWrite, Area::Eeprom);
+
+/// The Current Limit register.
+pub const CURRENT_LIMIT: Register = Register::new("Current Limit", 38, 2, Access::ReadWrite, Area::Eeprom);
+
+/// The Velocity Limit register.
+pub const VELOCITY_LIMIT: Register = Register::new("Velocity Limit", 44, 4, Access::ReadWrite, Area::Eeprom);
+
+/// The Max Position Limit register.
+pub const MAX_POSITION_LIMIT: Register = Register::new("Max Position Limit", 48, 4, Access::ReadWrite, Area::Eeprom);
+
+/// The Min Position Limit register.
+pub const MIN_POSITION_LIMIT: Register = Register::new("Min Position Limit", 52, 4, Access::ReadWrite, Area::Eeprom);
+
+/// The Startup Configuration register.
+pub const STARTUP_CONFIGURATION: Register = Register::new("Startup Configuration", 60, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Shutdown register.
+pub const SHUTDOWN: Register = Register::new("Shutdown", 63, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Torque Enable register.
+pub const TORQUE_ENABLE: Register = Register::new("Torque Enable", 64, 1, Access::ReadWrite, Area::Ram);
+
+/// The LED register.
+pub const LED: Register = Register::new("LED", 65, 1, Access::ReadWrite, Area::Ram);
+
+/// The Status Return Level register.
+pub const STATUS_RETURN_LEVEL: Register = Register::new("Status Return Level", 68, 1, Access::ReadWrite, Area::Ram);
+
+/// The Registered Instruction register.
+pub const REGISTERED_INSTRUCTION: Register = Register::new("Registered Instruction", 69, 1, Access::Read, Area::Ram);
+
+/// The Hardware Error Status register.
+pub const HARDWARE_ERROR_STATUS: Register = Register::new("Hardware Error Status", 70, 1, Access::Read, Area::Ram);
+
+/// The Velocity I Gain register.
+pub const VELOCITY_I_GAIN: Register = Register::new("Velocity I Gain", 76, 2, Access::ReadWrite, Area::Ram);
+
+/// The Velocity P Gain register.
+pub const VELOCITY_P_GAIN: Register = Register::new("Velocity P Gain", 78, 2, Access::ReadWrite, Area::Ram);
+
+/// The Position D Gain register.
+pub const POSITION_D_GAIN: Register = Register::new("Position D Gain", 80, 2, Access::ReadWrite, Area::Ram);
+
+/// The Position I Gain register.
+pub const POSITION_I_GAIN: Register = Register::new("Position I Gain", 82, 2, Access::ReadWrite, Area::Ram);
+
+/// The Position P Gain register.
+pub const POSITION_P_GAIN: Register = Register::new("Position P Gain", 84, 2, Access::ReadWrite, Area::Ram);
+
+/// The Feedforward 2nd Gain register.
+pub const FEEDFORWARD_2ND_GAIN: Register = Register::new("Feedforward 2nd Gain", 88, 2, Access::ReadWrite, Area::Ram);
+
+/// The Feedforward 1st Gain register.
+pub const FEEDFORWARD_1ST_GAIN: Register = Register::new("Feedforward 1st Gain", 90, 2, Access::ReadWrite, Area::Ram);
+
+/// The Bus Watchdog register.
+pub const BUS_WATCHDOG: Register = Register::new("Bus Watchdog", 98, 1, Access::ReadWrite, Area::Ram);
+
+/// The Goal PWM register.
+pub const GOAL_PWM: Register = Register::new("Goal PWM", 100, 2, Access::ReadWrite, Area::Ram);
+
+/// The Goal Current register.
+pub const GOAL_CURRENT: Register = Register::new("Goal Current", 102, 2, Access::ReadWrite, Area::Ram);
+
+/// The Goal Velocity register.
+pub const GOAL_VELOCITY: Register = Register::new("Goal Velocity", 104, 4, Access::ReadWrite, Area::Ram);
+
+/// The Profile Acceleration register.
+pub const PROFILE_ACCELERATION: Register = Register::new("Profile Acceleration", 108, 4, Access::ReadWrite, Area::Ram);
+
+/// The Profile Velocity register.
+pub const PROFILE_VELOCITY: Register = Register::new("Profile Velocity", 112, 4, Access::ReadWrite, Area::Ram);
+
+/// The Goal Position register.
+pub const GOAL_POSITION: Register = Register::new("Goal Position", 116, 4, Access::ReadWrite, Area::Ram);
+
+/// The Realtime Tick register.
+pub const REALTIME_TICK: Register = Register::new("Realtime Tick", 120, 2, Access::Read, Area::Ram);
+
+/// The Moving register.
+pub const MOVING: Register = Register::new("Moving", 122, 1, Access::Read, Area::Ram);
+
+/// The Moving Status register.
+pub const MOVING_STATUS: Register = Register::new("Moving Status", 123, 1, Access::Read, Area::Ram);
+
+/// The Present PWM register.
+pub const PRESENT_PWM: Register = Register::new("Present PWM", 124, 2, Access::Read, Area::Ram);
+
+/// The Present Current register.
+pub const PRESENT_CURRENT: Register = Register::new("Present Current", 126, 2, Access::Read, Area::Ram);
+
+/// The Present Velocity register.
+pub const PRESENT_VELOCITY: Register = Register::new("Present Velocity", 128, 4, Access::Read, Area::Ram);
+
+/// The Present Position register.
+pub const PRESENT_POSITION: Register = Register::new("Present Position", 132, 4, Access::Read, Area::Ram);
+
+/// The Velocity Trajectory register.
+pub const VELOCITY_TRAJECTORY: Register = Register::new("Velocity Trajectory", 136, 4, Access::Read, Area::Ram);
+
+/// The Position Trajectory register.
+pub const POSITION_TRAJECTORY: Register = Register::new("Position Trajectory", 140, 4, Access::Read, Area::Ram);
+
+/// The Present Input Voltage register.
+pub const PRESENT_INPUT_VOLTAGE: Register = Register::new("Present Input Voltage", 144, 2, Access::Read, Area::Ram);
+
+/// The Present Temperature register.
+pub const PRESENT_TEMPERATURE: Register = Register::new("Present Temperature", 146, 1, Access::Read, Area::Ram);
+
+/// The Backup Ready register.
+pub const BACKUP_READY: Register = Register::new("Backup Ready", 147, 1, Access::Read, Area::Ram);
+
+/// The first block of indirect addresses: 20 addresses of 2 bytes each.
+pub const INDIRECT_ADDRESSES_1: Register = Register::new("Indirect Address 1-20", 168, 40, Access::ReadWrite, Area::Ram);
+
+/// The data of the first block of indirect addresses: 20 bytes.
+pub const INDIRECT_DATA_1: Register = Register::new("Indirect Data 1-20", 224, 20, Access::ReadWrite, Area::Ram);
+
+/// The second block of indirect addresses: 8 addresses of 2 bytes each.
+pub const INDIRECT_ADDRESSES_21: Register = Register::new("Indirect Address 21-28", 578, 16, Access::ReadWrite, Area::Ram);
+
+/// The data of the second block of indirect addresses: 8 bytes.
+pub const INDIRECT_DATA_21: Register = Register::new("Indirect Data 21-28", 634, 8, Access::ReadWrite, Area::Ram);
+
+/// The control table of the X-series.
+pub static CONTROL_TABLE: ControlTable = ControlTable {
+	name: "X-series",
+	registers: &[
+		MODEL_NUMBER,
+		MODEL_INFORMATION,
+		FIRMWARE_VERSION,
+		ID,
+		BAUD_RATE,
+		RETURN_DELAY_TIME,
+		DRIVE_MODE,
+		OPERATING_MODE,
+		SECONDARY_ID,
+		PROTOCOL_TYPE,
+		HOMING_OFFSET,
+		MOVING_THRESHOLD,
+		TEMPERATURE_LIMIT,
+		MAX_VOLTAGE_LIMIT,
+		MIN_VOLTAGE_LIMIT,
+		PWM_LIMIT,
+		CURRENT_LIMIT,
+		VELOCITY_LIMIT,
+		MAX_POSITION_LIMIT,
+		MIN_POSITION_LIMIT,
+		STARTUP_CONFIGURATION,
+		SHUTDOWN,
+		TORQUE_ENABLE,
+		LED,
+		STATUS_RETURN_LEVEL,
+		REGISTERED_INSTRUCTION,
+		HARDWARE_ERROR_STATUS,
+		VELOCITY_I_GAIN,
+		VELOCITY_P_GAIN,
+		POSITION_D_GAIN,
+		POSITION_I_GAIN,
+		POSITION_P_GAIN,
+		FEEDFORWARD_2ND_GAIN,
+		FEEDFORWARD_1ST_GAIN,
+		BUS_WATCHDOG,
+		GOAL_PWM,
+		GOAL_CURRENT,
+		GOAL_VELOCITY,
+		PROFILE_ACCELERATION,
+		PROFILE_VELOCITY,
+		GOAL_POSITION,
+		REALTIME_TICK,
+		MOVING,
+		MOVING_STATUS,
+		PRESENT_PWM,
+		PRESENT_CURRENT,
+		PRESENT_VELOCITY,
+		PRESENT_POSITION,
+		VELOCITY_TRAJECTORY,
+		POSITION_TRAJECTORY,
+		PRESENT_INPUT_VOLTAGE,
+		PRESENT_TEMPERATURE,
+		BACKUP_READY,
+		INDIRECT_ADDRESSES_1,
+		INDIRECT_DATA_1,
+		INDIRECT_ADDRESSES_21,
+		INDIRECT_DATA_21,
+	],
+	firmware_version: FIRMWARE_VERSION,
+	id: ID,
+	baud_rate: BAUD_RATE,
+	torque_enable: TORQUE_ENABLE,
+	baud_rates: &[BaudRate::B9600, BaudRate::B57600, BaudRate::B115200, BaudRate::B1M, BaudRate::B2M, BaudRate::B3M, BaudRate::B4M, BaudRate::B4_5M],
+};
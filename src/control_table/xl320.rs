@@ -0,0 +1,140 @@
+//! The control table of the XL-320.
+
+use super::{Access, Area, ControlTable, Register};
+use crate::client::BaudRate;
+
+/// The Model Number register.
+pub const MODEL_NUMBER: Register = Register::new("Model Number", 0, 2, Access::Read, Area::Eeprom);
+
+/// The Firmware Version register.
+pub const FIRMWARE_VERSION: Register = Register::new("Firmware Version", 2, 1, Access::Read, Area::Eeprom);
+
+/// The ID register.
+pub const ID: Register = Register::new("ID", 3, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Baud Rate register.
+pub const BAUD_RATE: Register = Register::new("Baud Rate", 4, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Return Delay Time register.
+pub const RETURN_DELAY_TIME: Register = Register::new("Return Delay Time", 5, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The CW Angle Limit register.
+pub const CW_ANGLE_LIMIT: Register = Register::new("CW Angle Limit", 6, 2, Access::ReadWrite, Area::Eeprom);
+
+/// The CCW Angle Limit register.
+pub const CCW_ANGLE_LIMIT: Register = Register::new("CCW Angle Limit", 8, 2, Access::ReadWrite, Area::Eeprom);
+
+/// The Control Mode register.
+pub const CONTROL_MODE: Register = Register::new("Control Mode", 11, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Temperature Limit register.
+pub const TEMPERATURE_LIMIT: Register = Register::new("Temperature Limit", 12, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Min Voltage Limit register.
+pub const MIN_VOLTAGE_LIMIT: Register = Register::new("Min Voltage Limit", 13, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Max Voltage Limit register.
+pub const MAX_VOLTAGE_LIMIT: Register = Register::new("Max Voltage Limit", 14, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Max Torque register.
+pub const MAX_TORQUE: Register = Register::new("Max Torque", 15, 2, Access::ReadWrite, Area::Eeprom);
+
+/// The Status Return Level register.
+pub const STATUS_RETURN_LEVEL: Register = Register::new("Status Return Level", 17, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Shutdown register.
+pub const SHUTDOWN: Register = Register::new("Shutdown", 18, 1, Access::ReadWrite, Area::Eeprom);
+
+/// The Torque Enable register.
+pub const TORQUE_ENABLE: Register = Register::new("Torque Enable", 24, 1, Access::ReadWrite, Area::Ram);
+
+/// The LED register.
+pub const LED: Register = Register::new("LED", 25, 1, Access::ReadWrite, Area::Ram);
+
+/// The D Gain register.
+pub const D_GAIN: Register = Register::new("D Gain", 27, 1, Access::ReadWrite, Area::Ram);
+
+/// The I Gain register.
+pub const I_GAIN: Register = Register::new("I Gain", 28, 1, Access::ReadWrite, Area::Ram);
+
+/// The P Gain register.
+pub const P_GAIN: Register = Register::new("P Gain", 29, 1, Access::ReadWrite, Area::Ram);
+
+/// The Goal Position register.
+pub const GOAL_POSITION: Register = Register::new("Goal Position", 30, 2, Access::ReadWrite, Area::Ram);
+
+/// The Moving Speed register.
+pub const MOVING_SPEED: Register = Register::new("Moving Speed", 32, 2, Access::ReadWrite, Area::Ram);
+
+/// The Torque Limit register.
+pub const TORQUE_LIMIT: Register = Register::new("Torque Limit", 35, 2, Access::ReadWrite, Area::Ram);
+
+/// The Present Position register.
+pub const PRESENT_POSITION: Register = Register::new("Present Position", 37, 2, Access::Read, Area::Ram);
+
+/// The Present Speed register.
+pub const PRESENT_SPEED: Register = Register::new("Present Speed", 39, 2, Access::Read, Area::Ram);
+
+/// The Present Load register.
+pub const PRESENT_LOAD: Register = Register::new("Present Load", 41, 2, Access::Read, Area::Ram);
+
+/// The Present Voltage register.
+pub const PRESENT_VOLTAGE: Register = Register::new("Present Voltage", 45, 1, Access::Read, Area::Ram);
+
+/// The Present Temperature register.
+pub const PRESENT_TEMPERATURE: Register = Register::new("Present Temperature", 46, 1, Access::Read, Area::Ram);
+
+/// The Registered register.
+pub const REGISTERED: Register = Register::new("Registered", 47, 1, Access::Read, Area::Ram);
+
+/// The Moving register.
+pub const MOVING: Register = Register::new("Moving", 49, 1, Access::Read, Area::Ram);
+
+/// The Hardware Error Status register.
+pub const HARDWARE_ERROR_STATUS: Register = Register::new("Hardware Error Status", 50, 1, Access::Read, Area::Ram);
+
+/// The Punch register.
+pub const PUNCH: Register = Register::new("Punch", 51, 2, Access::ReadWrite, Area::Ram);
+
+/// The control table of the XL-320.
+pub static CONTROL_TABLE: ControlTable = ControlTable {
+	name: "XL-320",
+	registers: &[
+		MODEL_NUMBER,
+		FIRMWARE_VERSION,
+		ID,
+		BAUD_RATE,
+		RETURN_DELAY_TIME,
+		CW_ANGLE_LIMIT,
+		CCW_ANGLE_LIMIT,
+		CONTROL_MODE,
+		TEMPERATURE_LIMIT,
+		MIN_VOLTAGE_LIMIT,
+		MAX_VOLTAGE_LIMIT,
+		MAX_TORQUE,
+		STATUS_RETURN_LEVEL,
+		SHUTDOWN,
+		TORQUE_ENABLE,
+		LED,
+		D_GAIN,
+		I_GAIN,
+		P_GAIN,
+		GOAL_POSITION,
+		MOVING_SPEED,
+		TORQUE_LIMIT,
+		PRESENT_POSITION,
+		PRESENT_SPEED,
+		PRESENT_LOAD,
+		PRESENT_VOLTAGE,
+		PRESENT_TEMPERATURE,
+		REGISTERED,
+		MOVING,
+		HARDWARE_ERROR_STATUS,
+		PUNCH,
+	],
+	firmware_version: FIRMWARE_VERSION,
+	id: ID,
+	baud_rate: BAUD_RATE,
+	torque_enable: TORQUE_ENABLE,
+	baud_rates: &[BaudRate::B9600, BaudRate::B57600, BaudRate::B115200, BaudRate::B1M],
+};
@@ -11,6 +11,168 @@ pub enum TransferError<E> {
 	ReadError(ReadError<E>),
 }
 
+/// An error that can occur while discovering motors on the bus.
+#[derive(Debug)]
+pub enum DiscoverError<E> {
+	/// Failed to change the baud rate of the serial port.
+	SetBaudRate(E),
+
+	/// Failed to write an instruction.
+	WriteError(WriteError<E>),
+
+	/// Failed to read from the serial port.
+	ReadError(ReadError<E>),
+}
+
+/// An error that can occur while changing the ID or baud rate of a motor.
+#[derive(Debug)]
+pub enum ConfigureError<E> {
+	/// Communication with the motor failed.
+	TransferError(TransferError<E>),
+
+	/// The setting can not be changed because the motor has torque enabled.
+	TorqueEnabled {
+		/// The ID of the motor.
+		motor_id: u8,
+	},
+
+	/// The new motor ID is not a valid unicast ID.
+	InvalidId {
+		/// The invalid motor ID.
+		motor_id: u8,
+	},
+
+	/// The new motor ID is already used by another motor.
+	IdInUse {
+		/// The motor ID that is already in use.
+		motor_id: u8,
+	},
+
+	/// The motor does not support the requested baud rate.
+	UnsupportedBaudRate {
+		/// The model number of the motor.
+		model: u16,
+
+		/// The requested baud rate.
+		baud_rate: crate::client::BaudRate,
+	},
+
+	/// Failed to change the baud rate of the serial port.
+	SetBaudRate(E),
+
+	/// The motor did not respond with the new setting after the change.
+	VerifyFailed(TransferError<E>),
+}
+
+/// An error that can occur while restoring a control table snapshot.
+#[derive(Debug)]
+pub enum RestoreError<E> {
+	/// Communication with the motor failed.
+	TransferError(TransferError<E>),
+
+	/// The snapshot was taken from a different model than the motor it is restored to.
+	ModelMismatch {
+		/// The ID of the motor.
+		motor_id: u8,
+
+		/// The model number in the snapshot.
+		expected: u16,
+
+		/// The model number reported by the motor.
+		actual: u16,
+	},
+}
+
+/// An error that can occur while sending a command with a [`Motor`](crate::client::Motor) handle.
+#[derive(Debug)]
+pub enum MotorCommandError<E> {
+	/// Communication with the motor failed.
+	TransferError(TransferError<E>),
+
+	/// The motor does not use the X-series control table layout.
+	UnsupportedModel {
+		/// The ID of the motor.
+		motor_id: u8,
+
+		/// The model number of the motor.
+		model: u16,
+	},
+
+	/// The command is not supported by the model of the motor.
+	UnsupportedCommand {
+		/// The ID of the motor.
+		motor_id: u8,
+
+		/// The model number of the motor.
+		model: u16,
+
+		/// The name of the command.
+		command: &'static str,
+	},
+
+	/// The command does not match the operating mode of the motor.
+	WrongOperatingMode {
+		/// The ID of the motor.
+		motor_id: u8,
+
+		/// The current operating mode of the motor.
+		operating_mode: crate::client::OperatingMode,
+
+		/// The name of the command.
+		command: &'static str,
+	},
+
+	/// The motor reported an unknown value in the operating mode register.
+	UnknownOperatingMode {
+		/// The ID of the motor.
+		motor_id: u8,
+
+		/// The raw value of the operating mode register.
+		value: u8,
+	},
+}
+
+/// An error that can occur while arming a [`Watchdog`](crate::client::Watchdog).
+#[derive(Debug)]
+pub enum WatchdogError<E> {
+	/// Communication with a motor failed.
+	TransferError(TransferError<E>),
+
+	/// The timeout is zero or longer than the Bus Watchdog register can hold.
+	InvalidTimeout {
+		/// The requested timeout.
+		timeout: core::time::Duration,
+	},
+}
+
+/// An error that can occur while building an [`IndirectMapping`](crate::control_table::IndirectMapping).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum IndirectMappingError {
+	/// The registers do not fit in the indirect data block.
+	TooLarge {
+		/// The total size of the registers in bytes.
+		required_size: usize,
+
+		/// The size of the indirect data block in bytes.
+		block_size: u16,
+	},
+
+	/// A register was added more than once.
+	DuplicateRegister {
+		/// The name of the duplicate register.
+		name: &'static str,
+	},
+
+	/// A register partially overlaps with a different register that was added before.
+	OverlappingRegister {
+		/// The name of the overlapping register.
+		name: &'static str,
+
+		/// The name of the register that was added before.
+		other: &'static str,
+	},
+}
+
 /// An error that can occur during a write transfer.
 #[derive(Debug)]
 pub enum WriteError<E> {
@@ -101,17 +263,210 @@ impl MotorError {
 	pub fn alert(&self) -> bool {
 		self.raw & 0x80 != 0
 	}
+
+	/// The status error reported by the motor.
+	///
+	/// Returns `None` if the error number is not defined by the protocol.
+	pub fn status_error(&self) -> Option<StatusError> {
+		StatusError::from_number(self.error_number())
+	}
 }
 
 impl Debug for MotorError {
 	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
 		f.debug_struct("MotorError")
 			.field("error_number", &self.error_number())
+			.field("status_error", &self.status_error())
 			.field("alert", &self.alert())
 			.finish()
 	}
 }
 
+/// A status error defined by the DYNAMIXEL Protocol 2.0.
+///
+/// This is the error number from the error field of a status packet, without the alert bit.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum StatusError {
+	/// The motor failed to process the instruction.
+	ResultFail,
+
+	/// The instruction is undefined, or an action instruction was received without a registered write.
+	InstructionError,
+
+	/// The checksum of the instruction packet does not match.
+	CrcError,
+
+	/// The data to be written is outside the range of the register.
+	DataRangeError,
+
+	/// The data is shorter than the size of the register.
+	DataLengthError,
+
+	/// The data to be written is outside the configured limits of the register.
+	DataLimitError,
+
+	/// The register can not be accessed, for example when writing to a read-only register
+	/// or to an EEPROM register while torque is enabled.
+	AccessError,
+}
+
+impl StatusError {
+	/// Get the status error for an error number.
+	///
+	/// Returns `None` if the error number is not defined by the protocol.
+	pub fn from_number(number: u8) -> Option<Self> {
+		match number {
+			0x01 => Some(Self::ResultFail),
+			0x02 => Some(Self::InstructionError),
+			0x03 => Some(Self::CrcError),
+			0x04 => Some(Self::DataRangeError),
+			0x05 => Some(Self::DataLengthError),
+			0x06 => Some(Self::DataLimitError),
+			0x07 => Some(Self::AccessError),
+			_ => None,
+		}
+	}
+
+	/// Get the error number of the status error.
+	pub fn number(self) -> u8 {
+		match self {
+			Self::ResultFail => 0x01,
+			Self::InstructionError => 0x02,
+			Self::CrcError => 0x03,
+			Self::DataRangeError => 0x04,
+			Self::DataLengthError => 0x05,
+			Self::DataLimitError => 0x06,
+			Self::AccessError => 0x07,
+		}
+	}
+
+	/// Get the name of the status error, as used in the online manual.
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::ResultFail => "Result Fail",
+			Self::InstructionError => "Instruction Error",
+			Self::CrcError => "CRC Error",
+			Self::DataRangeError => "Data Range Error",
+			Self::DataLengthError => "Data Length Error",
+			Self::DataLimitError => "Data Limit Error",
+			Self::AccessError => "Access Error",
+		}
+	}
+}
+
+/// The value of the "Hardware Error Status" register.
+///
+/// Each bit of the register signals a different hardware error.
+/// If any bit is set, the alert bit is set in all responses from the motor.
+/// Most motors disable torque when a hardware error occurs, and the error can only be cleared by a reboot.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct HardwareErrorStatus {
+	bits: u8,
+}
+
+impl HardwareErrorStatus {
+	/// The input voltage is outside the operating voltage range.
+	pub const INPUT_VOLTAGE: Self = Self::from_bits_retain(0x01);
+
+	/// The internal temperature is above the temperature limit.
+	pub const OVERHEATING: Self = Self::from_bits_retain(0x04);
+
+	/// The motor encoder is malfunctioning.
+	pub const MOTOR_ENCODER: Self = Self::from_bits_retain(0x08);
+
+	/// An electrical shock occurred on the circuit, or the input power is insufficient.
+	pub const ELECTRICAL_SHOCK: Self = Self::from_bits_retain(0x10);
+
+	/// A persistent load exceeded the maximum output of the motor.
+	pub const OVERLOAD: Self = Self::from_bits_retain(0x20);
+
+	/// All known flags with their names.
+	const FLAGS: [(Self, &'static str); 5] = [
+		(Self::INPUT_VOLTAGE, "Input Voltage Error"),
+		(Self::OVERHEATING, "Overheating Error"),
+		(Self::MOTOR_ENCODER, "Motor Encoder Error"),
+		(Self::ELECTRICAL_SHOCK, "Electrical Shock Error"),
+		(Self::OVERLOAD, "Overload Error"),
+	];
+
+	/// A value without any flags set.
+	pub const fn empty() -> Self {
+		Self { bits: 0 }
+	}
+
+	/// Create a value from the raw register value, keeping unknown bits.
+	pub const fn from_bits_retain(bits: u8) -> Self {
+		Self { bits }
+	}
+
+	/// Get the raw register value.
+	pub const fn bits(self) -> u8 {
+		self.bits
+	}
+
+	/// Check if no flags are set.
+	pub const fn is_empty(self) -> bool {
+		self.bits == 0
+	}
+
+	/// Check if all flags in `other` are set.
+	pub const fn contains(self, other: Self) -> bool {
+		self.bits & other.bits == other.bits
+	}
+
+	/// Check if any flag in `other` is set.
+	pub const fn intersects(self, other: Self) -> bool {
+		self.bits & other.bits != 0
+	}
+
+	/// Get the bits that do not correspond to a known flag.
+	pub fn unknown_bits(self) -> u8 {
+		Self::FLAGS.iter().fold(self.bits, |bits, (flag, _)| bits & !flag.bits)
+	}
+
+	/// Iterate over the names of the known flags that are set.
+	pub fn iter_names(self) -> impl Iterator<Item = (&'static str, Self)> {
+		Self::FLAGS
+			.into_iter()
+			.filter(move |(flag, _)| self.contains(*flag))
+			.map(|(flag, name)| (name, flag))
+	}
+}
+
+impl core::ops::BitOr for HardwareErrorStatus {
+	type Output = Self;
+
+	fn bitor(self, other: Self) -> Self {
+		Self::from_bits_retain(self.bits | other.bits)
+	}
+}
+
+impl core::ops::BitOrAssign for HardwareErrorStatus {
+	fn bitor_assign(&mut self, other: Self) {
+		self.bits |= other.bits;
+	}
+}
+
+impl core::ops::BitAnd for HardwareErrorStatus {
+	type Output = Self;
+
+	fn bitand(self, other: Self) -> Self {
+		Self::from_bits_retain(self.bits & other.bits)
+	}
+}
+
+impl From<u8> for HardwareErrorStatus {
+	fn from(bits: u8) -> Self {
+		Self::from_bits_retain(bits)
+	}
+}
+
+impl Debug for HardwareErrorStatus {
+	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+		write!(f, "HardwareErrorStatus(0x{:02X})", self.bits)
+	}
+}
+
 /// The received message has an invalid header prefix.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct InvalidHeaderPrefix {
@@ -289,10 +644,17 @@ impl InvalidParameterCount {
 }
 
 impl<E: Debug + Display> core::error::Error for TransferError<E> {}
+impl<E: Debug + Display> core::error::Error for ConfigureError<E> {}
+impl<E: Debug + Display> core::error::Error for DiscoverError<E> {}
+impl<E: Debug + Display> core::error::Error for RestoreError<E> {}
+impl<E: Debug + Display> core::error::Error for MotorCommandError<E> {}
+impl<E: Debug + Display> core::error::Error for WatchdogError<E> {}
+impl core::error::Error for IndirectMappingError {}
 impl<E: Debug + Display> core::error::Error for WriteError<E> {}
 impl<E: Debug + Display> core::error::Error for ReadError<E> {}
 impl core::error::Error for InvalidMessage {}
 impl core::error::Error for MotorError {}
+impl core::error::Error for StatusError {}
 impl core::error::Error for InvalidHeaderPrefix {}
 impl core::error::Error for InvalidChecksum {}
 impl core::error::Error for InvalidPacketId {}
@@ -353,6 +715,51 @@ impl<E> From<InvalidParameterCount> for TransferError<E> {
 	}
 }
 
+impl<E> From<TransferError<E>> for ConfigureError<E> {
+	fn from(other: TransferError<E>) -> Self {
+		Self::TransferError(other)
+	}
+}
+
+impl<E> From<TransferError<E>> for RestoreError<E> {
+	fn from(other: TransferError<E>) -> Self {
+		Self::TransferError(other)
+	}
+}
+
+impl<E> From<TransferError<E>> for MotorCommandError<E> {
+	fn from(other: TransferError<E>) -> Self {
+		Self::TransferError(other)
+	}
+}
+
+impl<E> From<TransferError<E>> for WatchdogError<E> {
+	fn from(other: TransferError<E>) -> Self {
+		Self::TransferError(other)
+	}
+}
+
+impl<E> From<WriteError<E>> for DiscoverError<E> {
+	fn from(other: WriteError<E>) -> Self {
+		Self::WriteError(other)
+	}
+}
+
+impl<E> From<ReadError<E>> for DiscoverError<E> {
+	fn from(other: ReadError<E>) -> Self {
+		Self::ReadError(other)
+	}
+}
+
+impl<E> From<TransferError<E>> for DiscoverError<E> {
+	fn from(other: TransferError<E>) -> Self {
+		match other {
+			TransferError::WriteError(e) => Self::WriteError(e),
+			TransferError::ReadError(e) => Self::ReadError(e),
+		}
+	}
+}
+
 impl<E> From<BufferTooSmallError> for WriteError<E> {
 	fn from(other: BufferTooSmallError) -> Self {
 		Self::BufferTooSmall(other)
@@ -449,6 +856,127 @@ where
 	}
 }
 
+impl<E> Display for ConfigureError<E>
+where
+	E: Display,
+{
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		match self {
+			Self::TransferError(e) => write!(f, "{}", e),
+			Self::TorqueEnabled { motor_id } => write!(f, "motor {} has torque enabled", motor_id),
+			Self::InvalidId { motor_id } => write!(f, "invalid motor ID: {}", motor_id),
+			Self::IdInUse { motor_id } => write!(f, "motor ID {} is already in use", motor_id),
+			Self::UnsupportedBaudRate { model, baud_rate } => write!(
+				f,
+				"motor model {} does not support a baud rate of {}",
+				model,
+				baud_rate.bits_per_second()
+			),
+			Self::SetBaudRate(e) => write!(f, "failed to set baud rate: {}", e),
+			Self::VerifyFailed(e) => write!(f, "failed to verify new setting: {}", e),
+		}
+	}
+}
+
+impl<E> Display for DiscoverError<E>
+where
+	E: Display,
+{
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		match self {
+			Self::SetBaudRate(e) => write!(f, "failed to set baud rate: {}", e),
+			Self::WriteError(e) => write!(f, "{}", e),
+			Self::ReadError(e) => write!(f, "{}", e),
+		}
+	}
+}
+
+impl<E> Display for RestoreError<E>
+where
+	E: Display,
+{
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		match self {
+			Self::TransferError(e) => write!(f, "{}", e),
+			Self::ModelMismatch {
+				motor_id,
+				expected,
+				actual,
+			} => write!(
+				f,
+				"snapshot is for model {}, but motor {} is model {}",
+				expected, motor_id, actual
+			),
+		}
+	}
+}
+
+impl<E> Display for MotorCommandError<E>
+where
+	E: Display,
+{
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		match self {
+			Self::TransferError(e) => write!(f, "{}", e),
+			Self::UnsupportedModel { motor_id, model } => write!(
+				f,
+				"motor {} is model {}, which does not use the X-series control table",
+				motor_id, model
+			),
+			Self::UnsupportedCommand { motor_id, model, command } => {
+				write!(f, "motor {} is model {}, which can not {}", motor_id, model, command)
+			},
+			Self::WrongOperatingMode {
+				motor_id,
+				operating_mode,
+				command,
+			} => write!(
+				f,
+				"can not {} while motor {} is in {:?} operating mode",
+				command, motor_id, operating_mode
+			),
+			Self::UnknownOperatingMode { motor_id, value } => {
+				write!(f, "motor {} reported an unknown operating mode: {}", motor_id, value)
+			},
+		}
+	}
+}
+
+impl<E> Display for WatchdogError<E>
+where
+	E: Display,
+{
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		match self {
+			Self::TransferError(e) => write!(f, "{}", e),
+			Self::InvalidTimeout { timeout } => write!(
+				f,
+				"watchdog timeout must be between 20 milliseconds and 2.54 seconds, got {:?}",
+				timeout
+			),
+		}
+	}
+}
+
+impl Display for IndirectMappingError {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		match self {
+			Self::TooLarge {
+				required_size,
+				block_size,
+			} => write!(
+				f,
+				"indirect mapping needs {} bytes, but the indirect data block holds only {} bytes",
+				required_size, block_size
+			),
+			Self::DuplicateRegister { name } => write!(f, "register {:?} is already mapped", name),
+			Self::OverlappingRegister { name, other } => {
+				write!(f, "register {:?} overlaps with register {:?}, which is already mapped", name, other)
+			},
+		}
+	}
+}
+
 impl<E> Display for WriteError<E>
 where
 	E: Display,
@@ -508,7 +1036,40 @@ impl Display for InvalidMessage {
 
 impl Display for MotorError {
 	fn fmt(&self, f: &mut Formatter) -> FmtResult {
-		write!(f, "motor reported error status: 0x{:02X}", self.raw,)
+		match self.status_error() {
+			Some(error) => write!(f, "motor reported error status: {} (0x{:02X})", error, self.raw),
+			None => write!(f, "motor reported error status: 0x{:02X}", self.raw),
+		}
+	}
+}
+
+impl Display for StatusError {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		f.write_str(self.name())
+	}
+}
+
+impl Display for HardwareErrorStatus {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		if self.is_empty() {
+			return f.write_str("no hardware error");
+		}
+		let mut first = true;
+		for (name, _) in self.iter_names() {
+			if !first {
+				f.write_str(", ")?;
+			}
+			f.write_str(name)?;
+			first = false;
+		}
+		let unknown = self.unknown_bits();
+		if unknown != 0 {
+			if !first {
+				f.write_str(", ")?;
+			}
+			write!(f, "unknown hardware error bits 0x{:02X}", unknown)?;
+		}
+		Ok(())
 	}
 }
 
@@ -563,3 +1124,40 @@ impl Display for InvalidParameterCount {
 		write!(f, "invalid parameter count, expected {}, got {}", self.expected, self.actual)
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn test_status_error() {
+		for number in 1..=7 {
+			let error = StatusError::from_number(number).unwrap();
+			assert!(error.number() == number);
+		}
+		assert!(StatusError::from_number(0) == None);
+		assert!(StatusError::from_number(8) == None);
+
+		let error = MotorError { raw: 0x84 };
+		assert!(error.alert());
+		assert!(error.status_error() == Some(StatusError::DataRangeError));
+		assert!(error.to_string() == "motor reported error status: Data Range Error (0x84)");
+		assert!(MotorError { raw: 0x7F }.to_string() == "motor reported error status: 0x7F");
+	}
+
+	#[test]
+	fn test_hardware_error_status() {
+		let status = HardwareErrorStatus::from_bits_retain(0x24);
+		assert!(status == HardwareErrorStatus::OVERHEATING | HardwareErrorStatus::OVERLOAD);
+		assert!(status.contains(HardwareErrorStatus::OVERLOAD));
+		assert!(!status.contains(HardwareErrorStatus::INPUT_VOLTAGE));
+		assert!(status.unknown_bits() == 0);
+		assert!(status.to_string() == "Overheating Error, Overload Error");
+
+		let status = HardwareErrorStatus::from_bits_retain(0x41);
+		assert!(status.unknown_bits() == 0x40);
+		assert!(status.to_string() == "Input Voltage Error, unknown hardware error bits 0x40");
+		assert!(HardwareErrorStatus::empty().to_string() == "no hardware error");
+	}
+}
@@ -22,6 +22,10 @@
 //!
 //! You can enable the `log` feature to have the library use `log::trace!()` to log all sent instructions and received replies.
 //!
+//! The `uom` feature implements conversions between the quantities of the [`units`] module and the types of the `uom` crate.
+//!
+//! The `config` feature enables the `config` module, to load the expected configuration of all motors on a bus from a TOML or YAML file and apply it.
+//!
 //! # Example
 //!
 //! For example, to ping a motor using the synchronous client:
@@ -54,6 +58,18 @@ pub mod bus;
 pub mod client;
 pub use client::{AsyncClient, Client};
 
+pub mod control_table;
+
+pub mod timing;
+
+#[cfg(feature = "std")]
+pub mod trajectory;
+
+pub mod units;
+
+#[cfg(feature = "config")]
+pub mod config;
+
 mod error;
 pub use error::*;
 
@@ -0,0 +1,220 @@
+//! Prediction of the bus time needed for instructions and their replies.
+//!
+//! Use [`predict_transaction()`] to check if a cycle of instructions fits in a control period
+//! before choosing a baud rate or the layout of a motor group.
+//!
+//! The predictions are based on the size of the instruction and status packets and the baud rate of the bus.
+//! Each byte takes 10 bits on the wire: 1 start bit, 8 data bits and 1 stop bit.
+//! Delays in the serial port driver or USB adapter are not included.
+
+use crate::bus::HEADER_SIZE;
+use core::time::Duration;
+
+/// The default additional time added to the automatically calculated read timeout of a status response.
+///
+/// The official SDK adds a flat 34 milliseconds, so we mimic that.
+pub const DEFAULT_RESPONSE_TIMEOUT_PADDING: Duration = Duration::from_millis(34);
+
+/// The default return delay time of most Protocol 2.0 motors: 250 units of 2 microseconds.
+pub const DEFAULT_RETURN_DELAY: Duration = Duration::from_micros(500);
+
+/// The parameters of a bus that determine the duration of a transaction.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BusTiming {
+	/// The baud rate of the bus.
+	pub baud_rate: u32,
+
+	/// The time a motor waits before sending a reply, as configured in the "Return Delay Time" register.
+	pub return_delay: Duration,
+
+	/// The additional time added to the read timeout of each reply, see [`Client::response_timeout_padding()`](crate::Client::response_timeout_padding).
+	pub response_timeout_padding: Duration,
+}
+
+impl BusTiming {
+	/// Create the timing parameters for a baud rate, with the default return delay and response timeout padding.
+	pub const fn new(baud_rate: u32) -> Self {
+		Self {
+			baud_rate,
+			return_delay: DEFAULT_RETURN_DELAY,
+			response_timeout_padding: DEFAULT_RESPONSE_TIMEOUT_PADDING,
+		}
+	}
+}
+
+/// An instruction to predict the duration of.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Instruction {
+	/// A ping of each motor, one after the other.
+	Ping,
+
+	/// A read from each motor, one after the other.
+	Read,
+
+	/// A write to each motor, one after the other, each waiting for a reply.
+	Write,
+
+	/// A sync read from all motors with a single instruction.
+	SyncRead,
+
+	/// A sync write to all motors with a single instruction.
+	SyncWrite,
+
+	/// A bulk read from all motors with a single instruction.
+	BulkRead,
+
+	/// A bulk write to all motors with a single instruction.
+	BulkWrite,
+
+	/// A fast sync read from all motors, which reply with a single combined status packet.
+	///
+	/// This instruction is not supported by the client yet.
+	FastSyncRead,
+}
+
+/// The predicted duration of a transaction.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TransactionTime {
+	/// The time to transfer all messages without byte stuffing or return delays.
+	pub min: Duration,
+
+	/// The time to transfer all messages without byte stuffing, including the return delays of the motors.
+	pub expected: Duration,
+
+	/// The time to transfer all messages with worst case byte stuffing,
+	/// including the return delays and the response timeout padding of each reply.
+	///
+	/// This is how long the client may wait before it gives up on a missing reply.
+	pub max: Duration,
+}
+
+/// Predict the duration of an instruction and all its replies.
+///
+/// `bytes` is the number of data bytes read from or written to each motor.
+/// It is ignored for [`Instruction::Ping`].
+///
+/// Instructions that address a single motor are repeated for each motor.
+pub fn predict_transaction(timing: &BusTiming, instruction: Instruction, motor_count: usize, bytes: u16) -> TransactionTime {
+	let motors = motor_count as u32;
+	let bytes = u32::from(bytes);
+
+	// The parameter count of all instructions, and the parameter count and number of all replies.
+	let (instructions, instruction_parameters, replies, reply_parameters) = match instruction {
+		Instruction::Ping => (motors, 0, motors, 3),
+		Instruction::Read => (motors, 4, motors, bytes),
+		Instruction::Write => (motors, 2 + bytes, motors, 0),
+		Instruction::SyncRead => (1, 4 + motors, motors, bytes),
+		Instruction::SyncWrite => (1, 4 + motors * (1 + bytes), 0, 0),
+		Instruction::BulkRead => (1, 5 * motors, motors, bytes),
+		Instruction::BulkWrite => (1, motors * (5 + bytes), 0, 0),
+		// The combined reply holds the error, ID, data and CRC of each motor.
+		Instruction::FastSyncRead => (1, 4 + motors, 1, (motors * (4 + bytes)).saturating_sub(3)),
+	};
+
+	let instruction = Packet::instruction(instruction_parameters);
+	let reply = Packet::status(reply_parameters);
+	let instructions_min = instruction.transfer_time(timing.baud_rate) * instructions;
+	let instructions_max = instruction.stuffed_transfer_time(timing.baud_rate) * instructions;
+	let replies_min = reply.transfer_time(timing.baud_rate) * replies;
+	let replies_max = reply.stuffed_transfer_time(timing.baud_rate) * replies;
+	let return_delays = if replies > 0 { timing.return_delay * motors } else { Duration::ZERO };
+	let padding = timing.response_timeout_padding * replies;
+
+	let min = instructions_min + replies_min;
+	TransactionTime {
+		min,
+		expected: min + return_delays,
+		max: instructions_max + replies_max + return_delays + padding,
+	}
+}
+
+/// The size of a packet without byte stuffing.
+struct Packet {
+	/// The total size of the packet.
+	size: u32,
+
+	/// The size of the part of the packet that can be byte stuffed.
+	body: u32,
+}
+
+impl Packet {
+	/// An instruction packet with the given number of parameters.
+	fn instruction(parameters: u32) -> Self {
+		// The body is the instruction ID and the parameters, followed by a 2 byte CRC.
+		Self {
+			size: HEADER_SIZE as u32 + 1 + parameters + 2,
+			body: 1 + parameters,
+		}
+	}
+
+	/// A status packet with the given number of parameters.
+	fn status(parameters: u32) -> Self {
+		// The body is the instruction ID, the error field and the parameters, followed by a 2 byte CRC.
+		Self {
+			size: HEADER_SIZE as u32 + 2 + parameters + 2,
+			body: 2 + parameters,
+		}
+	}
+
+	fn transfer_time(&self, baud_rate: u32) -> Duration {
+		crate::bus::message_transfer_time(self.size, baud_rate)
+	}
+
+	/// The transfer time if every 3 bytes of the body form a header prefix, and need an extra stuffing byte.
+	fn stuffed_transfer_time(&self, baud_rate: u32) -> Duration {
+		crate::bus::message_transfer_time(self.size + self.body / 3, baud_rate)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	const TIMING: BusTiming = BusTiming {
+		baud_rate: 1_000_000,
+		return_delay: Duration::from_micros(500),
+		response_timeout_padding: Duration::from_millis(10),
+	};
+
+	#[test]
+	fn predict_read() {
+		// 14 bytes for the instruction and 15 bytes for the reply, at 10 µs per byte.
+		let time = predict_transaction(&TIMING, Instruction::Read, 1, 4);
+		assert!(time.min == Duration::from_micros(290));
+		assert!(time.expected == Duration::from_micros(790));
+		// The instruction body of 5 bytes and the reply body of 6 bytes can be stuffed with 1 and 2 bytes.
+		assert!(time.max == Duration::from_micros(10_820));
+
+		let time = predict_transaction(&TIMING, Instruction::Ping, 3, 0);
+		assert!(time.min == Duration::from_micros(3 * 100 + 3 * 140));
+		assert!(time.expected == time.min + Duration::from_micros(1500));
+	}
+
+	#[test]
+	fn predict_sync_instructions() {
+		// 16 bytes for the instruction and 2 replies of 15 bytes.
+		let time = predict_transaction(&TIMING, Instruction::SyncRead, 2, 4);
+		assert!(time.min == Duration::from_micros(460));
+		assert!(time.expected == Duration::from_micros(1460));
+
+		// A bulk read has larger instructions than a sync read, but the same replies.
+		let bulk = predict_transaction(&TIMING, Instruction::BulkRead, 2, 4);
+		assert!(bulk.min == Duration::from_micros(500));
+
+		// 24 bytes for the instruction, and no replies.
+		let time = predict_transaction(&TIMING, Instruction::SyncWrite, 2, 4);
+		assert!(time.min == Duration::from_micros(240));
+		assert!(time.expected == time.min);
+		assert!(time.max == Duration::from_micros(290));
+	}
+
+	#[test]
+	fn predict_fast_sync_read() {
+		// 16 bytes for the instruction and a single reply of 11 + 2 * 8 - 3 bytes.
+		let time = predict_transaction(&TIMING, Instruction::FastSyncRead, 2, 4);
+		assert!(time.min == Duration::from_micros(400));
+		assert!(time.min < predict_transaction(&TIMING, Instruction::SyncRead, 2, 4).min);
+		assert!(time.max - time.expected >= TIMING.response_timeout_padding);
+	}
+}
@@ -0,0 +1,379 @@
+//! Smooth point-to-point trajectories for a group of motors.
+//!
+//! A [`Trajectory`] moves a list of motors from a start position to an end position,
+//! with either a trapezoidal velocity profile or a minimum-jerk profile.
+//! All motors start and finish at the same time, so the motion is a straight line in joint space.
+//!
+//! Use [`Client::stream_trajectory()`](crate::Client::stream_trajectory) to stream the set-points of a trajectory
+//! to the motors with sync write instructions at a fixed rate.
+
+use crate::units::{Angle, AngularAcceleration, AngularVelocity};
+use core::time::Duration;
+
+/// A point-to-point trajectory for a list of motors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trajectory {
+	/// The start position of each motor.
+	start: Vec<Angle>,
+
+	/// The end position of each motor.
+	end: Vec<Angle>,
+
+	/// The normalized shape of the trajectory, shared by all motors.
+	shape: Shape,
+}
+
+/// A motion profile that moves from 0 to 1.
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+	/// A trapezoidal velocity profile: constant acceleration, constant velocity and constant deceleration.
+	Trapezoid {
+		/// The acceleration in 1/s².
+		acceleration: f64,
+
+		/// The time spent accelerating, in seconds.
+		acceleration_time: f64,
+
+		/// The time spent at the peak velocity, in seconds.
+		cruise_time: f64,
+	},
+
+	/// A minimum-jerk profile: a fifth order polynomial with zero velocity and acceleration at both ends.
+	MinimumJerk {
+		/// The duration in seconds.
+		duration: f64,
+	},
+}
+
+impl Trajectory {
+	/// Create a trajectory with a trapezoidal velocity profile.
+	///
+	/// The motor that moves the furthest is limited by `max_velocity` and `max_acceleration`.
+	/// The other motors move slower, so that all motors arrive at the same time.
+	///
+	/// # Panics
+	/// This function panics if `start` and `end` have a different length,
+	/// or if `max_velocity` or `max_acceleration` is not positive.
+	pub fn trapezoid(start: &[Angle], end: &[Angle], max_velocity: AngularVelocity, max_acceleration: AngularAcceleration) -> Self {
+		let max_velocity = max_velocity.radians_per_second();
+		let max_acceleration = max_acceleration.radians_per_second_squared();
+		assert!(max_velocity > 0.0, "Trajectory::trapezoid: the maximum velocity must be positive");
+		assert!(max_acceleration > 0.0, "Trajectory::trapezoid: the maximum acceleration must be positive");
+
+		let distance = max_distance(start, end);
+		let shape = if distance == 0.0 {
+			Shape::MinimumJerk { duration: 0.0 }
+		} else if distance >= max_velocity * max_velocity / max_acceleration {
+			// The motor reaches the maximum velocity.
+			Shape::Trapezoid {
+				acceleration: max_acceleration / distance,
+				acceleration_time: max_velocity / max_acceleration,
+				cruise_time: distance / max_velocity - max_velocity / max_acceleration,
+			}
+		} else {
+			// The motor has to decelerate before it reaches the maximum velocity.
+			Shape::Trapezoid {
+				acceleration: max_acceleration / distance,
+				acceleration_time: (distance / max_acceleration).sqrt(),
+				cruise_time: 0.0,
+			}
+		};
+		Self::new(start, end, shape)
+	}
+
+	/// Create a trajectory with a minimum-jerk profile and a fixed duration.
+	///
+	/// # Panics
+	/// This function panics if `start` and `end` have a different length.
+	pub fn minimum_jerk(start: &[Angle], end: &[Angle], duration: Duration) -> Self {
+		Self::new(
+			start,
+			end,
+			Shape::MinimumJerk {
+				duration: duration.as_secs_f64(),
+			},
+		)
+	}
+
+	fn new(start: &[Angle], end: &[Angle], shape: Shape) -> Self {
+		assert_eq!(
+			start.len(),
+			end.len(),
+			"Trajectory: got {} start positions and {} end positions",
+			start.len(),
+			end.len()
+		);
+		Self {
+			start: start.to_vec(),
+			end: end.to_vec(),
+			shape,
+		}
+	}
+
+	/// Get the number of motors in the trajectory.
+	pub fn motor_count(&self) -> usize {
+		self.start.len()
+	}
+
+	/// Get the start position of each motor.
+	pub fn start(&self) -> &[Angle] {
+		&self.start
+	}
+
+	/// Get the end position of each motor.
+	pub fn end(&self) -> &[Angle] {
+		&self.end
+	}
+
+	/// Get the duration of the trajectory.
+	pub fn duration(&self) -> Duration {
+		Duration::from_secs_f64(self.shape.duration())
+	}
+
+	/// Compute the position of each motor at a time since the start of the trajectory.
+	///
+	/// Before the start and after the end of the trajectory, the start or end position is returned.
+	///
+	/// # Panics
+	/// This function panics if the length of `positions` does not match the number of motors.
+	pub fn sample(&self, time: Duration, positions: &mut [Angle]) {
+		assert_eq!(
+			positions.len(),
+			self.motor_count(),
+			"Trajectory::sample: got room for {} positions for {} motors",
+			positions.len(),
+			self.motor_count()
+		);
+		let progress = self.shape.progress(time.as_secs_f64());
+		for ((position, start), end) in positions.iter_mut().zip(&self.start).zip(&self.end) {
+			*position = Angle::from_radians(start.radians() + (end.radians() - start.radians()) * progress);
+		}
+	}
+
+	/// Get the peak velocity of a motor during the trajectory.
+	///
+	/// # Panics
+	/// This function panics if the index is out of bounds.
+	pub fn peak_velocity(&self, index: usize) -> AngularVelocity {
+		AngularVelocity::from_radians_per_second(self.distance(index) * self.shape.peak_velocity())
+	}
+
+	/// Get the peak acceleration of a motor during the trajectory.
+	///
+	/// # Panics
+	/// This function panics if the index is out of bounds.
+	pub fn peak_acceleration(&self, index: usize) -> AngularAcceleration {
+		AngularAcceleration::from_radians_per_second_squared(self.distance(index) * self.shape.peak_acceleration())
+	}
+
+	/// Get the absolute distance a motor travels, in radians.
+	fn distance(&self, index: usize) -> f64 {
+		(self.end[index].radians() - self.start[index].radians()).abs()
+	}
+}
+
+impl Shape {
+	/// The duration in seconds.
+	fn duration(&self) -> f64 {
+		match self {
+			Self::Trapezoid {
+				acceleration_time,
+				cruise_time,
+				..
+			} => 2.0 * acceleration_time + cruise_time,
+			Self::MinimumJerk { duration } => *duration,
+		}
+	}
+
+	/// The progress from 0 to 1 at a time in seconds.
+	///
+	/// A trajectory with zero duration is at its end immediately.
+	fn progress(&self, time: f64) -> f64 {
+		let duration = self.duration();
+		if time >= duration {
+			return 1.0;
+		} else if time <= 0.0 {
+			return 0.0;
+		}
+		match *self {
+			Self::Trapezoid {
+				acceleration,
+				acceleration_time,
+				cruise_time,
+			} => {
+				let peak_velocity = acceleration * acceleration_time;
+				if time < acceleration_time {
+					0.5 * acceleration * time * time
+				} else if time < acceleration_time + cruise_time {
+					0.5 * peak_velocity * acceleration_time + peak_velocity * (time - acceleration_time)
+				} else {
+					let remaining = duration - time;
+					1.0 - 0.5 * acceleration * remaining * remaining
+				}
+			},
+			Self::MinimumJerk { duration } => {
+				let t = time / duration;
+				t * t * t * (10.0 - 15.0 * t + 6.0 * t * t)
+			},
+		}
+	}
+
+	/// The peak velocity in 1/s.
+	fn peak_velocity(&self) -> f64 {
+		match *self {
+			Self::Trapezoid {
+				acceleration,
+				acceleration_time,
+				..
+			} => acceleration * acceleration_time,
+			Self::MinimumJerk { duration: 0.0 } => 0.0,
+			Self::MinimumJerk { duration } => 1.875 / duration,
+		}
+	}
+
+	/// The peak acceleration in 1/s².
+	fn peak_acceleration(&self) -> f64 {
+		match *self {
+			Self::Trapezoid { acceleration, .. } => acceleration,
+			Self::MinimumJerk { duration: 0.0 } => 0.0,
+			// The acceleration peaks at t = 1/2 ± √3/6, with a value of 10/√3.
+			Self::MinimumJerk { duration } => 10.0 / 3f64.sqrt() / (duration * duration),
+		}
+	}
+}
+
+/// Get the largest absolute distance between the start and end position of any motor, in radians.
+fn max_distance(start: &[Angle], end: &[Angle]) -> f64 {
+	start
+		.iter()
+		.zip(end)
+		.map(|(start, end)| (end.radians() - start.radians()).abs())
+		.fold(0.0, f64::max)
+}
+
+/// Options for [`Client::stream_trajectory()`](crate::Client::stream_trajectory).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct StreamOptions {
+	/// The time between two set-points.
+	pub period: Duration,
+
+	/// Write the peak velocity and acceleration of each motor to its Profile Velocity and Profile Acceleration registers before streaming.
+	///
+	/// This lets the motor smoothly interpolate between set-points.
+	/// It only works correctly if the drive mode of the motors uses a velocity based profile.
+	pub profile_registers: bool,
+}
+
+impl StreamOptions {
+	/// Create options to stream set-points at a fixed period, without using the profile registers.
+	pub fn new(period: Duration) -> Self {
+		Self {
+			period,
+			profile_registers: false,
+		}
+	}
+
+	/// Enable or disable writing the profile registers before streaming.
+	pub fn with_profile_registers(mut self, profile_registers: bool) -> Self {
+		self.profile_registers = profile_registers;
+		self
+	}
+}
+
+/// The result of streaming a trajectory.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StreamOutcome {
+	/// All set-points up to the end position were written.
+	Completed,
+
+	/// Streaming was aborted.
+	Aborted {
+		/// The time since the start of the trajectory of the last written set-point.
+		///
+		/// This is `None` if the trajectory was aborted before the first set-point was written.
+		at: Option<Duration>,
+	},
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	fn approx_eq(a: f64, b: f64) -> bool {
+		(a - b).abs() < 1e-9
+	}
+
+	fn positions(trajectory: &Trajectory, time: f64) -> Vec<f64> {
+		let mut positions = vec![Angle::default(); trajectory.motor_count()];
+		trajectory.sample(Duration::from_secs_f64(time), &mut positions);
+		positions.iter().map(|position| position.radians()).collect()
+	}
+
+	#[test]
+	fn trapezoid_reaches_max_velocity() {
+		let start = [Angle::from_radians(0.0), Angle::from_radians(1.0)];
+		let end = [Angle::from_radians(4.0), Angle::from_radians(-1.0)];
+		let trajectory = Trajectory::trapezoid(
+			&start,
+			&end,
+			AngularVelocity::from_radians_per_second(2.0),
+			AngularAcceleration::from_radians_per_second_squared(2.0),
+		);
+		// 1 second to accelerate to 2 rad/s, 1 second at 2 rad/s and 1 second to decelerate.
+		assert!(approx_eq(trajectory.duration().as_secs_f64(), 3.0));
+		assert!(positions(&trajectory, 0.0) == [0.0, 1.0]);
+		let halfway = positions(&trajectory, 1.5);
+		assert!(approx_eq(halfway[0], 2.0));
+		assert!(approx_eq(halfway[1], 0.0));
+		assert!(approx_eq(positions(&trajectory, 1.0)[0], 1.0));
+		assert!(positions(&trajectory, 5.0) == [4.0, -1.0]);
+
+		assert!(approx_eq(trajectory.peak_velocity(0).radians_per_second(), 2.0));
+		assert!(approx_eq(trajectory.peak_velocity(1).radians_per_second(), 1.0));
+		assert!(approx_eq(trajectory.peak_acceleration(1).radians_per_second_squared(), 1.0));
+	}
+
+	#[test]
+	fn trapezoid_without_cruise() {
+		let trajectory = Trajectory::trapezoid(
+			&[Angle::from_radians(0.0)],
+			&[Angle::from_radians(1.0)],
+			AngularVelocity::from_radians_per_second(10.0),
+			AngularAcceleration::from_radians_per_second_squared(1.0),
+		);
+		assert!(approx_eq(trajectory.duration().as_secs_f64(), 2.0));
+		assert!(approx_eq(positions(&trajectory, 1.0)[0], 0.5));
+		assert!(approx_eq(trajectory.peak_velocity(0).radians_per_second(), 1.0));
+	}
+
+	#[test]
+	fn minimum_jerk() {
+		let trajectory = Trajectory::minimum_jerk(&[Angle::from_radians(1.0)], &[Angle::from_radians(3.0)], Duration::from_secs(2));
+		assert!(trajectory.duration() == Duration::from_secs(2));
+		assert!(approx_eq(positions(&trajectory, 1.0)[0], 2.0));
+		assert!(positions(&trajectory, 2.0) == [3.0]);
+		assert!(approx_eq(trajectory.peak_velocity(0).radians_per_second(), 1.875));
+	}
+
+	#[test]
+	fn zero_duration() {
+		let trajectory = Trajectory::minimum_jerk(&[Angle::from_radians(1.0)], &[Angle::from_radians(3.0)], Duration::ZERO);
+		assert!(trajectory.duration() == Duration::ZERO);
+		assert!(positions(&trajectory, 0.0) == [3.0]);
+	}
+
+	#[test]
+	fn empty_motion() {
+		let start = [Angle::from_radians(1.0)];
+		let trajectory = Trajectory::trapezoid(
+			&start,
+			&start,
+			AngularVelocity::from_radians_per_second(1.0),
+			AngularAcceleration::from_radians_per_second_squared(1.0),
+		);
+		assert!(trajectory.duration() == Duration::ZERO);
+		assert!(positions(&trajectory, 0.0) == [1.0]);
+		assert!(trajectory.peak_velocity(0).radians_per_second() == 0.0);
+	}
+}
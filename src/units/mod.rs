@@ -0,0 +1,328 @@
+//! Conversion between raw register values and physical units.
+//!
+//! Registers like the present position or present velocity hold values in model specific units,
+//! such as encoder ticks or multiples of 0.229 rpm.
+//! This module contains types for physical quantities and [`UnitRegister`] descriptions,
+//! which combine a [`Register`] with the size of one raw unit.
+//!
+//! Use [`Client::read_register()`](crate::Client::read_register) and [`Client::write_register()`](crate::Client::write_register)
+//! to read and write registers in physical units:
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use dynamixel2::units::x_series;
+//! # let mut client = dynamixel2::Client::open("/dev/ttyUSB0", 57600)?;
+//! let position = client.read_register(1, x_series::PRESENT_POSITION)?.data;
+//! println!("position: {} degrees", position.degrees());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The position and velocity units are the same for all models of a family.
+//! The current unit differs per model, so the current registers are created from the [`ModelUnits`] of a model,
+//! as found in the [`control_table::MODELS`](crate::control_table::MODELS) database.
+//!
+//! If the `uom` feature is enabled, the quantities can be converted to and from the types of the `uom` crate.
+
+use crate::bus::Data;
+use crate::control_table::Register;
+use core::marker::PhantomData;
+
+pub mod x_series;
+pub mod xl320;
+
+#[cfg(feature = "uom")]
+mod uom;
+
+/// A physical quantity, stored as a floating point value in a fixed unit.
+pub trait Quantity: Copy {
+	/// Create the quantity from a value in the unit of the quantity.
+	fn from_value(value: f64) -> Self;
+
+	/// Get the value in the unit of the quantity.
+	fn value(self) -> f64;
+}
+
+macro_rules! quantity {
+	($(#[$meta:meta])* $name:ident, $from:ident, $get:ident, $unit:literal) => {
+		$(#[$meta])*
+		#[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd)]
+		pub struct $name {
+			value: f64,
+		}
+
+		impl $name {
+			#[doc = concat!("Create a value in ", $unit, ".")]
+			pub const fn $from(value: f64) -> Self {
+				Self { value }
+			}
+
+			#[doc = concat!("Get the value in ", $unit, ".")]
+			pub const fn $get(self) -> f64 {
+				self.value
+			}
+		}
+
+		impl Quantity for $name {
+			fn from_value(value: f64) -> Self {
+				Self::$from(value)
+			}
+
+			fn value(self) -> f64 {
+				self.$get()
+			}
+		}
+	};
+}
+
+quantity!(
+	/// An angle, such as a position.
+	Angle, from_radians, radians, "radians"
+);
+
+quantity!(
+	/// An angular velocity.
+	AngularVelocity, from_radians_per_second, radians_per_second, "radians per second"
+);
+
+quantity!(
+	/// An angular acceleration.
+	AngularAcceleration, from_radians_per_second_squared, radians_per_second_squared, "radians per second squared"
+);
+
+quantity!(
+	/// An electrical current.
+	Current, from_amperes, amperes, "amperes"
+);
+
+quantity!(
+	/// A temperature.
+	Temperature, from_degrees_celsius, degrees_celsius, "degrees Celsius"
+);
+
+quantity!(
+	/// An electrical voltage.
+	Voltage, from_volts, volts, "volts"
+);
+
+impl Angle {
+	/// Create an angle in degrees.
+	pub const fn from_degrees(degrees: f64) -> Self {
+		Self::from_radians(degrees * RADIANS_PER_DEGREE)
+	}
+
+	/// Get the angle in degrees.
+	pub const fn degrees(self) -> f64 {
+		self.radians() / RADIANS_PER_DEGREE
+	}
+}
+
+impl AngularVelocity {
+	/// Create an angular velocity in revolutions per minute.
+	pub const fn from_rpm(rpm: f64) -> Self {
+		Self::from_radians_per_second(rpm * RADIANS_PER_SECOND_PER_RPM)
+	}
+
+	/// Get the angular velocity in revolutions per minute.
+	pub const fn rpm(self) -> f64 {
+		self.radians_per_second() / RADIANS_PER_SECOND_PER_RPM
+	}
+}
+
+impl Current {
+	/// Create a current in milliamperes.
+	pub const fn from_milliamperes(milliamperes: f64) -> Self {
+		Self::from_amperes(milliamperes / 1000.0)
+	}
+
+	/// Get the current in milliamperes.
+	pub const fn milliamperes(self) -> f64 {
+		self.amperes() * 1000.0
+	}
+}
+
+/// One degree in radians.
+const RADIANS_PER_DEGREE: f64 = core::f64::consts::PI / 180.0;
+
+/// One revolution per minute in radians per second.
+const RADIANS_PER_SECOND_PER_RPM: f64 = core::f64::consts::TAU / 60.0;
+
+/// The size of the raw units of a motor model.
+///
+/// Only units that differ between models of the same family are included.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ModelUnits {
+	/// The size of one position unit in radians.
+	pub position: f64,
+
+	/// The size of one velocity unit in radians per second.
+	pub velocity: f64,
+
+	/// The size of one current unit in amperes, or `None` if the model has no current registers.
+	pub current: Option<f64>,
+}
+
+impl ModelUnits {
+	/// The units of an X-series or MX-series (Protocol 2.0) model with the given current unit in milliamperes.
+	///
+	/// A position unit is 1/4096 revolution and a velocity unit is 0.229 rpm.
+	pub const fn x_series(current_milliamperes: Option<f64>) -> Self {
+		Self {
+			position: x_series::POSITION_UNIT,
+			velocity: x_series::VELOCITY_UNIT,
+			current: match current_milliamperes {
+				Some(current) => Some(current / 1000.0),
+				None => None,
+			},
+		}
+	}
+
+	/// The units of the XL-320.
+	///
+	/// A position unit is 300/1024 degrees and a velocity unit is 0.111 rpm.
+	/// The XL-320 has no current registers.
+	pub const XL320: Self = Self {
+		position: xl320::POSITION_UNIT,
+		velocity: xl320::VELOCITY_UNIT,
+		current: None,
+	};
+}
+
+/// A register holding a physical quantity.
+///
+/// The raw value of type `T` is multiplied by `scale` to get the value of the quantity `Q`.
+#[derive(Debug)]
+pub struct UnitRegister<T, Q> {
+	/// The register.
+	pub register: Register,
+
+	/// The size of one raw unit, in the unit of the quantity.
+	pub scale: f64,
+
+	_marker: PhantomData<fn() -> (T, Q)>,
+}
+
+impl<T, Q> Clone for UnitRegister<T, Q> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<T, Q> Copy for UnitRegister<T, Q> {}
+
+impl<T: RawValue, Q: Quantity> UnitRegister<T, Q> {
+	/// Create a new register description.
+	///
+	/// The size of the register must match the size of `T`.
+	pub const fn new(register: Register, scale: f64) -> Self {
+		assert!(register.size == T::ENCODED_SIZE, "register size does not match the raw value type");
+		Self {
+			register,
+			scale,
+			_marker: PhantomData,
+		}
+	}
+
+	/// Convert a raw register value to the quantity.
+	pub fn to_quantity(&self, raw: T) -> Q {
+		Q::from_value(raw.to_f64() * self.scale)
+	}
+
+	/// Convert a quantity to the raw register value.
+	///
+	/// The value is rounded to the nearest raw value, and saturates at the limits of `T`.
+	pub fn to_raw(&self, value: Q) -> T {
+		T::from_f64(value.value() / self.scale)
+	}
+}
+
+/// A raw register value that can be converted to and from a floating point value.
+pub trait RawValue: Data + Copy {
+	/// Convert the raw value to a floating point value.
+	fn to_f64(self) -> f64;
+
+	/// Convert a floating point value to the nearest raw value.
+	///
+	/// Values outside the range of the type are saturated.
+	fn from_f64(value: f64) -> Self;
+}
+
+macro_rules! impl_raw_value {
+	($($type:ty),*) => {
+		$(
+			impl RawValue for $type {
+				fn to_f64(self) -> f64 {
+					self.into()
+				}
+
+				fn from_f64(value: f64) -> Self {
+					// Float to integer casts saturate, so only rounding is needed.
+					if value < 0.0 {
+						(value - 0.5) as Self
+					} else {
+						(value + 0.5) as Self
+					}
+				}
+			}
+		)*
+	};
+}
+
+impl_raw_value!(u8, i8, u16, i16, u32, i32);
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	fn approx_eq(a: f64, b: f64) -> bool {
+		a - b < 1e-9 && b - a < 1e-9
+	}
+
+	#[test]
+	fn convert_position() {
+		let register = x_series::PRESENT_POSITION;
+		assert!(approx_eq(register.to_quantity(2048).degrees(), 180.0));
+		assert!(approx_eq(register.to_quantity(-1024).degrees(), -90.0));
+		assert!(register.to_raw(Angle::from_degrees(90.0)) == 1024);
+		assert!(register.to_raw(Angle::from_degrees(-0.05)) == -1);
+		assert!(xl320::PRESENT_POSITION.to_raw(Angle::from_degrees(150.0)) == 512);
+	}
+
+	#[test]
+	fn convert_velocity() {
+		let register = x_series::PRESENT_VELOCITY;
+		assert!(approx_eq(register.to_quantity(100).rpm(), 22.9));
+		assert!(register.to_raw(AngularVelocity::from_rpm(-22.9)) == -100);
+	}
+
+	#[test]
+	fn convert_current() {
+		let units = ModelUnits::x_series(Some(2.69));
+		let register = x_series::present_current(&units).unwrap();
+		assert!(approx_eq(register.to_quantity(100).milliamperes(), 269.0));
+		assert!(register.to_raw(Current::from_amperes(-0.269)) == -100);
+		assert!(x_series::present_current(&ModelUnits::x_series(None)).is_none());
+	}
+
+	#[test]
+	#[cfg(feature = "uom")]
+	fn convert_to_uom() {
+		use ::uom::si::angle::degree;
+		use ::uom::si::f64;
+		use ::uom::si::thermodynamic_temperature::kelvin;
+
+		let angle: f64::Angle = Angle::from_degrees(90.0).into();
+		assert!(approx_eq(angle.get::<degree>(), 90.0));
+		let temperature: f64::ThermodynamicTemperature = Temperature::from_degrees_celsius(25.0).into();
+		assert!(approx_eq(temperature.get::<kelvin>(), 298.15));
+		assert!(approx_eq(Temperature::from(temperature).degrees_celsius(), 25.0));
+	}
+
+	#[test]
+	fn raw_values_saturate() {
+		assert!(u8::from_f64(300.0) == 255);
+		assert!(u8::from_f64(-1.0) == 0);
+		assert!(i16::from_f64(-1e9) == i16::MIN);
+		assert!(u16::from_f64(f64::NAN) == 0);
+	}
+}
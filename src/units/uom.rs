@@ -0,0 +1,32 @@
+//! Conversions between the quantities of this module and the `uom` crate.
+
+use uom::si::angle::radian;
+use uom::si::angular_acceleration::radian_per_second_squared;
+use uom::si::angular_velocity::radian_per_second;
+use uom::si::electric_current::ampere;
+use uom::si::electric_potential::volt;
+use uom::si::f64;
+use uom::si::thermodynamic_temperature::degree_celsius;
+
+macro_rules! impl_uom_conversion {
+	($name:ident, $uom:ty, $unit:ty) => {
+		impl From<super::$name> for $uom {
+			fn from(other: super::$name) -> Self {
+				<$uom>::new::<$unit>(super::Quantity::value(other))
+			}
+		}
+
+		impl From<$uom> for super::$name {
+			fn from(other: $uom) -> Self {
+				<Self as super::Quantity>::from_value(other.get::<$unit>())
+			}
+		}
+	};
+}
+
+impl_uom_conversion!(Angle, f64::Angle, radian);
+impl_uom_conversion!(AngularAcceleration, f64::AngularAcceleration, radian_per_second_squared);
+impl_uom_conversion!(AngularVelocity, f64::AngularVelocity, radian_per_second);
+impl_uom_conversion!(Current, f64::ElectricCurrent, ampere);
+impl_uom_conversion!(Temperature, f64::ThermodynamicTemperature, degree_celsius);
+impl_uom_conversion!(Voltage, f64::ElectricPotential, volt);
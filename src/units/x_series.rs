@@ -0,0 +1,94 @@
+//! Registers of the X-series with physical units, also used by the MX-series with Protocol 2.0 firmware.
+//!
+//! The current registers depend on the model, see [`present_current()`], [`goal_current()`] and [`current_limit()`].
+
+use super::{Angle, AngularAcceleration, AngularVelocity, Current, ModelUnits, Temperature, UnitRegister, Voltage};
+use crate::control_table::x_series;
+
+/// The size of one position unit in radians: 1/4096 revolution.
+pub const POSITION_UNIT: f64 = core::f64::consts::TAU / 4096.0;
+
+/// The size of one velocity unit in radians per second: 0.229 rpm.
+pub const VELOCITY_UNIT: f64 = 0.229 * core::f64::consts::TAU / 60.0;
+
+/// The size of one acceleration unit in radians per second squared: 214.577 revolutions per minute squared.
+pub const ACCELERATION_UNIT: f64 = 214.577 * core::f64::consts::TAU / 3600.0;
+
+/// The size of one temperature unit in degrees Celsius.
+pub const TEMPERATURE_UNIT: f64 = 1.0;
+
+/// The size of one voltage unit in volts.
+pub const VOLTAGE_UNIT: f64 = 0.1;
+
+/// The Homing Offset register.
+pub const HOMING_OFFSET: UnitRegister<i32, Angle> = UnitRegister::new(x_series::HOMING_OFFSET, POSITION_UNIT);
+
+/// The Temperature Limit register.
+pub const TEMPERATURE_LIMIT: UnitRegister<u8, Temperature> = UnitRegister::new(x_series::TEMPERATURE_LIMIT, TEMPERATURE_UNIT);
+
+/// The Max Voltage Limit register.
+pub const MAX_VOLTAGE_LIMIT: UnitRegister<u16, Voltage> = UnitRegister::new(x_series::MAX_VOLTAGE_LIMIT, VOLTAGE_UNIT);
+
+/// The Min Voltage Limit register.
+pub const MIN_VOLTAGE_LIMIT: UnitRegister<u16, Voltage> = UnitRegister::new(x_series::MIN_VOLTAGE_LIMIT, VOLTAGE_UNIT);
+
+/// The Velocity Limit register.
+pub const VELOCITY_LIMIT: UnitRegister<u32, AngularVelocity> = UnitRegister::new(x_series::VELOCITY_LIMIT, VELOCITY_UNIT);
+
+/// The Max Position Limit register.
+pub const MAX_POSITION_LIMIT: UnitRegister<u32, Angle> = UnitRegister::new(x_series::MAX_POSITION_LIMIT, POSITION_UNIT);
+
+/// The Min Position Limit register.
+pub const MIN_POSITION_LIMIT: UnitRegister<u32, Angle> = UnitRegister::new(x_series::MIN_POSITION_LIMIT, POSITION_UNIT);
+
+/// The Goal Velocity register.
+pub const GOAL_VELOCITY: UnitRegister<i32, AngularVelocity> = UnitRegister::new(x_series::GOAL_VELOCITY, VELOCITY_UNIT);
+
+/// The Profile Acceleration register.
+///
+/// This register is only in acceleration units if the drive mode uses a velocity based profile.
+pub const PROFILE_ACCELERATION: UnitRegister<u32, AngularAcceleration> = UnitRegister::new(x_series::PROFILE_ACCELERATION, ACCELERATION_UNIT);
+
+/// The Profile Velocity register.
+///
+/// This register is only in velocity units if the drive mode uses a velocity based profile.
+pub const PROFILE_VELOCITY: UnitRegister<u32, AngularVelocity> = UnitRegister::new(x_series::PROFILE_VELOCITY, VELOCITY_UNIT);
+
+/// The Goal Position register.
+pub const GOAL_POSITION: UnitRegister<i32, Angle> = UnitRegister::new(x_series::GOAL_POSITION, POSITION_UNIT);
+
+/// The Present Velocity register.
+pub const PRESENT_VELOCITY: UnitRegister<i32, AngularVelocity> = UnitRegister::new(x_series::PRESENT_VELOCITY, VELOCITY_UNIT);
+
+/// The Present Position register.
+pub const PRESENT_POSITION: UnitRegister<i32, Angle> = UnitRegister::new(x_series::PRESENT_POSITION, POSITION_UNIT);
+
+/// The Present Input Voltage register.
+pub const PRESENT_INPUT_VOLTAGE: UnitRegister<u16, Voltage> = UnitRegister::new(x_series::PRESENT_INPUT_VOLTAGE, VOLTAGE_UNIT);
+
+/// The Present Temperature register.
+pub const PRESENT_TEMPERATURE: UnitRegister<u8, Temperature> = UnitRegister::new(x_series::PRESENT_TEMPERATURE, TEMPERATURE_UNIT);
+
+/// The Current Limit register, or `None` if the model has no current registers.
+pub const fn current_limit(units: &ModelUnits) -> Option<UnitRegister<u16, Current>> {
+	match units.current {
+		Some(scale) => Some(UnitRegister::new(x_series::CURRENT_LIMIT, scale)),
+		None => None,
+	}
+}
+
+/// The Goal Current register, or `None` if the model has no current registers.
+pub const fn goal_current(units: &ModelUnits) -> Option<UnitRegister<i16, Current>> {
+	match units.current {
+		Some(scale) => Some(UnitRegister::new(x_series::GOAL_CURRENT, scale)),
+		None => None,
+	}
+}
+
+/// The Present Current register, or `None` if the model has no current registers.
+pub const fn present_current(units: &ModelUnits) -> Option<UnitRegister<i16, Current>> {
+	match units.current {
+		Some(scale) => Some(UnitRegister::new(x_series::PRESENT_CURRENT, scale)),
+		None => None,
+	}
+}
@@ -0,0 +1,47 @@
+//! Registers of the XL-320 with physical units.
+//!
+//! The speed registers of the XL-320 store the direction in bit 10 instead of using a signed value,
+//! so they are not available as [`UnitRegister`].
+//! Use [`VELOCITY_UNIT`] to convert them manually.
+
+use super::{Angle, Temperature, UnitRegister, Voltage};
+use crate::control_table::xl320;
+
+/// The size of one position unit in radians: 300/1024 degrees.
+pub const POSITION_UNIT: f64 = 300.0 / 1024.0 * core::f64::consts::PI / 180.0;
+
+/// The size of one velocity unit in radians per second: 0.111 rpm.
+pub const VELOCITY_UNIT: f64 = 0.111 * core::f64::consts::TAU / 60.0;
+
+/// The size of one temperature unit in degrees Celsius.
+pub const TEMPERATURE_UNIT: f64 = 1.0;
+
+/// The size of one voltage unit in volts.
+pub const VOLTAGE_UNIT: f64 = 0.1;
+
+/// The CW Angle Limit register.
+pub const CW_ANGLE_LIMIT: UnitRegister<u16, Angle> = UnitRegister::new(xl320::CW_ANGLE_LIMIT, POSITION_UNIT);
+
+/// The CCW Angle Limit register.
+pub const CCW_ANGLE_LIMIT: UnitRegister<u16, Angle> = UnitRegister::new(xl320::CCW_ANGLE_LIMIT, POSITION_UNIT);
+
+/// The Temperature Limit register.
+pub const TEMPERATURE_LIMIT: UnitRegister<u8, Temperature> = UnitRegister::new(xl320::TEMPERATURE_LIMIT, TEMPERATURE_UNIT);
+
+/// The Min Voltage Limit register.
+pub const MIN_VOLTAGE_LIMIT: UnitRegister<u8, Voltage> = UnitRegister::new(xl320::MIN_VOLTAGE_LIMIT, VOLTAGE_UNIT);
+
+/// The Max Voltage Limit register.
+pub const MAX_VOLTAGE_LIMIT: UnitRegister<u8, Voltage> = UnitRegister::new(xl320::MAX_VOLTAGE_LIMIT, VOLTAGE_UNIT);
+
+/// The Goal Position register.
+pub const GOAL_POSITION: UnitRegister<u16, Angle> = UnitRegister::new(xl320::GOAL_POSITION, POSITION_UNIT);
+
+/// The Present Position register.
+pub const PRESENT_POSITION: UnitRegister<u16, Angle> = UnitRegister::new(xl320::PRESENT_POSITION, POSITION_UNIT);
+
+/// The Present Voltage register.
+pub const PRESENT_VOLTAGE: UnitRegister<u8, Voltage> = UnitRegister::new(xl320::PRESENT_VOLTAGE, VOLTAGE_UNIT);
+
+/// The Present Temperature register.
+pub const PRESENT_TEMPERATURE: UnitRegister<u8, Temperature> = UnitRegister::new(xl320::PRESENT_TEMPERATURE, TEMPERATURE_UNIT);
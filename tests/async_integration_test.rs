@@ -0,0 +1,193 @@
+#![cfg(feature = "std")]
+
+use assert2::{assert, let_assert};
+use dynamixel2::client::{BulkReadData, DiscoveredMotor, ProtocolVersion};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+pub mod common;
+use common::mock::run_mock_async;
+
+#[test_log::test(tokio::test)]
+async fn test_sync_read() {
+	run_mock_async(|ids, mut client| async move {
+		let mut response = client.sync_read::<u32>(ids, 132).await.unwrap();
+		for id in ids {
+			let_assert!(Some(Ok(r)) = response.read_next().await, "id {id}");
+			assert!(r.motor_id == *id);
+		}
+		assert!(let None = response.read_next().await);
+	})
+	.await
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sync_read_all() {
+	run_mock_async(|ids, mut client| async move {
+		let responses: BTreeMap<_, _> = client.sync_read_all::<u32, _>(ids, 132).await.unwrap();
+		assert!(responses.len() == ids.len());
+		for id in ids {
+			let_assert!(Some(Ok(r)) = responses.get(id), "id {id}");
+			assert!(r.motor_id == *id);
+		}
+
+		let scanned: BTreeMap<_, _> = client.scan_all().await.unwrap();
+		assert!(scanned.keys().copied().eq(ids.iter().copied()));
+	})
+	.await
+}
+
+#[test_log::test(tokio::test)]
+async fn test_discover_with_protocol1() {
+	run_mock_async(|ids, mut client| async move {
+		client.set_baud_rate(115_200).unwrap();
+		// The mock devices never reply to Protocol 1.0 probes, so keep the probe timeouts short.
+		client.set_response_timeout_padding(Duration::from_millis(1));
+		let motors: Vec<DiscoveredMotor> = client.discover_with_protocol1(&[57_600]).await.unwrap();
+		assert!(client.baud_rate() == 115_200);
+		let mut found: Vec<u8> = motors.iter().map(|motor| motor.motor_id).collect();
+		found.sort();
+		assert!(found == ids);
+		assert!(motors.iter().all(|motor| motor.protocol == ProtocolVersion::V2 && motor.baud_rate == 57_600));
+	})
+	.await
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sync_read_dropped_early() {
+	run_mock_async(|ids, mut client| async move {
+		{
+			let mut response = client.sync_read::<u32>(ids, 132).await.unwrap();
+			let_assert!(Some(Ok(r)) = response.read_next().await);
+			assert!(r.motor_id == ids[0]);
+		}
+
+		// The reply of the second motor must not be mistaken for the ping reply.
+		let_assert!(Ok(r) = client.ping(ids[0]).await);
+		assert!(r.motor_id == ids[0]);
+	})
+	.await
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sync_read_bytes_dropped_unread() {
+	run_mock_async(|ids, mut client| async move {
+		{
+			let _response = client.sync_read_bytes::<Vec<u8>>(ids, 132, 4).await.unwrap();
+		}
+
+		let_assert!(Ok(r) = client.read::<u16>(ids[0], 132).await);
+		assert!(r.motor_id == ids[0]);
+	})
+	.await
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sync_read_dropped_unread_records_alerts() {
+	use dynamixel2::control_table::x_series as registers;
+
+	run_mock_async(|ids, mut client| async move {
+		// Fake a hardware error by writing the read-only Hardware Error Status register, which only the mock allows.
+		let_assert!(Ok(_) = client.write(ids[1], registers::HARDWARE_ERROR_STATUS.address, &1u8).await);
+		let_assert!(Ok(_) = client.read::<u8>(ids[1], registers::HARDWARE_ERROR_STATUS.address).await);
+		assert!(client.take_alert(ids[1]));
+		{
+			let _response = client.sync_read::<u32>(ids, 132).await.unwrap();
+		}
+
+		// The discarded replies still record the alert bit.
+		let_assert!(Ok(_) = client.ping(ids[0]).await);
+		assert!(client.alerts().contains(ids[1]));
+		assert!(!client.alerts().contains(ids[0]));
+	})
+	.await
+}
+
+#[test_log::test(tokio::test)]
+async fn test_sync_read_cancelled() {
+	run_mock_async(|ids, mut client| async move {
+		{
+			let mut response = client.sync_read::<u32>(ids, 132).await.unwrap();
+			// Cancel the read halfway, like a losing `tokio::select!` branch.
+			let _ = tokio::time::timeout(Duration::ZERO, response.read_next()).await;
+		}
+
+		let_assert!(Ok(r) = client.ping(ids[1]).await);
+		assert!(r.motor_id == ids[1]);
+	})
+	.await
+}
+
+#[test_log::test(tokio::test)]
+async fn test_bulk_read_bytes_dropped_early() {
+	run_mock_async(|ids, mut client| async move {
+		let bulk_read_data: Vec<_> = ids
+			.iter()
+			.map(|id| BulkReadData {
+				motor_id: *id,
+				address: 132,
+				count: 4,
+			})
+			.collect();
+		{
+			let mut response = client.bulk_read_bytes::<Vec<u8>>(&bulk_read_data).await.unwrap();
+			let_assert!(Some(Ok(r)) = response.read_next().await);
+			assert!(r.motor_id == ids[0]);
+		}
+
+		let_assert!(Ok(r) = client.ping(ids[0]).await);
+		assert!(r.motor_id == ids[0]);
+	})
+	.await
+}
+
+#[test_log::test(tokio::test)]
+async fn test_scan_dropped_early() {
+	run_mock_async(|ids, mut client| async move {
+		{
+			let mut scan = client.scan().await.unwrap();
+			let_assert!(Some(Ok(_)) = scan.scan_next().await);
+		}
+
+		let_assert!(Ok(r) = client.read::<u32>(ids[1], 132).await);
+		assert!(r.motor_id == ids[1]);
+	})
+	.await
+}
+
+#[test_log::test(tokio::test)]
+#[cfg(feature = "serial2-tokio")]
+async fn test_control_loop() {
+	use dynamixel2::client::{AsyncControlLoop, AsyncMotorGroup};
+	use dynamixel2::control_table::x_series as registers;
+	use std::ops::ControlFlow;
+
+	run_mock_async(|ids, mut client| async move {
+		let group = AsyncMotorGroup::<i32>::from_registers(ids, &registers::GOAL_POSITION, &registers::PRESENT_POSITION);
+		let mut control_loop = AsyncControlLoop::new(&client, group, Duration::from_millis(50));
+
+		let_assert!(Ok(_) = client.write(ids[0], 132, &10i32).await);
+		let_assert!(Ok(_) = client.write(ids[1], 132, &20i32).await);
+		let result = control_loop
+			.run(&mut client, |cycle, states, goals| {
+				if cycle.index >= 3 {
+					return ControlFlow::Break(cycle.index);
+				}
+				for (goal, state) in goals.iter_mut().zip(states) {
+					*goal = state + 1;
+				}
+				ControlFlow::Continue(())
+			})
+			.await;
+		let_assert!(Ok(index) = result);
+		let stats = control_loop.stats();
+		assert!(stats.cycles + stats.skipped_cycles == index);
+		assert!(stats.max_cycle_time >= stats.last_cycle_time);
+
+		let_assert!(Ok(response) = client.read::<i32>(ids[0], 116).await);
+		assert!(response.data == 11);
+		let_assert!(Ok(response) = client.read::<i32>(ids[1], 116).await);
+		assert!(response.data == 21);
+	})
+	.await
+}
@@ -10,6 +10,30 @@ use std::thread;
 use std::thread::JoinHandle;
 use std::time::Duration;
 
+/// The address of the ID register in the X-series control table.
+const ID_ADDRESS: u16 = 7;
+
+/// The address of the Operating Mode register in the X-series control table.
+const OPERATING_MODE_ADDRESS: u16 = 11;
+
+/// The operating modes supported by the X-series.
+const OPERATING_MODES: [u8; 6] = [0, 1, 3, 4, 5, 16];
+
+/// The address of the Bus Watchdog register in the X-series control table.
+const BUS_WATCHDOG_ADDRESS: u16 = 98;
+
+/// The largest value of the Bus Watchdog register that can be written.
+const MAX_BUS_WATCHDOG: u8 = 127;
+
+/// The Data Range error of a status packet.
+const DATA_RANGE_ERROR: u8 = 0x04;
+
+/// The address of the Hardware Error Status register in the X-series control table.
+const HARDWARE_ERROR_STATUS_ADDRESS: u16 = 70;
+
+/// The start of the RAM area in the X-series control table, which is reset by a reboot.
+const RAM_ADDRESS: usize = 64;
+
 pub struct ControlTable {
 	data: Vec<u8>,
 }
@@ -39,6 +63,21 @@ impl ControlTable {
 		trace!("Wrote {:?} to {:?} in control table", &self.data[start..end], address);
 		true
 	}
+
+	/// Reset the RAM area to zero, like a reboot of a real motor.
+	pub fn reset_ram(&mut self) {
+		self.data[RAM_ADDRESS..].fill(0);
+	}
+}
+
+/// Check the values of a write instruction, like a real motor rejects values outside the range of a register.
+fn is_valid_write(address: u16, parameters: &[u8]) -> bool {
+	let value_at = |register: u16| {
+		let offset = register.checked_sub(address)?;
+		parameters.get(usize::from(offset)).copied()
+	};
+	value_at(OPERATING_MODE_ADDRESS).is_none_or(|mode| OPERATING_MODES.contains(&mode))
+		&& value_at(BUS_WATCHDOG_ADDRESS).is_none_or(|value| value <= MAX_BUS_WATCHDOG)
 }
 
 pub struct MockDevice {
@@ -53,7 +92,7 @@ impl MockDevice {
 		MockDevice {
 			id,
 			device,
-			control_table: ControlTable::new(200),
+			control_table: ControlTable::new(700),
 			action_register: None,
 		}
 	}
@@ -84,9 +123,10 @@ impl MockDevice {
 						);
 					},
 					Instructions::Read { address, length } => {
+						let error = self.status_error();
 						if let Some(data) = self.control_table.read(address, length) {
 							let_assert!(
-								Ok(()) = self.device.write_status(self.id, 0, length as usize, |buffer| {
+								Ok(()) = self.device.write_status(self.id, error, length as usize, |buffer| {
 									buffer.copy_from_slice(data);
 									Ok(())
 								})
@@ -96,8 +136,16 @@ impl MockDevice {
 						}
 					},
 					Instructions::Write { address, parameters } => {
-						if self.control_table.write(address, parameters) {
+						let id_offset = ID_ADDRESS.checked_sub(address);
+						let new_id = id_offset.and_then(|offset| parameters.get(usize::from(offset)).copied());
+						if !is_valid_write(address, parameters) {
+							let_assert!(Ok(()) = self.device.write_status_error(self.id, DATA_RANGE_ERROR));
+						} else if self.control_table.write(address, parameters) {
 							let_assert!(Ok(()) = self.device.write_status_ok(self.id));
+							// Like a real motor, start using a new ID after replying.
+							if let Some(id) = new_id {
+								self.id = id;
+							}
 						} else {
 							let_assert!(Ok(()) = self.device.write_status_error(self.id, 0x07));
 						}
@@ -139,7 +187,7 @@ impl MockDevice {
 									break id_index;
 								}
 								let length = u16::from_le_bytes(parameters[id_index + 3..id_index + 5].try_into().unwrap());
-								id_index += length as usize
+								id_index += 5 + length as usize
 							}
 						};
 						let address = u16::from_le_bytes(parameters[id_index + 1..id_index + 3].try_into().unwrap());
@@ -165,7 +213,12 @@ impl MockDevice {
 						}
 					},
 					Instructions::FactoryReset(_) => todo!("handle FactoryReset"),
-					Instructions::Reboot => todo!("handle Reboot"),
+					Instructions::Reboot => {
+						if id != 254 {
+							let_assert!(Ok(()) = self.device.write_status_ok(self.id));
+						}
+						self.control_table.reset_ram();
+					},
 					Instructions::Clear(_) => todo!("handle Clear"),
 					Instructions::StatusPacket { .. } => (),
 					Instructions::Unknown { instruction, .. } => error!("Unknown instruction {:?}", instruction),
@@ -174,12 +227,21 @@ impl MockDevice {
 		})
 	}
 
+	/// The error field for a status packet: the alert bit is set while the Hardware Error Status register is not zero.
+	fn status_error(&self) -> u8 {
+		match self.control_table.read(HARDWARE_ERROR_STATUS_ADDRESS, 1) {
+			Some([0]) | None => 0,
+			Some(_) => 0x80,
+		}
+	}
+
 	fn handle_sync_bulk_read(&mut self, address: u16, length: u16, ids: Vec<u8>) {
 		for next_id in ids.clone() {
 			if next_id == self.id {
+				let error = self.status_error();
 				if let Some(data) = self.control_table.read(address, length) {
 					let_assert!(
-						Ok(()) = self.device.write_status(self.id, 0, length as usize, |buffer| {
+						Ok(()) = self.device.write_status(self.id, error, length as usize, |buffer| {
 							buffer.copy_from_slice(data);
 							Ok(())
 						})
@@ -1,4 +1,4 @@
-use dynamixel2::SerialPort;
+use dynamixel2::{AsyncSerialPort, SerialPort};
 use log::trace;
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::{Duration, Instant};
@@ -58,6 +58,27 @@ impl MockSerial {
 	pub fn add_device(&mut self, serial: SharedBuffer) {
 		self.other_device_buffers.push(serial);
 	}
+
+	fn try_read(&mut self, buffer: &mut [u8]) -> Option<usize> {
+		let mut data = self.read_buffer.read()?;
+		if data.is_empty() {
+			return None;
+		}
+		let len = data.len().min(buffer.len());
+		if len == 0 {
+			panic!("buffer is too small");
+		}
+		buffer[..len].copy_from_slice(&data[..len]);
+		data.drain(..len);
+		trace!("{} read: {:?}", self.name, &buffer[..len]);
+		Some(len)
+	}
+
+	fn write_to_devices(&mut self, buffer: &[u8]) {
+		self.other_device_buffers.iter().for_each(|sender| {
+			sender.write(buffer);
+		});
+	}
 }
 
 impl SerialPort for MockSerial {
@@ -82,28 +103,58 @@ impl SerialPort for MockSerial {
 			if Instant::now() > *deadline {
 				return Err(std::io::ErrorKind::TimedOut.into());
 			}
-			if let Some(mut data) = self.read_buffer.read() {
-				if data.is_empty() {
-					continue;
-				}
-				let len = data.len();
-				if len > buffer.len() {
-					panic!("buffer is too small");
-				}
-				buffer[..len].copy_from_slice(&data);
-				data.clear();
-				if !buffer[..len].is_empty() {
-					trace!("{} read: {:?}", self.name, &buffer[..len]);
-				}
+			if let Some(len) = self.try_read(buffer) {
 				return Ok(len);
 			}
+			std::thread::yield_now();
 		}
 	}
 
 	fn write_all(&mut self, buffer: &[u8]) -> Result<(), Self::Error> {
-		self.other_device_buffers.iter().for_each(|sender| {
-			sender.write(buffer);
-		});
+		self.write_to_devices(buffer);
+		Ok(())
+	}
+
+	fn make_deadline(&self, timeout: Duration) -> Self::Instant {
+		Instant::now() + timeout + self.extra_timeout
+	}
+
+	fn is_timeout_error(error: &Self::Error) -> bool {
+		error.kind() == std::io::ErrorKind::TimedOut
+	}
+}
+
+impl AsyncSerialPort for MockSerial {
+	type Error = std::io::Error;
+	type Instant = std::time::Instant;
+
+	fn baud_rate(&self) -> Result<u32, Self::Error> {
+		Ok(self.baud_rate)
+	}
+
+	fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), Self::Error> {
+		self.baud_rate = baud_rate;
+		Ok(())
+	}
+
+	fn discard_input_buffer(&mut self) -> Result<(), Self::Error> {
+		Ok(())
+	}
+
+	async fn read(&mut self, buffer: &mut [u8], deadline: &Self::Instant) -> Result<usize, Self::Error> {
+		loop {
+			if Instant::now() > *deadline {
+				return Err(std::io::ErrorKind::TimedOut.into());
+			}
+			if let Some(len) = self.try_read(buffer) {
+				return Ok(len);
+			}
+			tokio::task::yield_now().await;
+		}
+	}
+
+	async fn write_all(&mut self, buffer: &[u8]) -> Result<(), Self::Error> {
+		self.write_to_devices(buffer);
 		Ok(())
 	}
 
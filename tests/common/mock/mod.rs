@@ -1,6 +1,4 @@
-#![cfg(feature = "std")]
-
-use dynamixel2::{client::Client, device::Device};
+use dynamixel2::{client::Client, device::Device, AsyncClient};
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering::Relaxed;
 use std::sync::Arc;
@@ -11,6 +9,18 @@ use mock_device::MockDevice;
 use mock_serial_port::MockSerial;
 
 pub fn new_client_device(device_ids: &[u8]) -> Result<(Client<MockSerial>, Vec<MockDevice>), std::io::Error> {
+	let (client_serial, devices) = new_serial_device(device_ids);
+	let client = Client::new(client_serial)?;
+	Ok((client, devices))
+}
+
+pub fn new_async_client_device(device_ids: &[u8]) -> Result<(AsyncClient<MockSerial>, Vec<MockDevice>), std::io::Error> {
+	let (client_serial, devices) = new_serial_device(device_ids);
+	let client = AsyncClient::new(client_serial)?;
+	Ok((client, devices))
+}
+
+fn new_serial_device(device_ids: &[u8]) -> (MockSerial, Vec<MockDevice>) {
 	let mut client_serial = MockSerial::new("Client");
 	let device_ports: Vec<_> = device_ids
 		.iter()
@@ -37,9 +47,7 @@ pub fn new_client_device(device_ids: &[u8]) -> Result<(Client<MockSerial>, Vec<M
 			MockDevice::new(*id, device)
 		})
 		.collect();
-	let client = Client::new(client_serial)?;
-
-	Ok((client, devices))
+	(client_serial, devices)
 }
 
 pub fn run_mock<F>(test: F)
@@ -54,3 +62,17 @@ where
 	kill_device.store(true, Relaxed);
 	device_t.into_iter().for_each(|d| d.join().unwrap());
 }
+
+pub async fn run_mock_async<F, Fut>(test: F)
+where
+	F: FnOnce(&'static [u8], AsyncClient<MockSerial>) -> Fut,
+	Fut: std::future::Future<Output = ()>,
+{
+	let device_ids = &[1, 2];
+	let kill_device = Arc::new(AtomicBool::new(false));
+	let (bus, devices) = new_async_client_device(device_ids).unwrap();
+	let device_t = devices.into_iter().map(|d| d.run(kill_device.clone())).collect::<Vec<_>>();
+	test(device_ids, bus).await;
+	kill_device.store(true, Relaxed);
+	device_t.into_iter().for_each(|d| d.join().unwrap());
+}
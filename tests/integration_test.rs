@@ -1,7 +1,12 @@
 #![cfg(feature = "std")]
 
 use assert2::{assert, let_assert};
-use dynamixel2::client::{BulkReadData, SyncWriteData};
+use dynamixel2::client::{BaudRate, BulkReadData, ControlLoop, DiscoveredMotor, MotorGroup, MotorMap, Priority, ProtocolVersion, SafeStopReport, SharedClient, SyncWriteData, Watchdog};
+use dynamixel2::units::{x_series, Angle, AngularAcceleration, AngularVelocity};
+use dynamixel2::{ConfigureError, RestoreError};
+use std::collections::BTreeMap;
+use std::ops::ControlFlow;
+use std::time::Duration;
 use test_log::test;
 
 pub mod common;
@@ -114,6 +119,39 @@ fn test_bulk_read_bytes() {
 	})
 }
 
+#[test]
+fn test_sync_read_all() {
+	run(|ids, mut client| {
+		let responses: BTreeMap<_, _> = client.sync_read_all::<u32, _>(ids, 132).unwrap();
+		assert!(responses.len() == ids.len());
+		for id in ids {
+			let_assert!(Some(Ok(r)) = responses.get(id), "id {id}");
+			assert!(r.motor_id == *id);
+		}
+	})
+}
+
+#[test]
+fn test_bulk_read_all() {
+	run(|ids, mut client| {
+		let bulk_read_data: Vec<_> = ids
+			.iter()
+			.map(|id| BulkReadData {
+				motor_id: *id,
+				address: 132,
+				count: 4,
+			})
+			.collect();
+		let responses: MotorMap<_, 8> = client.bulk_read_all::<Vec<u8>, _>(&bulk_read_data).unwrap();
+		assert!(responses.len() == ids.len());
+		for id in ids {
+			let_assert!(Some(Ok(r)) = responses.get(*id), "id {id}");
+			assert!(r.motor_id == *id);
+			assert!(r.data.len() == 4);
+		}
+	})
+}
+
 #[test]
 fn test_sync_write() {
 	run(|ids, mut client| {
@@ -129,3 +167,684 @@ fn test_ping() {
 		assert!(response.into_iter().count() == ids.len(), "missing motor ping");
 	})
 }
+
+#[test]
+fn test_scan_all() {
+	run(|ids, mut client| {
+		let responses: Vec<_> = client.scan_all().unwrap();
+		let mut found: Vec<u8> = responses.iter().map(|(id, _)| *id).collect();
+		found.sort();
+		assert!(found == ids);
+	})
+}
+
+#[test]
+fn test_scan_ends_without_pending_responses() {
+	run(|ids, mut client| {
+		let mut scan = client.scan().unwrap();
+		let mut found = Vec::new();
+		for response in scan.by_ref() {
+			found.push(response.unwrap().motor_id);
+		}
+		found.sort();
+		assert!(found == ids);
+
+		// The scan already waited for the last reply, so neither dropping it nor the next instruction should wait again.
+		let start = std::time::Instant::now();
+		drop(scan);
+		let_assert!(Ok(_) = client.ping(ids[0]));
+		assert!(start.elapsed() < Duration::from_millis(100));
+	})
+}
+
+#[test]
+fn test_discover() {
+	run(|ids, mut client| {
+		let original_baud_rate = client.baud_rate();
+		let motors: Vec<DiscoveredMotor> = client.discover(&[57_600, 115_200]).unwrap();
+		assert!(client.baud_rate() == original_baud_rate);
+		for baud_rate in [57_600, 115_200] {
+			let mut found: Vec<u8> = motors
+				.iter()
+				.filter(|motor| motor.baud_rate == baud_rate)
+				.map(|motor| motor.motor_id)
+				.collect();
+			found.sort();
+			assert!(found == ids, "baud rate {baud_rate}");
+		}
+		assert!(motors.iter().all(|motor| motor.protocol == ProtocolVersion::V2));
+		let_assert!(Ok(_) = client.ping(ids[0]));
+	})
+}
+
+#[test]
+fn test_change_id() {
+	run(|ids, mut client| {
+		let_assert!(Err(ConfigureError::IdInUse { motor_id }) = client.change_id(ids[0], ids[1]));
+		assert!(motor_id == ids[1]);
+		let_assert!(Err(ConfigureError::InvalidId { .. }) = client.change_id(ids[0], 254));
+
+		let_assert!(Ok(()) = client.change_id(ids[0], 10));
+		let_assert!(Ok(_) = client.ping(10));
+		let_assert!(Ok(()) = client.change_id(10, ids[0]));
+		let_assert!(Ok(_) = client.ping(ids[0]));
+	})
+}
+
+#[test]
+fn test_change_id_torque_enabled() {
+	run(|ids, mut client| {
+		let_assert!(Ok(_) = client.write(ids[0], 64, &1u8));
+		let_assert!(Err(ConfigureError::TorqueEnabled { motor_id }) = client.change_id(ids[0], 10));
+		assert!(motor_id == ids[0]);
+		let_assert!(Ok(_) = client.write(ids[0], 64, &0u8));
+	})
+}
+
+#[test]
+fn test_change_baud_rate() {
+	run(|ids, mut client| {
+		let original_baud_rate = BaudRate::from_bits_per_second(client.baud_rate()).unwrap_or(BaudRate::B57600);
+		let_assert!(Ok(()) = client.change_baud_rate(ids[0], BaudRate::B115200));
+		assert!(client.baud_rate() == 115_200);
+		let_assert!(Ok(response) = client.read::<u8>(ids[0], 8));
+		assert!(response.data == 2);
+		let_assert!(Ok(()) = client.change_baud_rate(ids[0], original_baud_rate));
+	})
+}
+
+#[test]
+#[cfg(feature = "config")]
+fn test_apply_config() {
+	use dynamixel2::config::BusConfig;
+
+	run(|ids, mut client| {
+		let config = format!(
+			r#"
+				[[motors]]
+				id = {}
+				registers = {{ "Homing Offset" = -10, "Position P Gain" = 800 }}
+
+				[[motors]]
+				id = 42
+			"#,
+			ids[0]
+		);
+		let_assert!(Ok(config) = BusConfig::from_toml(&config));
+
+		let_assert!(Ok(diff) = client.diff_config(&config));
+		assert!(diff.missing_motors == [42]);
+		assert!(diff.motors.len() == 1);
+		let changed: Vec<_> = diff.motors[0].changes.iter().map(|change| change.register.name).collect();
+		assert!(changed == ["Homing Offset", "Position P Gain"]);
+
+		let_assert!(Ok(applied) = client.apply_config(&config));
+		assert!(applied == diff);
+		let_assert!(Ok(response) = client.read::<i32>(ids[0], 20));
+		assert!(response.data == -10);
+
+		let_assert!(Ok(diff) = client.diff_config(&config));
+		assert!(diff.motors[0].changes.is_empty());
+	})
+}
+
+#[test]
+#[cfg(feature = "config")]
+fn test_apply_config_restores_torque_on_error() {
+	use dynamixel2::config::{BusConfig, ConfigError};
+	use dynamixel2::{ReadError, StatusError, TransferError};
+
+	run(|ids, mut client| {
+		// Operating mode 2 is not supported, so the motor rejects it after torque has been disabled.
+		let config = format!(
+			r#"
+				[[motors]]
+				id = {}
+				registers = {{ "Operating Mode" = 2 }}
+			"#,
+			ids[0]
+		);
+		let_assert!(Ok(config) = BusConfig::from_toml(&config));
+
+		let_assert!(Ok(_) = client.write(ids[0], 64, &1u8));
+		let_assert!(Err(ConfigError::TransferError(TransferError::ReadError(ReadError::MotorError(e)))) = client.apply_config(&config));
+		assert!(e.status_error() == Some(StatusError::DataRangeError));
+		let_assert!(Ok(response) = client.read::<u8>(ids[0], 64));
+		assert!(response.data == 1);
+		let_assert!(Ok(_) = client.write(ids[0], 64, &0u8));
+	})
+}
+
+#[test]
+fn test_dump_and_restore_control_table() {
+	run(|ids, mut client| {
+		let_assert!(Ok(_) = client.write(ids[0], 20, &-10i32));
+		let_assert!(Ok(snapshot) = client.dump_control_table(ids[0], 0..148));
+		assert!(snapshot.motor_id == ids[0]);
+		assert!(snapshot.model == 5130);
+		assert!(snapshot.firmware == 46);
+		assert!(snapshot.data.len() == 148);
+		assert!(snapshot.data[20..24] == (-10i32).to_le_bytes());
+
+		let_assert!(Ok(_) = client.write(ids[0], 20, &25i32));
+		let_assert!(Ok(()) = client.restore_control_table_snapshot(ids[0], &snapshot));
+		let_assert!(Ok(response) = client.read::<i32>(ids[0], 20));
+		assert!(response.data == -10);
+
+		let_assert!(Ok(()) = client.restore_control_table_snapshot(ids[1], &snapshot));
+		let_assert!(Ok(_) = client.ping(ids[1]));
+		let_assert!(Ok(response) = client.read::<i32>(ids[1], 20));
+		assert!(response.data == -10);
+
+		let mut other_model = snapshot.clone();
+		other_model.model = 1020;
+		let_assert!(Err(RestoreError::ModelMismatch { actual: 5130, .. }) = client.restore_control_table_snapshot(ids[0], &other_model));
+	})
+}
+
+#[test]
+fn test_restore_control_table_changes() {
+	run(|ids, mut client| {
+		let_assert!(Ok(snapshot) = client.dump_control_table(ids[0], 0..148));
+		let_assert!(Ok(_) = client.write(ids[0], 84, &900u16));
+		let_assert!(Ok(current) = client.dump_control_table(ids[0], 0..148));
+
+		// The homing offset matches in both snapshots, so it is not written.
+		let_assert!(Ok(_) = client.write(ids[0], 20, &-10i32));
+		let_assert!(Ok(()) = client.restore_control_table_changes(ids[0], &snapshot, &current));
+		let_assert!(Ok(response) = client.read::<u16>(ids[0], 84));
+		assert!(response.data.to_le_bytes() == snapshot.data[84..86]);
+		let_assert!(Ok(response) = client.read::<i32>(ids[0], 20));
+		assert!(response.data == -10);
+		let_assert!(Ok(()) = client.restore_control_table_snapshot(ids[0], &snapshot));
+	})
+}
+
+#[test]
+fn test_restore_control_table_restores_torque_on_error() {
+	use dynamixel2::{ReadError, StatusError, TransferError};
+
+	run(|ids, mut client| {
+		let_assert!(Ok(mut snapshot) = client.dump_control_table(ids[0], 0..148));
+		// Operating mode 2 is not supported, so the motor rejects it after torque has been disabled.
+		snapshot.data[11] = 2;
+
+		let_assert!(Ok(_) = client.write(ids[0], 64, &1u8));
+		let_assert!(
+			Err(RestoreError::TransferError(TransferError::ReadError(ReadError::MotorError(e)))) =
+				client.restore_control_table_snapshot(ids[0], &snapshot)
+		);
+		assert!(e.status_error() == Some(StatusError::DataRangeError));
+		let_assert!(Ok(response) = client.read::<u8>(ids[0], 64));
+		assert!(response.data == 1);
+		let_assert!(Ok(_) = client.write(ids[0], 64, &0u8));
+	})
+}
+
+#[test]
+// Writes the read-only Hardware Error Status register, which only the mock allows.
+#[cfg(not(feature = "integration-tests"))]
+fn test_read_hardware_error() {
+	use dynamixel2::HardwareErrorStatus;
+
+	run(|ids, mut client| {
+		let_assert!(Ok(response) = client.read_hardware_error(ids[0]));
+		assert!(response.motor_id == ids[0]);
+		assert!(response.data.is_empty());
+
+		let_assert!(Ok(_) = client.write(ids[0], 70, &0x24u8));
+		let_assert!(Ok(response) = client.read_hardware_error(ids[0]));
+		assert!(response.data == HardwareErrorStatus::OVERHEATING | HardwareErrorStatus::OVERLOAD);
+	})
+}
+
+#[test]
+// Writes the read-only Present Position register, which only the mock allows.
+#[cfg(not(feature = "integration-tests"))]
+fn test_read_and_write_register() {
+	run(|ids, mut client| {
+		let_assert!(Ok(_) = client.write_register(ids[0], x_series::GOAL_POSITION, Angle::from_degrees(90.0)));
+		let_assert!(Ok(response) = client.read::<i32>(ids[0], 116));
+		assert!(response.data == 1024);
+
+		let_assert!(Ok(_) = client.write(ids[0], 132, &-2048i32));
+		let_assert!(Ok(response) = client.read_register(ids[0], x_series::PRESENT_POSITION));
+		assert!(response.motor_id == ids[0]);
+		assert!(response.data == Angle::from_radians(-std::f64::consts::PI));
+	})
+}
+
+#[test]
+// Relies on the model number and operating mode of the mock, and writes read-only registers, which only the mock allows.
+#[cfg(not(feature = "integration-tests"))]
+fn test_motor() {
+	use dynamixel2::client::OperatingMode;
+	use dynamixel2::units::{Current, Temperature};
+	use dynamixel2::MotorCommandError;
+
+	run(|ids, mut client| {
+		let_assert!(Ok(_) = client.write(ids[0], 146, &40u8));
+		let_assert!(Ok(_) = client.write(ids[0], 132, &-2048i32));
+
+		let_assert!(Ok(mut motor) = client.motor(ids[0]));
+		assert!(motor.model() == 5130);
+		assert!(motor.operating_mode() == OperatingMode::Current);
+		let_assert!(Err(MotorCommandError::WrongOperatingMode { .. }) = motor.set_goal_position(Angle::from_degrees(90.0)));
+		let_assert!(Err(MotorCommandError::UnsupportedCommand { .. }) = motor.set_goal_current(Current::from_amperes(0.1)));
+
+		let_assert!(Ok(_) = motor.enable_torque());
+		let_assert!(Ok(()) = motor.set_operating_mode(OperatingMode::Position));
+		let_assert!(Ok(_) = motor.set_goal_position(Angle::from_degrees(90.0)));
+		let_assert!(Err(MotorCommandError::WrongOperatingMode { .. }) = motor.set_goal_velocity(AngularVelocity::from_rpm(10.0)));
+
+		let_assert!(Ok(state) = motor.present_state());
+		assert!(state.data.position == Angle::from_radians(-std::f64::consts::PI));
+		assert!(state.data.temperature == Temperature::from_degrees_celsius(40.0));
+		assert!(state.data.current == None);
+
+		let_assert!(Ok(response) = client.read::<u8>(ids[0], 11));
+		assert!(response.data == 3);
+		let_assert!(Ok(response) = client.read::<u8>(ids[0], 64));
+		assert!(response.data == 1);
+		let_assert!(Ok(response) = client.read::<i32>(ids[0], 116));
+		assert!(response.data == 1024);
+	})
+}
+
+#[test]
+// Writes the read-only Present Position register, which only the mock allows.
+#[cfg(not(feature = "integration-tests"))]
+fn test_motor_group() {
+	use dynamixel2::control_table::x_series as registers;
+
+	run(|ids, mut client| {
+		let mut group = MotorGroup::<i32>::from_registers(ids, &registers::GOAL_POSITION, &registers::PRESENT_POSITION);
+		assert!(group.uses_sync_write());
+		assert!(group.uses_sync_read());
+		let_assert!(Ok(()) = group.write_goals(&mut client, &[100, -200]));
+		let_assert!(Ok(response) = client.read::<i32>(ids[1], 116));
+		assert!(response.data == -200);
+
+		let_assert!(Ok(_) = client.write(ids[0], 132, &10i32));
+		let_assert!(Ok(_) = client.write(ids[1], 132, &20i32));
+		let_assert!(Ok(states) = group.read_states(&mut client));
+		assert!(states == [10, 20]);
+
+		let mut group = MotorGroup::<i32>::new();
+		group.add_motor(ids[0], &registers::GOAL_POSITION, &registers::PRESENT_POSITION);
+		group.add_motor(ids[1], &registers::HOMING_OFFSET, &registers::MOVING_THRESHOLD);
+		assert!(!group.uses_sync_write());
+		assert!(!group.uses_sync_read());
+		let_assert!(Ok(()) = group.write_goals(&mut client, &[300, 400]));
+		let_assert!(Ok(response) = client.read::<i32>(ids[0], 116));
+		assert!(response.data == 300);
+		let_assert!(Ok(response) = client.read::<i32>(ids[1], 20));
+		assert!(response.data == 400);
+
+		let_assert!(Ok(_) = client.write(ids[1], 24, &30i32));
+		let_assert!(Ok(states) = group.read_states(&mut client));
+		assert!(states == [10, 30]);
+	})
+}
+
+#[test]
+fn test_response_timeouts() {
+	run(|ids, mut client| {
+		let_assert!(Ok(_) = client.write_timeout(ids[0], 116, &1234i32, Duration::from_millis(100)));
+		let_assert!(Ok(response) = client.read_timeout::<i32>(ids[0], 116, Duration::from_millis(100)));
+		assert!(response.data == 1234);
+
+		let reads = client.sync_read_timeout::<i32>(ids, 116, Duration::from_millis(100)).unwrap();
+		let values: Vec<_> = reads.map(|response| response.map(|response| response.data)).collect();
+		let_assert!([Ok(1234), Ok(_)] = values.as_slice());
+
+		// A motor that does not exist times out after the custom timeout.
+		let start = std::time::Instant::now();
+		let_assert!(Err(_) = client.read_timeout::<i32>(100, 116, Duration::from_millis(5)));
+		assert!(start.elapsed() < client.response_timeout_padding());
+	})
+}
+
+#[test]
+fn test_motor_response_timeout_padding() {
+	use dynamixel2::control_table::x_series as registers;
+
+	run(|ids, mut client| {
+		assert!(client.motor_response_timeout_padding(ids[0]) == client.response_timeout_padding());
+		client.set_motor_response_timeout_padding(ids[0], Some(Duration::from_millis(100)));
+		assert!(client.motor_response_timeout_padding(ids[0]) == Duration::from_millis(100));
+		assert!(client.motor_response_timeout_padding(ids[1]) == client.response_timeout_padding());
+		client.set_motor_response_timeout_padding(ids[0], None);
+		assert!(client.motor_response_timeout_padding(ids[0]) == client.response_timeout_padding());
+
+		let_assert!(Ok(padding) = client.calibrate_response_timeout_padding(ids[1], 5));
+		assert!(padding >= Duration::from_millis(1));
+		assert!(client.motor_response_timeout_padding(ids[1]) == padding);
+
+		// The cycle budget of a motor group includes the padding of each motor.
+		let group = MotorGroup::<i32>::from_registers(ids, &registers::GOAL_POSITION, &registers::PRESENT_POSITION);
+		let budget = group.cycle_budget(&client);
+		assert!(budget.timeout_padding == client.response_timeout_padding() + padding);
+
+		let_assert!(Ok(reads) = client.sync_read::<i32>(ids, 132));
+		assert!(reads.count() == 2);
+	})
+}
+
+#[test]
+// Writes the read-only Present Position register, which only the mock allows.
+#[cfg(not(feature = "integration-tests"))]
+fn test_control_loop() {
+	use dynamixel2::control_table::x_series as registers;
+
+	run(|ids, mut client| {
+		let group = MotorGroup::<i32>::from_registers(ids, &registers::GOAL_POSITION, &registers::PRESENT_POSITION);
+		let budget = group.cycle_budget(&client);
+		let mut control_loop = ControlLoop::new(&client, group, Duration::from_millis(50));
+		assert!(control_loop.budget() == budget);
+		assert!(budget.transfer_time() < control_loop.period());
+		assert!(budget.worst_case() > control_loop.period());
+
+		let_assert!(Ok(_) = client.write(ids[0], 132, &10i32));
+		let_assert!(Ok(_) = client.write(ids[1], 132, &20i32));
+		let result = control_loop.run(&mut client, |cycle, states, goals| {
+			if cycle.index >= 3 {
+				return ControlFlow::Break(cycle.index);
+			}
+			for (goal, state) in goals.iter_mut().zip(states) {
+				*goal = state + 1;
+			}
+			ControlFlow::Continue(())
+		});
+		let_assert!(Ok(index) = result);
+		let stats = control_loop.stats();
+		assert!(stats.cycles + stats.skipped_cycles == index);
+		assert!(stats.max_cycle_time >= stats.last_cycle_time);
+
+		let_assert!(Ok(response) = client.read::<i32>(ids[0], 116));
+		assert!(response.data == 11);
+		let_assert!(Ok(response) = client.read::<i32>(ids[1], 116));
+		assert!(response.data == 21);
+	})
+}
+
+#[test]
+fn test_control_loop_records_failed_cycles() {
+	use dynamixel2::control_table::x_series as registers;
+
+	run(|ids, mut client| {
+		// Motor 42 does not exist, so reading the states fails.
+		let group = MotorGroup::<i32>::from_registers(&[ids[0], 42], &registers::GOAL_POSITION, &registers::PRESENT_POSITION);
+		let mut control_loop = ControlLoop::new(&client, group, Duration::from_millis(50));
+		let_assert!(Err(_) = control_loop.step(&mut client, |_, _, _| ControlFlow::<()>::Continue(())));
+		let stats = control_loop.stats();
+		assert!(stats.cycles == 1);
+		assert!(stats.failed_cycles == 1);
+		assert!(stats.last_cycle_time > Duration::ZERO);
+	})
+}
+
+#[test]
+fn test_stream_trajectory() {
+	use dynamixel2::trajectory::{StreamOptions, StreamOutcome, Trajectory};
+	use std::sync::atomic::AtomicBool;
+
+	run(|ids, mut client| {
+		let start = [Angle::from_degrees(0.0), Angle::from_degrees(90.0)];
+		let end = [Angle::from_degrees(90.0), Angle::from_degrees(45.0)];
+		let trajectory = Trajectory::trapezoid(
+			&start,
+			&end,
+			AngularVelocity::from_rpm(60.0),
+			AngularAcceleration::from_radians_per_second_squared(100.0),
+		);
+		let options = StreamOptions::new(Duration::from_millis(20)).with_profile_registers(true);
+		let abort = AtomicBool::new(false);
+		let_assert!(Ok(StreamOutcome::Completed) = client.stream_trajectory(ids, &trajectory, &options, &abort));
+
+		let_assert!(Ok(response) = client.read::<i32>(ids[0], x_series::GOAL_POSITION.register.address));
+		assert!(response.data == 1024);
+		let_assert!(Ok(response) = client.read::<i32>(ids[1], x_series::GOAL_POSITION.register.address));
+		assert!(response.data == 512);
+		let_assert!(Ok(response) = client.read::<u32>(ids[0], x_series::PROFILE_VELOCITY.register.address));
+		assert!(response.data == x_series::PROFILE_VELOCITY.to_raw(AngularVelocity::from_rpm(60.0)));
+		let_assert!(Ok(response) = client.read::<u32>(ids[1], x_series::PROFILE_ACCELERATION.register.address));
+		assert!(response.data == x_series::PROFILE_ACCELERATION.to_raw(trajectory.peak_acceleration(1)));
+
+		let abort = AtomicBool::new(true);
+		let reversed = Trajectory::minimum_jerk(&end, &start, Duration::from_millis(100));
+		let options = StreamOptions::new(Duration::from_millis(20));
+		let_assert!(Ok(StreamOutcome::Aborted { at: None }) = client.stream_trajectory(ids, &reversed, &options, &abort));
+		let_assert!(Ok(response) = client.read::<i32>(ids[0], x_series::GOAL_POSITION.register.address));
+		assert!(response.data == 1024);
+	})
+}
+
+#[test]
+fn test_watchdog() {
+	use dynamixel2::control_table::x_series as registers;
+	use dynamixel2::WatchdogError;
+
+	run(|ids, mut client| {
+		for &id in ids {
+			let_assert!(Ok(_) = client.write(id, registers::TORQUE_ENABLE.address, &1u8));
+		}
+
+		// Timeouts that do not fit in the Bus Watchdog register are rejected.
+		assert!(let Err(WatchdogError::InvalidTimeout { .. }) = Watchdog::arm(&mut client, ids, Duration::ZERO));
+		assert!(let Err(WatchdogError::InvalidTimeout { .. }) = Watchdog::arm(&mut client, ids, Duration::from_secs(3)));
+
+		// Dropping an armed watchdog disables the torque.
+		{
+			let mut watchdog = Watchdog::arm(&mut client, ids, Duration::from_millis(90)).unwrap();
+			assert!(watchdog.timeout() == Duration::from_millis(100));
+			assert!(watchdog.is_armed());
+			let_assert!(Ok(()) = watchdog.feed());
+
+			let group = MotorGroup::<i32>::from_registers(ids, &registers::GOAL_POSITION, &registers::PRESENT_POSITION);
+			let mut control_loop = ControlLoop::new(watchdog.client(), group, Duration::from_millis(20));
+			let result = watchdog.step(&mut control_loop, |_, states, goals| {
+				goals.copy_from_slice(states);
+				ControlFlow::<()>::Continue(())
+			});
+			let_assert!(Ok(ControlFlow::Continue(())) = result);
+		}
+		for &id in ids {
+			let_assert!(Ok(response) = client.read::<u8>(id, registers::BUS_WATCHDOG.address));
+			assert!(response.data == 5);
+			let_assert!(Ok(response) = client.read::<u8>(id, registers::TORQUE_ENABLE.address));
+			assert!(response.data == 0);
+		}
+
+		// A safe stop reports the motors that acknowledged.
+		let mut watchdog = Watchdog::arm(&mut client, ids, Duration::from_millis(100)).unwrap();
+		let_assert!(Ok(report) = watchdog.safe_stop());
+		assert!(report == SafeStopReport { acknowledged: ids.to_vec(), unacknowledged: vec![] });
+		assert!(!watchdog.is_armed());
+		drop(watchdog);
+
+		// Disarming clears the watchdog and leaves the torque alone.
+		for &id in ids {
+			let_assert!(Ok(_) = client.write(id, registers::TORQUE_ENABLE.address, &1u8));
+		}
+		let watchdog = Watchdog::arm(&mut client, ids, Duration::from_millis(100)).unwrap();
+		let_assert!(Ok(()) = watchdog.disarm());
+		for &id in ids {
+			let_assert!(Ok(response) = client.read::<u8>(id, registers::BUS_WATCHDOG.address));
+			assert!(response.data == 0);
+			let_assert!(Ok(response) = client.read::<u8>(id, registers::TORQUE_ENABLE.address));
+			assert!(response.data == 1);
+		}
+	})
+}
+
+#[test]
+// Fakes sensor values and hardware errors by writing read-only registers, which only the mock allows.
+// Real motors reject those writes, and would have to actually overheat or lose power to trigger the events.
+#[cfg(not(feature = "integration-tests"))]
+fn test_health_monitor() {
+	use dynamixel2::client::{HealthEvent, HealthMonitor, HealthThresholds};
+	use dynamixel2::control_table::x_series as registers;
+	use dynamixel2::units::{Temperature, Voltage};
+	use dynamixel2::HardwareErrorStatus;
+
+	run(|ids, mut client| {
+		let make_healthy = |client: &mut dynamixel2::Client<_, _>| {
+			for &id in ids {
+				let_assert!(Ok(_) = client.write_register(id, x_series::PRESENT_INPUT_VOLTAGE, Voltage::from_volts(12.0)));
+				let_assert!(Ok(_) = client.write_register(id, x_series::PRESENT_TEMPERATURE, Temperature::from_degrees_celsius(40.0)));
+			}
+		};
+		make_healthy(&mut client);
+		let thresholds = HealthThresholds {
+			max_temperature: Temperature::from_degrees_celsius(70.0),
+			min_voltage: Voltage::from_volts(10.0),
+			max_voltage: Voltage::from_volts(14.0),
+		};
+		let mut monitor = HealthMonitor::new(ids, thresholds, Duration::from_secs(60));
+		let mut events = Vec::new();
+		let_assert!(Ok(true) = monitor.poll(&mut client, |event| events.push(event)));
+		let_assert!(Ok(false) = monitor.poll(&mut client, |event| events.push(event)));
+		assert!(events == []);
+		let_assert!(Some(health) = monitor.health(ids[0]));
+		assert!(health.reachable);
+		assert!(health.voltage == Voltage::from_volts(12.0));
+
+		// Conditions are reported once when they start.
+		let_assert!(Ok(_) = client.write(ids[0], registers::GOAL_POSITION.address, &1000i32));
+		let_assert!(Ok(()) = monitor.save_configuration(&mut client, ids[0], 64..147));
+		let_assert!(Ok(_) = client.write(ids[0], registers::HARDWARE_ERROR_STATUS.address, &HardwareErrorStatus::OVERHEATING.bits()));
+		let_assert!(Ok(_) = client.write_register(ids[0], x_series::PRESENT_TEMPERATURE, Temperature::from_degrees_celsius(75.0)));
+		let_assert!(Ok(_) = client.write_register(ids[1], x_series::PRESENT_INPUT_VOLTAGE, Voltage::from_volts(9.0)));
+		for _ in 0..2 {
+			let_assert!(Ok(()) = monitor.check(&mut client, |event| events.push(event)));
+		}
+		assert!(client.alerts().is_empty());
+		assert!(events == [
+			HealthEvent::Alert { motor_id: ids[0] },
+			HealthEvent::HardwareError {
+				motor_id: ids[0],
+				status: HardwareErrorStatus::OVERHEATING,
+			},
+			HealthEvent::Overheating {
+				motor_id: ids[0],
+				temperature: Temperature::from_degrees_celsius(75.0),
+			},
+			HealthEvent::VoltageOutOfRange {
+				motor_id: ids[1],
+				voltage: Voltage::from_volts(9.0),
+			},
+		]);
+
+		// Recovery reboots the motor and restores the saved configuration.
+		events.clear();
+		let_assert!(Ok(()) = monitor.recover(&mut client, ids[0], |event| events.push(event)));
+		assert!(events == [HealthEvent::Recovered { motor_id: ids[0] }]);
+		assert!(monitor.health(ids[0]) == None);
+		let_assert!(Ok(response) = client.read::<i32>(ids[0], registers::GOAL_POSITION.address));
+		assert!(response.data == 1000);
+		let_assert!(Ok(response) = client.read_hardware_error(ids[0]));
+		assert!(response.data == HardwareErrorStatus::default());
+		assert!(!response.alert);
+	})
+}
+
+#[test]
+fn test_reboot_and_restore() {
+	use dynamixel2::control_table::x_series as registers;
+
+	run(|ids, mut client| {
+		let_assert!(Ok(_) = client.write(ids[0], registers::TORQUE_ENABLE.address, &1u8));
+		let_assert!(Ok(_) = client.write(ids[0], registers::POSITION_P_GAIN.address, &900u16));
+		let_assert!(Ok(_) = client.write(ids[0], registers::GOAL_POSITION.address, &1234i32));
+		let_assert!(Ok(_) = client.write(ids[1], registers::GOAL_POSITION.address, &4321i32));
+
+		let_assert!(Ok(snapshot) = client.reboot_and_restore(ids[0], 64..208));
+		assert!(snapshot.address == 64);
+		let_assert!(Ok(response) = client.read::<u8>(ids[0], registers::TORQUE_ENABLE.address));
+		assert!(response.data == 1);
+		let_assert!(Ok(response) = client.read::<u16>(ids[0], registers::POSITION_P_GAIN.address));
+		assert!(response.data == 900);
+		let_assert!(Ok(response) = client.read::<i32>(ids[0], registers::GOAL_POSITION.address));
+		assert!(response.data == 1234);
+
+		// A plain reboot clears the RAM registers.
+		let_assert!(Ok(_) = client.reboot(ids[1]));
+		let_assert!(Ok(response) = client.read::<i32>(ids[1], registers::GOAL_POSITION.address));
+		assert!(response.data == 0);
+	})
+}
+
+#[test]
+// Fakes a tripped Bus Watchdog with a sync write of an invalid value, which only the mock accepts.
+#[cfg(not(feature = "integration-tests"))]
+fn test_reboot_and_restore_tripped_watchdog() {
+	use dynamixel2::control_table::x_series as registers;
+
+	run(|ids, mut client| {
+		let_assert!(Ok(_) = client.write(ids[0], registers::TORQUE_ENABLE.address, &1u8));
+		let_assert!(Err(_) = client.write(ids[0], registers::BUS_WATCHDOG.address, &0xFFu8));
+		let tripped = [SyncWriteData { motor_id: ids[0], data: 0xFFu8 }];
+		let_assert!(Ok(_) = client.sync_write(registers::BUS_WATCHDOG.address, tripped));
+
+		// The tripped watchdog is not written back, so restoring the torque enable is not aborted.
+		let_assert!(Ok(_) = client.reboot_and_restore(ids[0], 64..208));
+		let_assert!(Ok(response) = client.read::<u8>(ids[0], registers::TORQUE_ENABLE.address));
+		assert!(response.data == 1);
+		let_assert!(Ok(response) = client.read::<u8>(ids[0], registers::BUS_WATCHDOG.address));
+		assert!(response.data == 0);
+	})
+}
+
+#[test]
+// Writes indirect data mapped to read-only registers, which only the mock allows.
+#[cfg(not(feature = "integration-tests"))]
+fn test_write_indirect_mapping() {
+	use dynamixel2::control_table::{x_series as registers, IndirectMapping};
+
+	run(|ids, mut client| {
+		let_assert!(
+			Ok(layout) = IndirectMapping::new()
+				.register(registers::PRESENT_POSITION)
+				.register(registers::PRESENT_CURRENT)
+				.register(registers::HARDWARE_ERROR_STATUS)
+				.build()
+		);
+		let_assert!(Ok(_) = client.write_indirect_mapping(ids[0], &layout));
+		let_assert!(Ok(response) = client.read::<[u16; 7]>(ids[0], 168));
+		assert!(response.data == [132, 133, 134, 135, 126, 127, 70]);
+
+		let_assert!(Ok(_) = client.write_bytes(ids[0], layout.address(), &[0x00, 0x04, 0x00, 0x00, 0x0A, 0x00, 0x20]));
+		let_assert!(Ok(response) = client.read_bytes::<Vec<u8>>(ids[0], layout.address(), layout.size()));
+		assert!(let Some(Ok(1024)) = layout.get::<i32>(&response.data, &registers::PRESENT_POSITION));
+		assert!(let Some(Ok(10)) = layout.get::<i16>(&response.data, &registers::PRESENT_CURRENT));
+		assert!(let Some(Ok(0x20)) = layout.get::<u8>(&response.data, &registers::HARDWARE_ERROR_STATUS));
+	})
+}
+
+#[test]
+fn test_shared_client() {
+	run(|ids, client| {
+		let client = SharedClient::new(client);
+		let threads: Vec<_> = [Priority::Low, Priority::High]
+			.into_iter()
+			.map(|priority| {
+				let client = client.with_priority(priority);
+				let ids = ids.to_vec();
+				std::thread::spawn(move || {
+					for _ in 0..5 {
+						let mut client = client.lock();
+						let response = client.sync_read::<u32>(&ids, 132).unwrap();
+						for (r, id) in response.zip(&ids) {
+							let_assert!(Ok(r) = r, "id {id}");
+							assert!(r.motor_id == *id);
+						}
+					}
+				})
+			})
+			.collect();
+		for thread in threads {
+			thread.join().unwrap();
+		}
+	})
+}